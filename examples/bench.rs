@@ -0,0 +1,40 @@
+//! Runs the standard benchmark suite and prints one JSON line per scenario.
+//!
+//! ```text
+//! cargo run --release --example bench --features bench [-- ITERATIONS THREADS]
+//! cargo run --release --example bench --features bench-jemalloc
+//! cargo run --release --example bench --features bench-mimalloc
+//! ```
+
+use parex::bench::{Scenario, run};
+
+#[cfg(feature = "bench-jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+#[cfg(all(feature = "bench-mimalloc", not(feature = "bench-jemalloc")))]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+const ALLOCATOR: &str = if cfg!(feature = "bench-jemalloc") {
+    "jemalloc"
+} else if cfg!(feature = "bench-mimalloc") {
+    "mimalloc"
+} else {
+    "system"
+};
+
+fn main() -> Result<(), parex::ParexError> {
+    let mut args = std::env::args().skip(1);
+    let iterations = args.next().and_then(|a| a.parse().ok()).unwrap_or(10);
+    let threads = args.next().and_then(|a| a.parse().ok()).unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+    });
+
+    for scenario in Scenario::standard(threads) {
+        println!("{}", run(&scenario, iterations, ALLOCATOR)?.to_json());
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Aggregations computed from matches while the walk runs.
+//!
+//! Each aggregator is a [`Sink`](crate::Sink): pass it to
+//! [`SearchBuilder::sink`](crate::SearchBuilder::sink) behind an `Arc`, keep
+//! a clone, and read the totals once [`run()`](crate::SearchBuilder::run)
+//! returns. Nothing is collected per entry, so a report over millions of
+//! files costs a few counters.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::entry::{Entry, EntryKind};
+use crate::error::ParexError;
+use crate::traits::Sink;
+
+// ---------------------------------------------------------------------------
+// AgeBucket
+// ---------------------------------------------------------------------------
+
+/// A range of modification ages, measured back from the time the
+/// aggregation started.
+///
+/// Ordered from youngest to oldest. Files modified in the future (clock
+/// skew, archives extracted with preserved times) count as [`Today`](Self::Today).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AgeBucket {
+    /// Modified within the last 24 hours.
+    Today,
+
+    /// Within the last 7 days.
+    Week,
+
+    /// Within the last 30 days.
+    Month,
+
+    /// Within the last 365 days.
+    Year,
+
+    /// Longer ago than that.
+    Older,
+}
+
+impl AgeBucket {
+    /// Every bucket, youngest first.
+    pub const ALL: [AgeBucket; 5] = [
+        Self::Today,
+        Self::Week,
+        Self::Month,
+        Self::Year,
+        Self::Older,
+    ];
+
+    /// The bucket for a file last modified `age` ago.
+    pub fn for_age(age: Duration) -> Self {
+        const DAY: u64 = 24 * 60 * 60;
+
+        match age.as_secs() / DAY {
+            0 => Self::Today,
+            1..7 => Self::Week,
+            7..30 => Self::Month,
+            30..365 => Self::Year,
+            _ => Self::Older,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AgeBuckets
+// ---------------------------------------------------------------------------
+
+/// Counts matched files and their bytes by modification age.
+///
+/// Only regular files are counted. Size and mtime come from
+/// [`Entry::metadata`] when the source cached it, otherwise from a `stat()`
+/// of the entry; a file that cannot be stat'ed is reported as a recoverable
+/// error and left out of the totals.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::aggregate::AgeBuckets;
+/// use std::sync::Arc;
+/// # use parex::{Source, Entry, ParexError};
+/// # use parex::engine::WalkConfig;
+/// # struct Files;
+/// # impl Source for Files {
+/// #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+/// #         Box::new(std::iter::empty())
+/// #     }
+/// # }
+///
+/// let ages = Arc::new(AgeBuckets::new());
+/// parex::search().source(Files).sink(Arc::clone(&ages)).run()?;
+///
+/// for (bucket, totals) in ages.report().iter() {
+///     println!("{bucket:?}: {} files, {} bytes", totals.files, totals.bytes);
+/// }
+/// # Ok::<(), ParexError>(())
+/// ```
+#[derive(Debug)]
+pub struct AgeBuckets {
+    now: SystemTime,
+    files: [AtomicU64; 5],
+    bytes: [AtomicU64; 5],
+}
+
+impl Default for AgeBuckets {
+    fn default() -> Self {
+        Self::as_of(SystemTime::now())
+    }
+}
+
+impl AgeBuckets {
+    /// Measure ages from the current time.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Measure ages from `now` instead of the current time, for reproducible
+    /// reports.
+    pub fn as_of(now: SystemTime) -> Self {
+        Self {
+            now,
+            files: Default::default(),
+            bytes: Default::default(),
+        }
+    }
+
+    /// Add one file of `len` bytes last modified at `modified`.
+    pub fn record(&self, modified: SystemTime, len: u64) {
+        let age = self.now.duration_since(modified).unwrap_or_default();
+        let i = AgeBucket::for_age(age) as usize;
+        self.files[i].fetch_add(1, Ordering::Relaxed);
+        self.bytes[i].fetch_add(len, Ordering::Relaxed);
+    }
+
+    /// The totals recorded so far.
+    pub fn report(&self) -> AgeReport {
+        AgeReport {
+            totals: std::array::from_fn(|i| BucketTotals {
+                files: self.files[i].load(Ordering::Relaxed),
+                bytes: self.bytes[i].load(Ordering::Relaxed),
+            }),
+        }
+    }
+}
+
+impl Sink for AgeBuckets {
+    fn accept(&self, entry: &Entry) -> Result<(), ParexError> {
+        if entry.kind != EntryKind::File {
+            return Ok(());
+        }
+
+        let io_err = |source| ParexError::Io {
+            path: entry.path.clone(),
+            source,
+        };
+        let stat;
+        let meta = match &entry.metadata {
+            Some(m) => m,
+            None => {
+                stat = std::fs::metadata(&entry.path).map_err(io_err)?;
+                &stat
+            }
+        };
+
+        self.record(meta.modified().map_err(io_err)?, meta.len());
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AgeReport
+// ---------------------------------------------------------------------------
+
+/// File and byte totals for one [`AgeBucket`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BucketTotals {
+    /// Number of files.
+    pub files: u64,
+
+    /// Sum of their apparent sizes.
+    pub bytes: u64,
+}
+
+/// A point-in-time copy of the totals in an [`AgeBuckets`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgeReport {
+    totals: [BucketTotals; 5],
+}
+
+impl AgeReport {
+    /// The totals for `bucket`.
+    pub fn get(&self, bucket: AgeBucket) -> BucketTotals {
+        self.totals[bucket as usize]
+    }
+
+    /// Every bucket with its totals, youngest first.
+    pub fn iter(&self) -> impl Iterator<Item = (AgeBucket, BucketTotals)> + '_ {
+        AgeBucket::ALL.into_iter().zip(self.totals.iter().copied())
+    }
+
+    /// Totals across all buckets.
+    pub fn total(&self) -> BucketTotals {
+        self.totals
+            .iter()
+            .fold(BucketTotals::default(), |acc, t| BucketTotals {
+                files: acc.files + t.files,
+                bytes: acc.bytes + t.bytes,
+            })
+    }
+}
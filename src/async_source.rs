@@ -0,0 +1,140 @@
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{Thread, ThreadId};
+use std::time::Duration;
+
+use futures_core::Stream;
+
+use crate::engine::{CancelToken, WalkConfig};
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::traits::Source;
+
+/// A stream of entries, as produced by an [`AsyncSource`].
+pub type EntryStream = Pin<Box<dyn Stream<Item = Result<Entry, ParexError>>>>;
+
+/// How often a walk waiting on a pending stream rechecks for cancellation.
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+// ---------------------------------------------------------------------------
+// AsyncSource
+// ---------------------------------------------------------------------------
+
+/// A [`Source`] whose entries arrive as a [`Stream`] — object store
+/// listings, database cursors, paginated APIs.
+///
+/// Pass one to [`SearchBuilder::async_source`](crate::SearchBuilder::async_source).
+/// The engine polls the stream on the thread consuming the walk and parks
+/// that thread while it is pending, so no async runtime is needed and any
+/// executor-agnostic stream works. Streams tied to a runtime's reactor —
+/// tokio sockets, for instance — must be driven by that runtime: spawn the
+/// listing there and hand its entries over through a channel whose
+/// receiving end is the stream.
+///
+/// Everything [`Source`] says about errors, depth, and `config` applies.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::{AsyncSource, Entry, EntryKind, ParexError};
+/// use parex::engine::WalkConfig;
+/// use parex::EntryStream;
+///
+/// /// Keys of an object store bucket.
+/// struct Bucket(Vec<String>);
+///
+/// impl AsyncSource for Bucket {
+///     fn stream(&self, _config: &WalkConfig) -> EntryStream {
+///         let keys = self.0.clone();
+///         Box::pin(futures::stream::iter(keys.into_iter().map(|key| {
+///             Ok(Entry::new(key, EntryKind::File, 1))
+///         })))
+///     }
+/// }
+///
+/// let bucket = Bucket(vec!["2024/invoice.pdf".into(), "2024/photo.jpg".into()]);
+/// let results = parex::search().async_source(bucket).matching("invoice").run()?;
+/// assert_eq!(results.matches, 1);
+/// # Ok::<(), ParexError>(())
+/// ```
+pub trait AsyncSource: Send + Sync {
+    /// Start listing, yielding entries as they become available.
+    fn stream(&self, config: &WalkConfig) -> EntryStream;
+
+    /// The directory the listing starts from, if it has one. See
+    /// [`Source::root`].
+    fn root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Blocking adapter
+// ---------------------------------------------------------------------------
+
+/// Runs an [`AsyncSource`] as a [`Source`].
+pub(crate) struct Blocking<S>(pub(crate) S);
+
+impl<S: AsyncSource> Source for Blocking<S> {
+    fn walk(&self, config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+        Box::new(BlockOn {
+            stream: self.0.stream(config),
+            cancel: config.cancel_token().clone(),
+            waker: None,
+        })
+    }
+
+    fn root(&self) -> Option<&Path> {
+        self.0.root()
+    }
+}
+
+/// Wakes a parked thread.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls a stream to completion, parking between wake-ups.
+struct BlockOn {
+    stream: EntryStream,
+    cancel: CancelToken,
+    /// Unparks the thread that polled last; remade if another one polls.
+    waker: Option<(ThreadId, Waker)>,
+}
+
+impl Iterator for BlockOn {
+    type Item = Result<Entry, ParexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = std::thread::current();
+        let waker = match &self.waker {
+            Some((id, waker)) if *id == current.id() => waker.clone(),
+            _ => {
+                let waker = Waker::from(Arc::new(Unpark(current.clone())));
+                self.waker = Some((current.id(), waker.clone()));
+                waker
+            }
+        };
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match self.stream.as_mut().poll_next(&mut cx) {
+                Poll::Ready(item) => return item,
+                // Timed, so cancellation is noticed even if the stream never wakes us
+                Poll::Pending if !self.cancel.is_cancelled() => {
+                    std::thread::park_timeout(CANCEL_POLL);
+                }
+                Poll::Pending => return None,
+            }
+        }
+    }
+}
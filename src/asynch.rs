@@ -0,0 +1,181 @@
+//! Searching from async code on a Tokio runtime.
+//!
+//! [`SearchBuilder::run_async`](crate::SearchBuilder::run_async) starts the
+//! walk on the runtime's blocking pool and returns an [`AsyncSearch`]: a
+//! [`Stream`] of matched entries that ends with the search's [`Results`].
+//! Sources that are themselves async — object store listings, database
+//! cursors — implement [`AsyncSource`].
+//!
+//! Without Tokio, [`MatchStream`](crate::MatchStream) (feature `futures`)
+//! offers the same stream on a dedicated thread.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use crate::engine::{CancelToken, EngineOptions, execute};
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::results::Results;
+
+pub use crate::async_source::{AsyncSource, EntryStream};
+
+// ---------------------------------------------------------------------------
+// AsyncSearch
+// ---------------------------------------------------------------------------
+
+/// An async [`Stream`] of matched entries, followed by the search's
+/// [`Results`].
+///
+/// Created via [`SearchBuilder::run_async()`](crate::SearchBuilder::run_async).
+/// The walk runs on the Tokio runtime's blocking pool, so it never stalls
+/// the runtime's async workers, and each match wakes the task polling the
+/// stream. Results are unordered, as with [`run()`](crate::SearchBuilder::run).
+///
+/// With [`EngineTuning::stream_capacity`](crate::engine::EngineTuning::stream_capacity)
+/// set, the walk pauses once that many matches are waiting, so a slow
+/// consumer applies backpressure to it.
+///
+/// Once the stream has ended, [`finish()`](Self::finish) returns the
+/// search's [`Results`]. Dropping the search before then cancels the walk.
+///
+/// # Example
+///
+/// ```rust
+/// # use parex::{Source, Entry, EntryKind, ParexError};
+/// # use parex::engine::WalkConfig;
+/// # struct Files(Vec<&'static str>);
+/// # impl Source for Files {
+/// #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+/// #         let v: Vec<_> = self.0.iter().map(|n| Ok(Entry::new(n, EntryKind::File, 1))).collect();
+/// #         Box::new(v.into_iter())
+/// #     }
+/// # }
+/// use futures::StreamExt;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut search = parex::search()
+///     .source(Files(vec!["a.log", "b.txt", "c.log"]))
+///     .matching(".log")
+///     .run_async()?;
+///
+/// while let Some(entry) = search.next().await {
+///     println!("{}", entry.path.display());
+/// }
+/// let results = search.finish().await;
+/// assert_eq!(results.matches, 2);
+/// # Ok::<(), ParexError>(())
+/// # }).unwrap();
+/// ```
+pub struct AsyncSearch {
+    rx: Option<Rx>,
+    worker: Option<JoinHandle<Results>>,
+    cancel: CancelToken,
+}
+
+/// The sending half, bounded when `stream_capacity` is set.
+enum Tx {
+    Unbounded(UnboundedSender<Entry>),
+    Bounded(Sender<Entry>),
+}
+
+impl Tx {
+    /// Send a match. A search that has gone away is ignored — the walk
+    /// still counts the match, and stops on cancellation by itself.
+    fn send(&self, entry: Entry) {
+        match self {
+            Self::Unbounded(tx) => {
+                let _ = tx.send(entry);
+            }
+            Self::Bounded(tx) => {
+                let _ = tx.blocking_send(entry);
+            }
+        }
+    }
+}
+
+/// The receiving half matching [`Tx`].
+enum Rx {
+    Unbounded(UnboundedReceiver<Entry>),
+    Bounded(Receiver<Entry>),
+}
+
+impl AsyncSearch {
+    /// Start walking on the current runtime's blocking pool.
+    pub(crate) fn spawn(opts: EngineOptions) -> Result<Self, ParexError> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(|e| {
+            ParexError::ThreadPool(format!("run_async() needs a Tokio runtime: {e}"))
+        })?;
+        let cancel = opts.config.cancel.clone();
+        let (tx, rx) = match opts.tuning.stream_capacity {
+            // A zero-capacity channel cannot be created
+            Some(cap) => {
+                let (tx, rx) = mpsc::channel(cap.max(1));
+                (Tx::Bounded(tx), Rx::Bounded(rx))
+            }
+            None => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                (Tx::Unbounded(tx), Rx::Unbounded(rx))
+            }
+        };
+
+        let worker = runtime.spawn_blocking(move || execute(opts, |entry, _| tx.send(entry)));
+
+        Ok(Self {
+            rx: Some(rx),
+            worker: Some(worker),
+            cancel,
+        })
+    }
+
+    /// Stop the search early, keeping any matches already sent.
+    ///
+    /// The stream yields what was found so far and then ends.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Wait for the walk to end and return its [`Results`] — counts,
+    /// stats, errors, and how it completed. `paths` is empty; matches not
+    /// yet taken from the stream are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Resumes a panic raised by the source or matcher during the walk.
+    pub async fn finish(mut self) -> Results {
+        // Unblocks a walk waiting for room in a bounded channel
+        drop(self.rx.take());
+        match self.worker.take().expect("joined once").await {
+            Ok(results) => results,
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Err(err) => panic!("search worker did not complete: {err}"),
+        }
+    }
+}
+
+impl Stream for AsyncSearch {
+    type Item = Entry;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Entry>> {
+        // `None` once the walk drops its sender and the channel is drained
+        match &mut self.get_mut().rx {
+            Some(Rx::Unbounded(rx)) => rx.poll_recv(cx),
+            Some(Rx::Bounded(rx)) => rx.poll_recv(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for AsyncSearch {
+    fn drop(&mut self) {
+        // Not awaited — the walk may be blocked inside the source. A
+        // search already over leaves the token alone, since the caller may
+        // share it with other searches.
+        if self.worker.as_ref().is_some_and(|w| !w.is_finished()) {
+            self.cancel();
+        }
+    }
+}
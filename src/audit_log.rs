@@ -0,0 +1,238 @@
+//! A trail of executed searches, for environments that must account for
+//! who looked for what.
+//!
+//! Set an observer with [`SearchBuilder::on_audit`](crate::SearchBuilder::on_audit)
+//! and every search built with it reports one [`AuditRecord`] when it ends —
+//! finished, cancelled, or failed — carrying the query, the
+//! [`requester`](crate::SearchBuilder::requester) label, the runtime, and
+//! the result counts. Nothing about the matched paths themselves is
+//! recorded, so the trail can be kept longer than the results.
+//!
+//! [`AuditRecord::to_json`] renders a record as one line of JSON, and
+//! [`json_lines`] turns any writer into an observer that appends them:
+//!
+//! ```rust,no_run
+//! use std::fs::OpenOptions;
+//!
+//! let log = OpenOptions::new().create(true).append(true).open("searches.jsonl")?;
+//! # struct Files;
+//! # impl parex::Source for Files {
+//! #     fn walk(&self, _: &parex::engine::WalkConfig)
+//! #         -> Box<dyn Iterator<Item = Result<parex::Entry, parex::ParexError>>> {
+//! #         Box::new(std::iter::empty())
+//! #     }
+//! # }
+//! let results = parex::search()
+//!     .source(Files)
+//!     .matching("invoice")
+//!     .requester("billing-service")
+//!     .on_audit(parex::audit_log::json_lines(log))
+//!     .run()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::ErrorCode;
+use crate::notify::Notifiers;
+use crate::plan::Plan;
+use crate::results::{Completion, Results};
+
+// ---------------------------------------------------------------------------
+// AuditRecord
+// ---------------------------------------------------------------------------
+
+/// One executed search, as reported to an
+/// [`on_audit`](crate::SearchBuilder::on_audit) observer and in
+/// [notifications](crate::notify).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// Who asked, as set with [`requester`](crate::SearchBuilder::requester).
+    pub requester: Option<String>,
+
+    /// When the search started.
+    pub started: SystemTime,
+
+    /// Wall-clock time from start to end, including any
+    /// [`debounce`](crate::SearchBuilder::debounce) delay.
+    pub elapsed: Duration,
+
+    /// The pattern given to [`matching`](crate::SearchBuilder::matching), if
+    /// that is how the matcher was set. Custom matchers appear by type name
+    /// in `plan`.
+    pub pattern: Option<String>,
+
+    /// The rest of the query: source, matcher and filters, limits.
+    pub plan: Plan,
+
+    /// Entries that matched.
+    pub matches: usize,
+
+    /// Files visited.
+    pub files: usize,
+
+    /// Directories visited.
+    pub dirs: usize,
+
+    /// Recoverable errors collected, if
+    /// [`collect_errors`](crate::SearchBuilder::collect_errors) was set.
+    pub errors: usize,
+
+    /// How the search ended.
+    pub outcome: Outcome,
+}
+
+/// How an audited search ended. See [`Completion`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Outcome {
+    /// Ran to the end or to its match limit.
+    Finished,
+
+    /// Cancelled before the end.
+    Cancelled,
+
+    /// Halted by a fatal error of this category.
+    Failed(ErrorCode),
+}
+
+impl AuditRecord {
+    /// The record as a single-line JSON object. Times are milliseconds
+    /// since the Unix epoch; durations are in nanoseconds.
+    ///
+    /// Strings are fully escaped, so a requester label or pattern cannot
+    /// break the line or forge another record.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        let started_ms = self
+            .started
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let _ = write!(
+            out,
+            "\"requester\":{},\"started_ms\":{started_ms},\"elapsed_ns\":{},\
+             \"source\":{},\"matcher\":{},\"pattern\":{},\"predicates\":[",
+            json_opt(self.requester.as_deref()),
+            self.elapsed.as_nanos(),
+            json_opt(self.plan.source),
+            json_str(self.plan.matcher),
+            json_opt(self.pattern.as_deref()),
+        );
+        for (i, p) in self.plan.predicates.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&json_str(p));
+        }
+        let _ = write!(
+            out,
+            "],\"threads\":{},\"max_depth\":{},\"limit\":{},\"excluded_paths\":{},\
+             \"matches\":{},\"files\":{},\"dirs\":{},\"errors\":{},\"outcome\":{}",
+            self.plan.threads,
+            json_num(self.plan.max_depth),
+            json_num(self.plan.limit),
+            self.plan.excluded_paths,
+            self.matches,
+            self.files,
+            self.dirs,
+            self.errors,
+            match self.outcome {
+                Outcome::Finished => "\"finished\"".into(),
+                Outcome::Cancelled => "\"cancelled\"".into(),
+                Outcome::Failed(code) => format!("\"failed\",\"error\":\"{code}\""),
+            },
+        );
+        out.push('}');
+        out
+    }
+}
+
+pub(crate) fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_opt(s: Option<&str>) -> String {
+    s.map_or_else(|| "null".into(), json_str)
+}
+
+fn json_num(n: Option<usize>) -> String {
+    n.map_or_else(|| "null".into(), |n| n.to_string())
+}
+
+/// An observer that appends each record to `out` as a line of JSON,
+/// flushing after every record.
+///
+/// Records from concurrent searches sharing the observer never interleave.
+/// A failed write is dropped; to handle it, write your own observer around
+/// [`AuditRecord::to_json`].
+pub fn json_lines(out: impl Write + Send + 'static) -> impl Fn(&AuditRecord) + Send + Sync {
+    let out = Mutex::new(out);
+    move |record| {
+        let mut line = record.to_json();
+        line.push('\n');
+        let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = out.write_all(line.as_bytes()).and_then(|()| out.flush());
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Audit
+// ---------------------------------------------------------------------------
+
+pub(crate) type AuditObserver = Box<dyn Fn(&AuditRecord) + Send + Sync>;
+
+/// What the builder knows about a search to be audited or notified about;
+/// completed by the engine once the search ends.
+pub(crate) struct Audit {
+    pub(crate) requester: Option<String>,
+    pub(crate) pattern: Option<String>,
+    pub(crate) plan: Plan,
+    pub(crate) observer: Option<AuditObserver>,
+    pub(crate) notifiers: Notifiers,
+}
+
+impl Audit {
+    /// Report the search that started at `started` and produced `results`.
+    pub(crate) fn finish(self, started: SystemTime, elapsed: Duration, results: &Results) {
+        let outcome = match &results.completion {
+            Completion::Finished => Outcome::Finished,
+            Completion::Cancelled => Outcome::Cancelled,
+            Completion::Failed(e) => Outcome::Failed(e.code()),
+        };
+        let record = AuditRecord {
+            requester: self.requester,
+            started,
+            elapsed,
+            pattern: self.pattern,
+            plan: self.plan,
+            matches: results.matches,
+            files: results.stats.files,
+            dirs: results.stats.dirs,
+            errors: results.errors.len(),
+            outcome,
+        };
+        if let Some(observer) = &self.observer {
+            observer(&record);
+        }
+        self.notifiers.fire(&record, &results.violations);
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use crate::entry::Entry;
+
+/// A callback that receives matches in batches.
+pub(crate) type BatchCallback = Box<dyn FnMut(&[Entry]) + Send>;
+
+// ---------------------------------------------------------------------------
+// MatchBatches
+// ---------------------------------------------------------------------------
+
+/// Coalesces matches for an
+/// [`on_matches`](crate::SearchBuilder::on_matches) callback.
+///
+/// Lives on the consuming thread. Entries are held until their batch is
+/// handed to the callback, then passed on by value, so batching costs no
+/// clones — only the delay until the batch fills.
+pub(crate) struct MatchBatches {
+    callback: BatchCallback,
+    size: usize,
+    interval: Duration,
+    batch: Vec<Entry>,
+    /// The scores of the entries in `batch`, in the same order.
+    scores: Vec<Option<f32>>,
+    /// When the first entry of the current batch arrived.
+    opened: Option<Instant>,
+}
+
+impl MatchBatches {
+    pub(crate) fn new(size: usize, interval: Duration, callback: BatchCallback) -> Self {
+        let size = size.max(1);
+        Self {
+            callback,
+            size,
+            interval,
+            batch: Vec::with_capacity(size),
+            scores: Vec::with_capacity(size),
+            opened: None,
+        }
+    }
+
+    /// Add a match, flushing the batch once it is full or has been open
+    /// for the interval.
+    pub(crate) fn push(
+        &mut self,
+        entry: Entry,
+        score: Option<f32>,
+        forward: &mut impl FnMut(Entry, Option<f32>),
+    ) {
+        let opened = *self.opened.get_or_insert_with(Instant::now);
+        self.batch.push(entry);
+        self.scores.push(score);
+        if self.batch.len() >= self.size || opened.elapsed() >= self.interval {
+            self.flush(forward);
+        }
+    }
+
+    /// Hand the pending matches to the callback, then to `forward`.
+    pub(crate) fn flush(&mut self, forward: &mut impl FnMut(Entry, Option<f32>)) {
+        if self.batch.is_empty() {
+            return;
+        }
+        (self.callback)(&self.batch);
+        self.opened = None;
+        self.batch
+            .drain(..)
+            .zip(self.scores.drain(..))
+            .for_each(|(entry, score)| forward(entry, score));
+    }
+}
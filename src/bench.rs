@@ -0,0 +1,365 @@
+//! A standardized benchmark harness for the engine.
+//!
+//! Enabled with the `bench` feature. The harness scans synthetic trees that
+//! exist only in memory, so timings measure the engine — batching, matching,
+//! the worker pool, result collection — rather than a particular disk. Every
+//! [`Measurement`] renders as one line of JSON, so reports from different
+//! releases, machines, or allocators can be diffed and plotted by script.
+//!
+//! The global allocator belongs to the final binary, not to parex. The
+//! `bench` example selects jemalloc or mimalloc through the `bench-jemalloc`
+//! and `bench-mimalloc` features and passes its name to [`run`] as a label:
+//!
+//! ```text
+//! cargo run --release --example bench --features bench-mimalloc
+//! ```
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::engine::WalkConfig;
+use crate::entry::{Entry, EntryKind};
+use crate::error::ParexError;
+use crate::plan::Cost;
+use crate::traits::{Matcher, Source};
+
+// ---------------------------------------------------------------------------
+// SyntheticTree
+// ---------------------------------------------------------------------------
+
+/// An in-memory directory tree of a fixed shape.
+///
+/// Every directory down to `depth` holds `dirs_per_dir` subdirectories and
+/// `files_per_dir` files named `file{n}.{ext}`, with extensions cycling
+/// through `rs`, `txt`, `log`, and `json`. Entries are generated lazily, one
+/// directory listing at a time, so even very large trees cost no memory up
+/// front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntheticTree {
+    /// Levels of subdirectories below the root.
+    pub depth: usize,
+
+    /// Subdirectories in each directory above the deepest level.
+    pub dirs_per_dir: usize,
+
+    /// Files in each directory.
+    pub files_per_dir: usize,
+}
+
+impl SyntheticTree {
+    /// Total entries the tree yields, excluding the root itself.
+    pub fn len(&self) -> usize {
+        // Directories at each level: 1, d, d², … d^depth
+        let dirs: usize = (0..=self.depth)
+            .map(|l| self.dirs_per_dir.pow(l as u32))
+            .sum();
+        (dirs - 1) + dirs * self.files_per_dir
+    }
+
+    /// Whether the tree yields no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Source for SyntheticTree {
+    fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+        Box::new(TreeIter {
+            tree: *self,
+            stack: vec![(PathBuf::from("/synthetic"), 0)],
+            pending: Vec::new(),
+        })
+    }
+}
+
+struct TreeIter {
+    tree: SyntheticTree,
+    /// Directories still to list, with their depth.
+    stack: Vec<(PathBuf, usize)>,
+    /// Entries of the current directory not yet yielded, in reverse.
+    pending: Vec<Entry>,
+}
+
+impl Iterator for TreeIter {
+    type Item = Result<Entry, ParexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        const EXTENSIONS: [&str; 4] = ["rs", "txt", "log", "json"];
+
+        loop {
+            if let Some(entry) = self.pending.pop() {
+                return Some(Ok(entry));
+            }
+
+            let (dir, depth) = self.stack.pop()?;
+            let child_depth = depth + 1;
+            for n in (0..self.tree.files_per_dir).rev() {
+                let ext = EXTENSIONS[n % EXTENSIONS.len()];
+                self.pending.push(Entry {
+                    path: dir.join(format!("file{n}.{ext}")),
+                    kind: EntryKind::File,
+                    depth: child_depth,
+                    metadata: None,
+                    tags: None,
+                    source_id: None,
+                    id: None,
+                });
+            }
+            if depth < self.tree.depth {
+                for n in (0..self.tree.dirs_per_dir).rev() {
+                    let path = dir.join(format!("dir{n}"));
+                    self.stack.push((path.clone(), child_depth));
+                    self.pending.push(Entry {
+                        path,
+                        kind: EntryKind::Dir,
+                        depth: child_depth,
+                        metadata: None,
+                        tags: None,
+                        source_id: None,
+                        id: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scenario
+// ---------------------------------------------------------------------------
+
+/// How a scenario decides what matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Workload {
+    /// `.matching(pattern)` — a name check on the consuming thread.
+    Substring(String),
+
+    /// A matcher declaring [`Cost::Metadata`] that hashes each path
+    /// `rounds` times, standing in for per-entry work heavy enough to run
+    /// on the engine's worker pool.
+    Hashing { rounds: u32 },
+}
+
+/// One standardized search to time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scenario {
+    /// Stable identifier used in reports.
+    pub name: &'static str,
+
+    /// The tree to scan.
+    pub tree: SyntheticTree,
+
+    /// What to match.
+    pub workload: Workload,
+
+    /// Threads for the search.
+    pub threads: usize,
+
+    /// Collect matched paths into `Results::paths`.
+    pub collect_paths: bool,
+}
+
+impl Scenario {
+    /// The standard suite: wide and deep trees, name and heavy matchers,
+    /// single- and multi-threaded. Names and shapes are stable across
+    /// releases so reports remain comparable.
+    pub fn standard(threads: usize) -> Vec<Scenario> {
+        let wide = SyntheticTree {
+            depth: 2,
+            dirs_per_dir: 40,
+            files_per_dir: 60,
+        };
+        let deep = SyntheticTree {
+            depth: 8,
+            dirs_per_dir: 3,
+            files_per_dir: 10,
+        };
+        let substring = || Workload::Substring("file1".into());
+
+        vec![
+            Scenario {
+                name: "wide-substring",
+                tree: wide,
+                workload: substring(),
+                threads,
+                collect_paths: false,
+            },
+            Scenario {
+                name: "wide-substring-collect",
+                tree: wide,
+                workload: substring(),
+                threads,
+                collect_paths: true,
+            },
+            Scenario {
+                name: "deep-substring",
+                tree: deep,
+                workload: substring(),
+                threads,
+                collect_paths: false,
+            },
+            Scenario {
+                name: "wide-hashing-1t",
+                tree: wide,
+                workload: Workload::Hashing { rounds: 64 },
+                threads: 1,
+                collect_paths: false,
+            },
+            Scenario {
+                name: "wide-hashing",
+                tree: wide,
+                workload: Workload::Hashing { rounds: 64 },
+                threads,
+                collect_paths: false,
+            },
+        ]
+    }
+}
+
+/// Burns CPU proportional to `rounds` so the search exercises the worker pool.
+struct Hashing(u32);
+
+impl Matcher for Hashing {
+    fn is_match(&self, entry: &Entry) -> bool {
+        let bytes = entry.path.as_os_str().as_encoded_bytes();
+        let mut h = 0xcbf2_9ce4_8422_2325u64;
+        for _ in 0..self.0 {
+            for &b in bytes {
+                h = (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        h >> 62 == 0
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+/// Timings for one scenario over several iterations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    /// The scenario's name.
+    pub scenario: &'static str,
+
+    /// Label of the global allocator the harness was built with.
+    pub allocator: String,
+
+    /// parex version the measurement was taken with.
+    pub version: &'static str,
+
+    /// Threads used.
+    pub threads: usize,
+
+    /// Entries visited per iteration.
+    pub entries: usize,
+
+    /// Matches per iteration.
+    pub matches: usize,
+
+    /// Timed iterations, after one untimed warm-up.
+    pub iterations: usize,
+
+    /// Fastest iteration.
+    pub min: Duration,
+
+    /// Median iteration.
+    pub median: Duration,
+
+    /// Mean iteration.
+    pub mean: Duration,
+}
+
+impl Measurement {
+    /// Entries per second at the median time.
+    pub fn entries_per_sec(&self) -> f64 {
+        let secs = self.median.as_secs_f64();
+        if secs > 0.0 {
+            self.entries as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// The measurement as a single-line JSON object. Durations are in
+    /// nanoseconds.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        let _ = write!(
+            out,
+            "\"scenario\":\"{}\",\"allocator\":\"{}\",\"version\":\"{}\",\
+             \"threads\":{},\"entries\":{},\"matches\":{},\"iterations\":{},\
+             \"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"entries_per_sec\":{:.0}",
+            escape(self.scenario),
+            escape(&self.allocator),
+            self.version,
+            self.threads,
+            self.entries,
+            self.matches,
+            self.iterations,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos(),
+            self.entries_per_sec(),
+        );
+        out.push('}');
+        out
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Run `scenario` once untimed, then `iterations` timed runs.
+///
+/// `allocator` is recorded verbatim in the measurement.
+///
+/// # Errors
+///
+/// Returns `Err` if the search cannot be configured.
+pub fn run(
+    scenario: &Scenario,
+    iterations: usize,
+    allocator: &str,
+) -> Result<Measurement, ParexError> {
+    let iterations = iterations.max(1);
+    let once = || {
+        let builder = crate::search()
+            .source(scenario.tree)
+            .threads(scenario.threads)
+            .collect_paths(scenario.collect_paths);
+        let builder = match &scenario.workload {
+            Workload::Substring(p) => builder.matching(p.as_str()),
+            Workload::Hashing { rounds } => builder.with_matcher(Hashing(*rounds)),
+        };
+        let start = Instant::now();
+        let results = builder.run()?;
+        Ok::<_, ParexError>((start.elapsed(), results.matches))
+    };
+
+    let (_, matches) = once()?;
+    let mut times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        times.push(once()?.0);
+    }
+    times.sort();
+
+    Ok(Measurement {
+        scenario: scenario.name,
+        allocator: allocator.to_owned(),
+        version: env!("CARGO_PKG_VERSION"),
+        threads: scenario.threads,
+        entries: scenario.tree.len(),
+        matches,
+        iterations,
+        min: times[0],
+        median: times[iterations / 2],
+        mean: times.iter().sum::<Duration>() / iterations as u32,
+    })
+}
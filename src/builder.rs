@@ -1,9 +1,34 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::engine::{EngineOptions, WalkConfig, run};
+use crate::audit_log::{Audit, AuditObserver, AuditRecord};
+use crate::batch::MatchBatches;
+use crate::combine::{AndMatcher, NotMatcher, OrMatcher};
+use crate::config::SearchConfig;
+use crate::context::MatchContext;
+use crate::engine::{
+    CancelToken, DirReadLimit, EngineOptions, EngineTuning, MetadataMode, WalkConfig, YieldPoints,
+    run,
+};
+use crate::entry::Entry;
 use crate::error::ParexError;
+use crate::exclude::ExclusionSet;
+use crate::iter::MatchIter;
+use crate::mounts::{MountPoints, SpecialMounts};
+use crate::notify::{Notifier, Notifiers, Threshold};
+use crate::overlay::Overlay;
+use crate::plan::{Cost, Plan};
+use crate::rank::{Rerank, Scored, ScoredMatcher};
 use crate::results::Results;
-use crate::traits::{Matcher, Source};
+use crate::rules::Rule;
+use crate::skip::SkipDirs;
+use crate::sort::SortOrder;
+#[cfg(feature = "futures")]
+use crate::stream::MatchStream;
+use crate::traits::{Matcher, Sink, Source};
+use crate::verify::{Manifest, Pool, Verification};
+use crate::warning::{Observer, Warning, Warnings};
 
 // ---------------------------------------------------------------------------
 // SearchBuilder
@@ -23,10 +48,7 @@ use crate::traits::{Matcher, Source};
 /// struct NameSource(Vec<&'static str>);
 /// impl Source for NameSource {
 ///     fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
-///         let entries = self.0.iter().map(|n| Ok(Entry {
-///             path: n.into(),
-///             kind: EntryKind::File, depth: 0, metadata: None,
-///         })).collect::<Vec<_>>();
+///         let entries = self.0.iter().map(|n| Ok(Entry::new(n, EntryKind::File, 0))).collect::<Vec<_>>();
 ///         Box::new(entries.into_iter())
 ///     }
 /// }
@@ -44,24 +66,140 @@ use crate::traits::{Matcher, Source};
 /// ```
 pub struct SearchBuilder {
     source: Option<Box<dyn Source>>,
+    source_name: Option<&'static str>,
     matcher: Option<Box<dyn Matcher>>,
+    matcher_name: &'static str,
+    pattern: Option<String>,
+    match_case: MatchCase,
+    /// The `.matching_regex()` pattern, kept so `to_config()` can save it.
+    #[cfg(feature = "regex")]
+    regex: Option<String>,
+    /// A `.matching_regex()` pattern that did not compile, reported by `run()`.
+    invalid_pattern: Option<ParexError>,
+    /// A saved setting `from_config()` could not restore, reported by `run()`.
+    invalid_config: Option<ParexError>,
+    filters: Vec<(Box<dyn Matcher>, &'static str)>,
     limit: Option<usize>,
-    threads: usize,
+    /// `None` uses every logical core.
+    threads: Option<usize>,
     max_depth: Option<usize>,
+    max_dir_reads: Option<usize>,
+    metadata_mode: MetadataMode,
+    shuffle_seed: Option<u64>,
     collect_paths: bool,
+    collect_entries: bool,
     collect_errors: bool,
+    collect_warnings: bool,
+    sort: Option<SortOrder>,
+    on_warning: Option<Observer>,
+    on_audit: Option<AuditObserver>,
+    notifiers: Notifiers,
+    rules: Vec<Rule>,
+    requester: Option<String>,
+    exclude: Option<ExclusionSet>,
+    skip_common_bloat: bool,
+    #[cfg(feature = "glob")]
+    include_globs: Vec<String>,
+    #[cfg(feature = "glob")]
+    exclude_globs: Vec<String>,
+    special_mounts: SpecialMounts,
+    traverse_special_mounts: bool,
+    sink: Option<Box<dyn Sink>>,
+    on_matches: Option<MatchBatches>,
+    overlay: Option<Box<dyn Overlay>>,
+    rerank: Option<Box<dyn Rerank>>,
+    scored: bool,
+    top_k: Option<usize>,
+    tuning: EngineTuning,
+    cancel: CancelToken,
+    checkpoint: Option<PathBuf>,
+    yield_every: usize,
+    on_yield: Option<Arc<dyn Fn() + Send + Sync>>,
+    debounce: Duration,
+    accurate_stats: bool,
+    dir_summaries: bool,
+    depth_histogram: bool,
+    profile: bool,
+    slowest_dirs: usize,
+    stat_ahead: usize,
+    #[cfg(feature = "prefetch")]
+    prefetch: usize,
+    #[cfg(feature = "signals")]
+    cancel_on_signal: bool,
+    #[cfg(all(target_os = "linux", feature = "cgroup"))]
+    io_cgroup: Option<crate::cgroup::IoCgroup>,
+    #[cfg(feature = "power")]
+    energy_policy: Option<crate::power::EnergyPolicy>,
+    #[cfg(feature = "otel")]
+    trace_context: Option<opentelemetry::Context>,
 }
 
 impl Default for SearchBuilder {
     fn default() -> Self {
         Self {
             source: None,
+            source_name: None,
             matcher: None,
+            matcher_name: std::any::type_name::<AllMatcher>(),
+            pattern: None,
+            match_case: MatchCase::default(),
+            #[cfg(feature = "regex")]
+            regex: None,
+            invalid_pattern: None,
+            invalid_config: None,
+            filters: Vec::new(),
             limit: None,
-            threads: num_cpus(),
+            threads: None,
             max_depth: None,
+            max_dir_reads: None,
+            metadata_mode: MetadataMode::Follow,
+            shuffle_seed: None,
             collect_paths: false,
+            collect_entries: false,
             collect_errors: false,
+            collect_warnings: false,
+            sort: None,
+            on_warning: None,
+            on_audit: None,
+            notifiers: Notifiers::default(),
+            rules: Vec::new(),
+            requester: None,
+            exclude: None,
+            skip_common_bloat: false,
+            #[cfg(feature = "glob")]
+            include_globs: Vec::new(),
+            #[cfg(feature = "glob")]
+            exclude_globs: Vec::new(),
+            special_mounts: SpecialMounts::default(),
+            traverse_special_mounts: false,
+            sink: None,
+            on_matches: None,
+            overlay: None,
+            rerank: None,
+            scored: false,
+            top_k: None,
+            tuning: EngineTuning::default(),
+            cancel: CancelToken::default(),
+            checkpoint: None,
+            yield_every: 0,
+            on_yield: None,
+            debounce: Duration::ZERO,
+            accurate_stats: false,
+            dir_summaries: false,
+            depth_histogram: false,
+            profile: false,
+            slowest_dirs: 0,
+            stat_ahead: 0,
+            #[cfg(feature = "prefetch")]
+            prefetch: 0,
+            #[cfg(feature = "signals")]
+            cancel_on_signal: false,
+            #[cfg(all(target_os = "linux", feature = "cgroup"))]
+            io_cgroup: None,
+            #[cfg(feature = "power")]
+            energy_policy: None,
+            #[cfg(feature = "otel")]
+            trace_context: None,
         }
     }
 }
@@ -73,8 +211,22 @@ impl SearchBuilder {
     ///
     /// Any type implementing [`Source`] is accepted — filesystem directories,
     /// in-memory collections, databases, etc.
-    pub fn source(mut self, s: impl Source + 'static) -> Self {
+    pub fn source<S: Source + 'static>(mut self, s: S) -> Self {
         self.source = Some(Box::new(s));
+        self.source_name = Some(std::any::type_name::<S>());
+        self
+    }
+
+    /// Set a source whose entries arrive as a stream.
+    ///
+    /// The stream is polled on the thread consuming the walk, which parks
+    /// while it is pending; see [`AsyncSource`](crate::AsyncSource) for
+    /// which streams can be driven this way. Replaces any
+    /// [`source`](Self::source).
+    #[cfg(feature = "futures")]
+    pub fn async_source<S: crate::AsyncSource + 'static>(mut self, s: S) -> Self {
+        self.source = Some(Box::new(crate::async_source::Blocking(s)));
+        self.source_name = Some(std::any::type_name::<S>());
         self
     }
 
@@ -86,24 +238,128 @@ impl SearchBuilder {
     /// matching logic — regex, fuzzy search, metadata filters, ML scoring, etc.
     ///
     /// For the common case of substring matching, prefer `.matching()`.
-    pub fn with_matcher(mut self, m: impl Matcher + 'static) -> Self {
+    pub fn with_matcher<M: Matcher + 'static>(mut self, m: M) -> Self {
         self.matcher = Some(Box::new(m));
+        self.matcher_name = std::any::type_name::<M>();
+        self.pattern = None;
+        #[cfg(feature = "regex")]
+        {
+            self.regex = None;
+        }
+        self.invalid_pattern = None;
         self
     }
 
     /// Shorthand for substring matching.
     ///
     /// Equivalent to `.with_matcher(SubstringMatcher::new(pattern))`.
-    /// Pattern matching is case-insensitive by default.
+    /// Pattern matching is case-insensitive by default; see
+    /// [`match_case`](Self::match_case).
     ///
     /// For custom matching logic, use `.with_matcher()` instead.
     pub fn matching(mut self, pattern: impl Into<String>) -> Self {
-        self.matcher = Some(Box::new(SubstringMatcher {
-            pattern: pattern.into().to_lowercase().into_bytes(),
-        }));
+        let pattern = pattern.into();
+        self.matcher = Some(Box::new(SubstringMatcher::new(&pattern, self.match_case)));
+        self.matcher_name = std::any::type_name::<SubstringMatcher>();
+        self.pattern = Some(pattern);
+        #[cfg(feature = "regex")]
+        {
+            self.regex = None;
+        }
+        self.invalid_pattern = None;
+        self
+    }
+
+    /// How [`.matching()`](Self::matching) treats letter case. Default:
+    /// [`MatchCase::Insensitive`].
+    ///
+    /// May be set before or after the pattern.
+    pub fn match_case(mut self, case: MatchCase) -> Self {
+        self.match_case = case;
+        if let Some(pattern) = &self.pattern {
+            self.matcher = Some(Box::new(SubstringMatcher::new(pattern, case)));
+        }
+        self
+    }
+
+    /// Shorthand for `.match_case(MatchCase::Sensitive)` when `yes`, and
+    /// `.match_case(MatchCase::Insensitive)` otherwise.
+    pub fn case_sensitive(self, yes: bool) -> Self {
+        self.match_case(if yes {
+            MatchCase::Sensitive
+        } else {
+            MatchCase::Insensitive
+        })
+    }
+
+    /// Match file names against a regular expression.
+    ///
+    /// Equivalent to `.with_matcher(RegexMatcher::new(pattern)?)`, with the
+    /// pattern compiled once here; if it does not compile,
+    /// [`run()`](Self::run) returns [`ParexError::InvalidPattern`]. Matching
+    /// is case-sensitive unless the pattern starts with `(?i)`. To match
+    /// whole paths, pass a [`RegexMatcher`](crate::matchers::RegexMatcher)
+    /// with `.full_path(true)` to `.with_matcher()`.
+    #[cfg(feature = "regex")]
+    pub fn matching_regex(self, pattern: &str) -> Self {
+        use crate::matchers::RegexMatcher;
+
+        let regex = Some(pattern.to_owned());
+        match RegexMatcher::new(pattern) {
+            Ok(m) => Self {
+                regex,
+                ..self.with_matcher(m)
+            },
+            Err(e) => Self {
+                regex,
+                invalid_pattern: Some(e),
+                ..self.with_matcher(AllMatcher)
+            },
+        }
+    }
+
+    /// Add a predicate every match must also satisfy.
+    ///
+    /// Filters are ANDed with the matcher and with each other. Before the
+    /// walk, all of them are ordered by [`Matcher::cost`] so cheap name
+    /// checks run before any `stat()` or file read, and any filter that
+    /// [prunes directories](Matcher::prunes_dir) is offered to the source
+    /// through [`WalkConfig::skip_dir`]. Equal-cost predicates keep the
+    /// order they were added in.
+    pub fn filter<M: Matcher + 'static>(mut self, m: M) -> Self {
+        self.filters.push((Box::new(m), std::any::type_name::<M>()));
         self
     }
 
+    /// Add a filter that every one of `matchers` must satisfy.
+    ///
+    /// Shorthand for `.filter()` with an [`AndMatcher`]; the matchers are
+    /// checked in the order given.
+    pub fn all_of<I>(self, matchers: I) -> Self
+    where
+        I: IntoIterator<Item = Box<dyn Matcher>>,
+    {
+        self.filter(matchers.into_iter().collect::<AndMatcher>())
+    }
+
+    /// Add a filter that at least one of `matchers` must satisfy.
+    ///
+    /// Shorthand for `.filter()` with an [`OrMatcher`], so with `.matching()`
+    /// it narrows the pattern's matches to those any of `matchers` accepts.
+    pub fn any_of<I>(self, matchers: I) -> Self
+    where
+        I: IntoIterator<Item = Box<dyn Matcher>>,
+    {
+        self.filter(matchers.into_iter().collect::<OrMatcher>())
+    }
+
+    /// Drop every entry `m` matches.
+    ///
+    /// Shorthand for `.filter(NotMatcher::new(m))`.
+    pub fn excluding<M: Matcher + 'static>(self, m: M) -> Self {
+        self.filter(NotMatcher::new(m))
+    }
+
     // ── Options ───────────────────────────────────────────────────────────
 
     /// Stop after `n` matches.
@@ -112,21 +368,111 @@ impl SearchBuilder {
         self
     }
 
+    /// Walk the whole source even when a [`limit`](Self::limit) is set, so
+    /// [`ScanStats`](crate::ScanStats) `files` and `dirs` count every entry.
+    ///
+    /// Matching stops at the limit — only `n` matches are reported, and
+    /// entries past it are counted without being matched — but the source
+    /// is read to the end, giving "first 10 matches of 48,213 files" from
+    /// one pass. `stats.complete` is then `true`. Off by default.
+    pub fn accurate_stats(mut self, yes: bool) -> Self {
+        self.accurate_stats = yes;
+        self
+    }
+
+    /// Match directories by what the walk found below them.
+    ///
+    /// Each directory is held back until the source is exhausted, then
+    /// matched with a [`DirSummary`](crate::DirSummary) of its contents
+    /// available through
+    /// [`MatchContext::dir_summary`](crate::MatchContext::dir_summary) —
+    /// "directories with more than 10 000 files" is a matcher on
+    /// `summary.files`. Directories are therefore reported after every
+    /// other match, and not at all if the search stops early (cancelled,
+    /// limited or failed), since their summaries would be incomplete.
+    ///
+    /// Byte counts and modification times come from
+    /// [`Entry::metadata`](crate::Entry::metadata); combine with
+    /// [`prefetch_metadata`](Self::prefetch_metadata) when the source does
+    /// not fill it in. Off by default.
+    pub fn dir_summaries(mut self, yes: bool) -> Self {
+        self.dir_summaries = yes;
+        self
+    }
+
     /// Number of threads to use for parallel traversal.
     ///
+    /// Passed to the source, and used for the engine's matcher pool: with
+    /// more than one thread, matchers whose [`cost()`](Matcher::cost) is
+    /// above [`Cost::Name`] run on a work-stealing pool of this many
+    /// workers, so even a sequential source is matched on every core.
+    /// Matches from the pool arrive in whatever order the workers finish.
+    ///
     /// Defaults to the number of logical CPU cores.
     pub fn threads(mut self, n: usize) -> Self {
-        self.threads = n;
+        self.threads = Some(n);
         self
     }
 
     /// Maximum traversal depth. `0` means root only, `1` means one level
     /// of children, and so on. Unlimited by default.
+    ///
+    /// Sources receive the limit so they can avoid descending, and the
+    /// engine drops any deeper entry a source yields anyway — deeper entries
+    /// are not matched or counted.
     pub fn max_depth(mut self, d: usize) -> Self {
         self.max_depth = Some(d);
         self
     }
 
+    /// Limit how many directories the source reads concurrently.
+    ///
+    /// Separate from [`threads()`](Self::threads): on NFS or SMB a handful
+    /// of in-flight `readdir` calls per export is often faster than hundreds,
+    /// while matching still benefits from every core. Sources apply it via
+    /// [`WalkConfig::dir_read_limit`]. Unlimited by default.
+    pub fn max_dir_reads(mut self, n: usize) -> Self {
+        self.max_dir_reads = Some(n);
+        self
+    }
+
+    /// Apply the tuning profile for the storage `root` lives on.
+    ///
+    /// Sets [`threads()`](Self::threads) and
+    /// [`max_dir_reads()`](Self::max_dir_reads) from
+    /// [`storage::detect`](crate::storage::detect). Either can still be
+    /// overridden by calling it after this.
+    #[cfg(feature = "storage")]
+    pub fn tune_for(mut self, root: impl AsRef<Path>) -> Self {
+        let profile = crate::storage::detect(root).profile(num_cpus());
+        self.threads = Some(profile.threads);
+        self.max_dir_reads = profile.max_dir_reads;
+        self
+    }
+
+    /// Whether metadata for symlinks describes the link or its target.
+    ///
+    /// Applies to every `stat()` matchers make through
+    /// [`MatchContext::metadata`], and is passed to sources via
+    /// [`WalkConfig::metadata_mode`]. Defaults to [`MetadataMode::Follow`].
+    pub fn metadata_mode(mut self, mode: MetadataMode) -> Self {
+        self.metadata_mode = mode;
+        self
+    }
+
+    /// Visit sibling directories in a random order derived from `seed`.
+    ///
+    /// Spreads a walk's load across storage stripes or shards instead of
+    /// hammering them in name order, and makes the first matches of an
+    /// interactive search a fair sample rather than the alphabetically
+    /// first directories. The same seed reproduces the same order. Sources
+    /// apply it with [`WalkConfig::shuffle`]; sources that ignore it walk in
+    /// their usual order.
+    pub fn shuffle(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+
     /// Collect matched paths into [`Results::paths`].
     ///
     /// Disabled by default to avoid allocation overhead when paths aren't needed.
@@ -135,6 +481,16 @@ impl SearchBuilder {
         self
     }
 
+    /// Collect matched entries into [`Results::entries`].
+    ///
+    /// Unlike [`collect_paths`](Self::collect_paths), each [`Entry`] keeps
+    /// the kind, depth, metadata, and tags the walk already produced, so
+    /// callers can act on them without a second `stat()`. Disabled by default.
+    pub fn collect_entries(mut self, yes: bool) -> Self {
+        self.collect_entries = yes;
+        self
+    }
+
     /// Collect non-fatal errors into [`Results::errors`].
     ///
     /// Disabled by default. When enabled, recoverable errors (permission denied,
@@ -144,8 +500,669 @@ impl SearchBuilder {
         self
     }
 
+    /// Sort [`Results::paths`] and [`Results::entries`] in `order` once the
+    /// walk finishes.
+    ///
+    /// Applies to [`run()`](Self::run) with `.collect_paths(true)` or
+    /// `.collect_entries(true)`; streamed
+    /// matches and sinks still see entries in discovery order.
+    pub fn sort(mut self, order: SortOrder) -> Self {
+        self.sort = Some(order);
+        self
+    }
+
+    /// Count matches per depth into [`Results::match_depths`].
+    ///
+    /// Disabled by default. A histogram that peaks six levels down says
+    /// more about where to set [`max_depth`](Self::max_depth) or an
+    /// exclusion than any single path does.
+    pub fn depth_histogram(mut self, yes: bool) -> Self {
+        self.depth_histogram = yes;
+        self
+    }
+
+    /// Time the walk and report it in [`Results::profile`].
+    ///
+    /// Records how long each pull from the source and each `stat()` made
+    /// through [`MatchContext::metadata`] took, throughput over the course
+    /// of the walk, and a guess at whether caches were warm. Costs two
+    /// clock reads per entry. Disabled by default. See
+    /// [`ScanProfile`](crate::profile::ScanProfile).
+    pub fn profile(mut self, yes: bool) -> Self {
+        self.profile = yes;
+        self
+    }
+
+    /// Report the `n` directories that took longest to enumerate and the
+    /// `n` that yielded the most entries, in [`Results::dir_report`].
+    ///
+    /// The quick way to find the `node_modules` worth excluding. Costs two
+    /// clock reads per entry and a map entry per directory. Disabled
+    /// (`0`) by default.
+    pub fn slowest_dirs(mut self, n: usize) -> Self {
+        self.slowest_dirs = n;
+        self
+    }
+
+    /// Collect [`Warning`]s into [`Results::warnings`].
+    ///
+    /// Disabled by default. Warnings are diagnostics that did not stop an
+    /// entry from being handled — a file a content matcher skipped as
+    /// binary, a source backing off a rate limit — kept apart from
+    /// [`Results::errors`].
+    pub fn collect_warnings(mut self, yes: bool) -> Self {
+        self.collect_warnings = yes;
+        self
+    }
+
+    /// Call `f` with every [`Warning`] as it is raised.
+    ///
+    /// Runs on whichever thread raised it — the source's or a matcher
+    /// thread — so keep it quick. Independent of
+    /// [`collect_warnings`](Self::collect_warnings).
+    pub fn on_warning(mut self, f: impl Fn(&Warning) + Send + Sync + 'static) -> Self {
+        self.on_warning = Some(Box::new(f));
+        self
+    }
+
+    /// Call `f` with an [`AuditRecord`] of the search once it ends —
+    /// finished, cancelled, or failed.
+    ///
+    /// The record carries the query, the [`requester`](Self::requester)
+    /// label, the runtime, and result counts, but no matched paths. Runs
+    /// on the thread that ran the search. See
+    /// [`audit_log`](crate::audit_log) for a JSON Lines writer.
+    pub fn on_audit(mut self, f: impl Fn(&AuditRecord) + Send + Sync + 'static) -> Self {
+        self.on_audit = Some(Box::new(f));
+        self
+    }
+
+    /// Tell `notifier` when the search ends, and when it breaches any
+    /// [`alert_when`](Self::alert_when) threshold.
+    ///
+    /// Can be called more than once; every notifier gets every event. See
+    /// [`notify`](crate::notify) for the events and a webhook notifier.
+    pub fn notify(mut self, notifier: impl Notifier + 'static) -> Self {
+        self.notifiers.notifiers.push(Box::new(notifier));
+        self
+    }
+
+    /// Raise an [`Event::Breached`](crate::notify::Event::Breached) for the
+    /// [`notify`](Self::notify) notifiers if the search exceeds `threshold`.
+    /// Can be called more than once.
+    pub fn alert_when(mut self, threshold: Threshold) -> Self {
+        self.notifiers.thresholds.push(threshold);
+        self
+    }
+
+    /// Check `rule` against the matches when the search ends, listing each
+    /// breach in [`Results::violations`]. Can be called more than once.
+    ///
+    /// Rules that total bytes `stat()` every matched file whose source did
+    /// not cache its metadata. See [`rules`](crate::rules).
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Label the search with who asked for it — a user, a client, a
+    /// service — for the [`on_audit`](Self::on_audit) record. Has no other
+    /// effect.
+    pub fn requester(mut self, label: impl Into<String>) -> Self {
+        self.requester = Some(label.into());
+        self
+    }
+
+    /// Skip entries whose path, or any ancestor of it, is in `set`.
+    ///
+    /// Excluded entries are dropped before matching and are not counted in
+    /// [`ScanStats`](crate::ScanStats). Build the set once and clone it to
+    /// reuse a large blocklist across searches.
+    pub fn exclude(mut self, set: ExclusionSet) -> Self {
+        self.exclude = Some(set);
+        self
+    }
+
+    /// Shorthand for `.exclude(ExclusionSet::new(paths))`.
+    pub fn exclude_paths<I, P>(self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.exclude(ExclusionSet::new(paths))
+    }
+
+    /// Skip version control, dependency, build and cache directories —
+    /// `.git`, `node_modules`, `target`, `__pycache__` and the rest of
+    /// [`COMMON_BLOAT`](crate::COMMON_BLOAT).
+    ///
+    /// Adds a [`SkipDirs::common()`] filter, so the directories are pruned
+    /// at sources that honour [`WalkConfig::skip_dir`] and their contents
+    /// never match elsewhere. Disabled by default. To adjust the list, pass
+    /// your own [`SkipDirs`] to [`filter`](Self::filter) instead.
+    pub fn skip_common_bloat(mut self, yes: bool) -> Self {
+        self.skip_common_bloat = yes;
+        self
+    }
+
+    /// Only match entries whose path below the root matches `pattern`.
+    ///
+    /// Repeatable: an entry matching any include glob passes. All include
+    /// and exclude globs are compiled into one
+    /// [`GlobMatcher`](crate::matchers::GlobMatcher) filter, which also
+    /// describes the pattern syntax; a pattern that does not compile makes
+    /// [`run()`](Self::run) return [`ParexError::InvalidPattern`].
+    #[cfg(feature = "glob")]
+    pub fn include_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.include_globs.push(pattern.into());
+        self
+    }
+
+    /// Drop entries whose path below the root matches `pattern`, and
+    /// everything inside directories that do.
+    ///
+    /// Repeatable. Excluded directories are pruned when the source reports
+    /// its [`root`](Source::root). See [`include_glob`](Self::include_glob).
+    #[cfg(feature = "glob")]
+    pub fn exclude_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude_globs.push(pattern.into());
+        self
+    }
+
+    /// Choose which filesystem types the search stays out of.
+    ///
+    /// By default a search skips the pseudo filesystems in
+    /// [`PSEUDO_FILESYSTEMS`](crate::PSEUDO_FILESYSTEMS) — `/proc`, `/sys`,
+    /// `/dev` and the like — so a scan rooted at `/` neither wastes time in
+    /// them nor hangs on a blocking read. Their mount points are pruned at
+    /// sources that honour [`WalkConfig::skip_dir`], and entries inside them
+    /// are dropped before matching, but only below the search root: a search
+    /// rooted in `/proc` still walks it. Mount points are matched against
+    /// entry paths as the source reports them, so sources should report
+    /// absolute paths.
+    ///
+    /// Pass [`SpecialMounts::pseudo()`] with more types to skip network
+    /// shares or FUSE mounts as well.
+    pub fn special_mounts(mut self, policy: SpecialMounts) -> Self {
+        self.special_mounts = policy;
+        self
+    }
+
+    /// Walk into every mount, pseudo filesystems included, ignoring
+    /// [`special_mounts`](Self::special_mounts). Off by default.
+    pub fn traverse_special_mounts(mut self, yes: bool) -> Self {
+        self.traverse_special_mounts = yes;
+        self
+    }
+
+    /// Hand every match to `sink` while the walk is running.
+    ///
+    /// See [`Sink`] for error semantics.
+    pub fn sink(mut self, sink: impl Sink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Call `f` with matches in batches of up to `size`, rather than once
+    /// per match.
+    ///
+    /// For scans matching so much that a per-match call would dominate: a
+    /// batch is handed over once it holds `size` matches, or once `interval`
+    /// has passed since its first match — checked as matches arrive, so a
+    /// quiet walk holds a partial batch until the next match or the end.
+    /// Whatever is pending when the search ends, however it ends, is
+    /// flushed. Runs on the thread consuming the walk, one batch at a time;
+    /// a match reaches [`Results::paths`] or a stream after its batch.
+    pub fn on_matches(
+        mut self,
+        size: usize,
+        interval: Duration,
+        f: impl FnMut(&[Entry]) + Send + 'static,
+    ) -> Self {
+        self.on_matches = Some(MatchBatches::new(size, interval, Box::new(f)));
+        self
+    }
+
+    /// Tag entries from a sidecar store before they reach the matcher.
+    ///
+    /// The engine looks tags up in batches and stores them in
+    /// [`Entry::tags`]. See [`Overlay`].
+    pub fn overlay(mut self, overlay: impl Overlay + 'static) -> Self {
+        self.overlay = Some(Box::new(overlay));
+        self
+    }
+
+    /// Rank matches with `stage` once the walk finishes, filling
+    /// [`Results::ranked`].
+    ///
+    /// Every match is kept as a [`ScoredEntry`](crate::rank::ScoredEntry)
+    /// while the walk runs, so this costs one `Entry` per match in memory.
+    /// Applies to [`run()`](Self::run) only. See [`Rerank`].
+    pub fn rerank(mut self, stage: impl Rerank + 'static) -> Self {
+        self.rerank = Some(Box::new(stage));
+        self
+    }
+
+    /// Match with `scorer` and rank the matches by its scores, filling
+    /// [`Results::ranked`] best first.
+    ///
+    /// Entries `scorer` gives a score are added as a `.filter()`, so it
+    /// narrows `.matching()` or `.with_matcher()` rather than replacing
+    /// them, and runs on the engine's matcher threads. Each entry is scored
+    /// once; a match starts with its score in place of `0.0`, so a
+    /// [`rerank()`](Self::rerank)
+    /// stage can adjust it — fuzzy scores plus a frecency boost, say.
+    /// Applies to [`run()`](Self::run) only. See [`ScoredMatcher`].
+    pub fn rank_by_score<S: ScoredMatcher + 'static>(mut self, scorer: S) -> Self {
+        self.filters.push((
+            Box::new(Scored(Box::new(scorer))),
+            std::any::type_name::<S>(),
+        ));
+        self.scored = true;
+        self
+    }
+
+    /// Keep only the `k` best-ranked matches in [`Results::ranked`].
+    ///
+    /// Without a `.rerank()` stage, scores are final as soon as a match is
+    /// found, so the rest are dropped during the walk and memory stays
+    /// proportional to `k` rather than to the number of matches. Unlike
+    /// [`limit()`](Self::limit), the whole source is still searched.
+    pub fn top_k(mut self, k: usize) -> Self {
+        self.top_k = Some(k);
+        self
+    }
+
+    /// Override internal buffer and batch sizes. See [`EngineTuning`].
+    pub fn tuning(mut self, tuning: EngineTuning) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    /// Use `token` to cancel this search from another thread.
+    ///
+    /// Calling [`CancelToken::cancel`] stops the walk at the next entry;
+    /// [`run()`](Self::run) then returns the results gathered so far.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Save the matches to a [`Snapshot`](crate::snapshot::Snapshot) file at
+    /// `path` if the search is cancelled or fails, so a shutdown does not
+    /// throw away hours of scanning.
+    ///
+    /// On cancellation the engine stops pulling from the source, lets matcher
+    /// threads hand back what they already matched, and saves every match
+    /// the results report. A search that runs to the end removes `path`
+    /// instead, so the file exists only after an interrupted search. A
+    /// failure to save or remove it is added to [`Results::errors`]. Unset
+    /// by default.
+    pub fn checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// Give up the CPU every `n` entries on each thread that pulls or
+    /// matches them, so the search can share cores with work that needs
+    /// bounded scheduling latency.
+    ///
+    /// Yields with [`std::thread::yield_now`] unless
+    /// [`on_yield`](Self::on_yield) supplies a hook. Sources that traverse
+    /// on their own threads yield too if they tick
+    /// [`WalkConfig::yield_points`]. `0`, the default, never yields.
+    pub fn yield_every(mut self, n: usize) -> Self {
+        self.yield_every = n;
+        self
+    }
+
+    /// Call `f` at each [`yield_every`](Self::yield_every) point instead of
+    /// [`std::thread::yield_now`] — to sleep for a slice, or hand control
+    /// to the host's scheduler.
+    ///
+    /// Runs on the yielding thread: the source's, the consuming thread, or
+    /// a matcher thread. Has no effect without `yield_every`.
+    pub fn on_yield(mut self, f: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_yield = Some(Arc::new(f));
+        self
+    }
+
+    /// Wait `delay` before starting the walk, giving up if the search is
+    /// cancelled in the meantime.
+    ///
+    /// For interactive pickers that start a search per keystroke and cancel
+    /// the previous one: with a short debounce, queries superseded while the
+    /// user is still typing never touch the source. [`run()`](Self::run)
+    /// then returns an empty, cancelled result. Disabled by default.
+    pub fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = delay;
+        self
+    }
+
+    /// `stat()` entries up to `n` ahead of the matcher on dedicated IO
+    /// threads.
+    ///
+    /// Metadata matchers — size, age, owner filters — otherwise wait on one
+    /// blocking `stat()` per entry. With a window of upcoming entries in
+    /// flight on [`EngineTuning::stat_threads`] threads, slow storage
+    /// answers many at once and each entry reaches the matcher with
+    /// [`Entry::metadata`] filled in. Only applies when the matcher's
+    /// [`cost()`](Matcher::cost) is [`Cost::Metadata`] or above. Disabled
+    /// by default.
+    pub fn prefetch_metadata(mut self, n: usize) -> Self {
+        self.stat_ahead = n;
+        self
+    }
+
+    /// Hint the kernel to read files `n` entries ahead of the matcher.
+    ///
+    /// Each upcoming regular file gets a `posix_fadvise(WILLNEED)` so its
+    /// contents load while earlier files are being matched — a large win on
+    /// cold caches and slow disks. Only applies when the matcher's
+    /// [`cost()`](Matcher::cost) is [`Cost::Content`]; name and metadata
+    /// matchers never read the files. A no-op on platforms without
+    /// `posix_fadvise`. Disabled by default.
+    #[cfg(feature = "prefetch")]
+    pub fn prefetch(mut self, n: usize) -> Self {
+        self.prefetch = n;
+        self
+    }
+
+    /// Cancel the search on SIGINT / SIGTERM instead of letting the process die.
+    ///
+    /// A process-wide handler is installed the first time a search opts in.
+    /// On Ctrl-C, in-flight searches stop at the next entry and
+    /// [`run()`](Self::run) returns the partial results, so CLI tools can
+    /// still print a summary. Fails with [`ParexError::Signal`] if the
+    /// application already installed its own handler.
+    #[cfg(feature = "signals")]
+    pub fn cancel_on_signal(mut self, yes: bool) -> Self {
+        self.cancel_on_signal = yes;
+        self
+    }
+
+    /// Defer or slow the search while the host runs on battery or is
+    /// thermally throttled.
+    ///
+    /// With [`EnergyPolicy::Defer`](crate::power::EnergyPolicy::Defer) the
+    /// walk waits, like a [`debounce`](Self::debounce), until the host is
+    /// unconstrained; with [`EnergyPolicy::Slow`](crate::power::EnergyPolicy::Slow)
+    /// it starts at once but with one thread and one directory read at a
+    /// time. The host is checked when the search starts. Unset by default.
+    /// See [`power`](crate::power).
+    #[cfg(feature = "power")]
+    pub fn energy_policy(mut self, policy: crate::power::EnergyPolicy) -> Self {
+        self.energy_policy = Some(policy);
+        self
+    }
+
+    /// Parent the search's spans to `cx` instead of the context current
+    /// when the search starts.
+    ///
+    /// For searches run on behalf of a remote caller, with `cx` extracted
+    /// from its request by a propagator.
+    #[cfg(feature = "otel")]
+    pub fn trace_context(mut self, cx: opentelemetry::Context) -> Self {
+        self.trace_context = Some(cx);
+        self
+    }
+
+    /// Run the search inside `cgroup`, so the kernel enforces its IO limits.
+    ///
+    /// The process joins the cgroup when the search starts and returns to
+    /// its previous cgroup when the last search using it ends; the whole
+    /// process is charged meanwhile. Searches in flight at the same time
+    /// must share one cgroup. Fails with [`ParexError::Io`] if the process
+    /// cannot be moved. See [`cgroup`](crate::cgroup).
+    #[cfg(all(target_os = "linux", feature = "cgroup"))]
+    pub fn io_cgroup(mut self, cgroup: crate::cgroup::IoCgroup) -> Self {
+        self.io_cgroup = Some(cgroup);
+        self
+    }
+
     // ── Execute ───────────────────────────────────────────────────────────
 
+    /// Describe what [`run()`](Self::run) would do, without walking anything.
+    ///
+    /// The [`Plan`] names the source and matcher, the matcher's cost class,
+    /// and which options prune the walk. Print it to debug a search that is
+    /// slow or matches unexpectedly.
+    ///
+    /// ```rust
+    /// # use parex::{Source, Entry, ParexError};
+    /// # use parex::engine::WalkConfig;
+    /// # struct Files;
+    /// # impl Source for Files {
+    /// #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+    /// #         Box::new(std::iter::empty())
+    /// #     }
+    /// # }
+    /// let plan = parex::search()
+    ///     .source(Files)
+    ///     .matching("invoice")
+    ///     .max_depth(3)
+    ///     .explain();
+    ///
+    /// assert_eq!(plan.cost, parex::Cost::Name);
+    /// assert!(!plan.reads_metadata());
+    /// println!("{plan}");
+    /// ```
+    pub fn explain(&self) -> Plan {
+        let mut predicates: Vec<(&'static str, Cost)> = self
+            .filters
+            .iter()
+            .map(|(f, name)| (*name, f.cost()))
+            .collect();
+        if self.skip_common_bloat {
+            predicates.push((std::any::type_name::<SkipDirs>(), Cost::Name));
+        }
+        #[cfg(feature = "glob")]
+        if !self.include_globs.is_empty() || !self.exclude_globs.is_empty() {
+            predicates.push((
+                std::any::type_name::<crate::matchers::GlobMatcher>(),
+                Cost::Name,
+            ));
+        }
+        if self.matcher.is_some() || predicates.is_empty() {
+            let cost = self
+                .matcher
+                .as_ref()
+                .map_or(AllMatcher.cost(), |m| m.cost());
+            predicates.insert(0, (self.matcher_name, cost));
+        }
+        // Same ordering as the planning pass in into_options()
+        predicates.sort_by_key(|(_, cost)| *cost);
+
+        Plan {
+            source: self.source_name,
+            root: self
+                .source
+                .as_ref()
+                .and_then(|s| s.root())
+                .map(Path::to_path_buf),
+            matcher: self.matcher_name,
+            cost: predicates
+                .iter()
+                .map(|(_, c)| *c)
+                .max()
+                .unwrap_or(Cost::Name),
+            predicates: predicates.into_iter().map(|(name, _)| name).collect(),
+            threads: self.thread_count(),
+            max_depth: self.max_depth,
+            metadata_mode: self.metadata_mode,
+            excluded_paths: self.exclude.as_ref().map_or(0, ExclusionSet::len),
+            limit: self.limit,
+            accurate_stats: self.accurate_stats,
+            overlay: self.overlay.is_some(),
+            sink: self.sink.is_some(),
+        }
+    }
+
+    /// The builder's structured settings, to save and rebuild it later with
+    /// [`from_config`](Self::from_config).
+    ///
+    /// See [`SearchConfig`] for what is captured and what is left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Unrepresentable`] when the builder has a
+    /// setting that decides what matches or how results are ranked but
+    /// cannot be written down — a matcher passed to `.with_matcher()`, a
+    /// `.filter()` or one of its shorthands, a `.rerank()` stage, or
+    /// `.rank_by_score()` — rather than return a configuration that would
+    /// match more than the builder does.
+    pub fn to_config(&self) -> Result<SearchConfig, ParexError> {
+        let unrepresentable = |what: &str| Err(ParexError::Unrepresentable(what.to_owned()));
+        #[cfg(feature = "regex")]
+        let has_spec = self.pattern.is_some() || self.regex.is_some();
+        #[cfg(not(feature = "regex"))]
+        let has_spec = self.pattern.is_some();
+        if self.matcher.is_some() && !has_spec {
+            return unrepresentable(&format!("matcher {}", self.matcher_name));
+        }
+        if let Some((_, name)) = self.filters.first() {
+            return unrepresentable(&format!("filter {name}"));
+        }
+        if self.rerank.is_some() {
+            return unrepresentable("rerank stage");
+        }
+        if self.scored {
+            return unrepresentable("rank_by_score matcher");
+        }
+        Ok(self.settings())
+    }
+
+    /// The captured settings, without checking for ones that cannot be.
+    pub(crate) fn settings(&self) -> SearchConfig {
+        let mut exclude_paths: Vec<PathBuf> = self
+            .exclude
+            .iter()
+            .flat_map(ExclusionSet::paths)
+            .map(Path::to_path_buf)
+            .collect();
+        exclude_paths.sort();
+
+        SearchConfig {
+            pattern: self.pattern.clone(),
+            #[cfg(feature = "regex")]
+            regex: self.regex.clone(),
+            #[cfg(not(feature = "regex"))]
+            regex: None,
+            match_case: self.match_case,
+            limit: self.limit,
+            accurate_stats: self.accurate_stats,
+            threads: self.threads,
+            max_depth: self.max_depth,
+            max_dir_reads: self.max_dir_reads,
+            metadata_mode: self.metadata_mode,
+            shuffle_seed: self.shuffle_seed,
+            exclude_paths,
+            #[cfg(feature = "glob")]
+            include_globs: self.include_globs.clone(),
+            #[cfg(not(feature = "glob"))]
+            include_globs: Vec::new(),
+            #[cfg(feature = "glob")]
+            exclude_globs: self.exclude_globs.clone(),
+            #[cfg(not(feature = "glob"))]
+            exclude_globs: Vec::new(),
+            skip_common_bloat: self.skip_common_bloat,
+            special_mounts: self.special_mounts.clone(),
+            traverse_special_mounts: self.traverse_special_mounts,
+            collect_paths: self.collect_paths,
+            collect_entries: self.collect_entries,
+            collect_errors: self.collect_errors,
+            collect_warnings: self.collect_warnings,
+            sort: self.sort.as_ref().map(|order| order.name().to_owned()),
+            rules: self.rules.clone(),
+            requester: self.requester.clone(),
+            debounce: self.debounce,
+            top_k: self.top_k,
+            dir_summaries: self.dir_summaries,
+            depth_histogram: self.depth_histogram,
+            profile: self.profile,
+            slowest_dirs: self.slowest_dirs,
+            prefetch_metadata: self.stat_ahead,
+            checkpoint: self.checkpoint.clone(),
+            yield_every: self.yield_every,
+            tuning: self.tuning,
+        }
+    }
+
+    /// A builder with the settings in `config`, typically one saved with
+    /// [`to_config`](Self::to_config). Add a source, and anything else the
+    /// configuration could not capture, before running it.
+    ///
+    /// A setting this build cannot restore, such as a regex without the
+    /// `regex` feature, makes [`run()`](Self::run) return the error instead
+    /// of searching without it.
+    pub fn from_config(config: SearchConfig) -> Self {
+        let mut builder = Self::default().match_case(config.match_case);
+        if let Some(pattern) = config.pattern {
+            builder = builder.matching(pattern);
+        }
+        if let Some(regex) = config.regex {
+            #[cfg(feature = "regex")]
+            {
+                builder = builder.matching_regex(&regex);
+            }
+            #[cfg(not(feature = "regex"))]
+            {
+                builder.invalid_config = Some(ParexError::InvalidPattern(format!(
+                    "regex {regex:?} needs the `regex` feature"
+                )));
+            }
+        }
+        if !config.exclude_paths.is_empty() {
+            builder = builder.exclude_paths(config.exclude_paths);
+        }
+        #[cfg(feature = "glob")]
+        {
+            builder.include_globs = config.include_globs;
+            builder.exclude_globs = config.exclude_globs;
+        }
+        #[cfg(not(feature = "glob"))]
+        if !config.include_globs.is_empty() || !config.exclude_globs.is_empty() {
+            builder.invalid_config = Some(ParexError::InvalidPattern(
+                "include and exclude globs need the `glob` feature".to_owned(),
+            ));
+        }
+        if let Some(name) = config.sort {
+            match SortOrder::from_name(&name) {
+                Ok(order) => builder.sort = Some(order),
+                Err(e) => builder.invalid_config = Some(e),
+            }
+        }
+        Self {
+            limit: config.limit,
+            accurate_stats: config.accurate_stats,
+            threads: config.threads,
+            max_depth: config.max_depth,
+            max_dir_reads: config.max_dir_reads,
+            metadata_mode: config.metadata_mode,
+            shuffle_seed: config.shuffle_seed,
+            skip_common_bloat: config.skip_common_bloat,
+            special_mounts: config.special_mounts,
+            traverse_special_mounts: config.traverse_special_mounts,
+            collect_paths: config.collect_paths,
+            collect_entries: config.collect_entries,
+            collect_errors: config.collect_errors,
+            collect_warnings: config.collect_warnings,
+            rules: config.rules,
+            requester: config.requester,
+            debounce: config.debounce,
+            top_k: config.top_k,
+            dir_summaries: config.dir_summaries,
+            depth_histogram: config.depth_histogram,
+            profile: config.profile,
+            slowest_dirs: config.slowest_dirs,
+            stat_ahead: config.prefetch_metadata,
+            checkpoint: config.checkpoint,
+            yield_every: config.yield_every,
+            tuning: config.tuning,
+            ..builder
+        }
+    }
+
     /// Execute the search and return results.
     ///
     /// Blocks until the search completes.
@@ -153,82 +1170,403 @@ impl SearchBuilder {
     /// # Errors
     ///
     /// Returns `Err` for fatal configuration errors (no source provided,
-    /// invalid source path, thread pool failure). Non-fatal errors during
-    /// traversal are collected into [`Results::errors`] when
-    /// `.collect_errors(true)` is set.
-    pub fn run(self) -> Result<Results, ParexError> {
+    /// invalid source path). Once the walk has started, nothing is lost:
+    /// a fatal error or cancellation mid-walk ends the search early and is
+    /// reported through [`Results::completion`] alongside the partial results.
+    /// Non-fatal errors during traversal are collected into
+    /// [`Results::errors`] when `.collect_errors(true)` is set.
+    pub fn run(mut self) -> Result<Results, ParexError> {
+        let sort = self.sort.take();
+        let mut results = run(self.into_options()?);
+        if let Some(order) = sort {
+            results.sort_paths(&order);
+        }
+        Ok(results)
+    }
+
+    /// Start the search on a background thread and return a blocking
+    /// [`Iterator`] of matched entries.
+    ///
+    /// Matches are yielded as they are found, so processing starts before
+    /// the walk completes; dropping the iterator abandons the search. Use
+    /// [`MatchIter::finish`] for the counts and errors once done.
+    /// `.collect_paths()`, `.collect_entries()`, and `.sort()` have no
+    /// effect — every match is handed to the iterator by value, in discovery order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` for the same fatal configuration errors as
+    /// [`run()`](Self::run), or if the worker thread cannot be spawned.
+    pub fn run_iter(self) -> Result<MatchIter, ParexError> {
+        MatchIter::spawn(self.into_options()?)
+    }
+
+    /// Start the search on a background thread and return an async
+    /// [`Stream`](futures_core::Stream) of matched entries.
+    ///
+    /// Matches are yielded as they are found instead of after the walk
+    /// completes. `.collect_paths()` and `.collect_entries()` have no effect
+    /// — every match is handed to the stream by value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` for the same fatal configuration errors as
+    /// [`run()`](Self::run), or if the worker thread cannot be spawned.
+    #[cfg(feature = "futures")]
+    pub fn match_stream(self) -> Result<MatchStream, ParexError> {
+        MatchStream::spawn(self.into_options()?)
+    }
+
+    /// Start the search on the current Tokio runtime's blocking pool and
+    /// return an [`AsyncSearch`](crate::asynch::AsyncSearch): a stream of
+    /// matched entries, then the search's [`Results`] from
+    /// [`finish()`](crate::asynch::AsyncSearch::finish).
+    ///
+    /// `.collect_paths()` and `.collect_entries()` have no effect — every
+    /// match is handed to the stream by value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` for the same fatal configuration errors as
+    /// [`run()`](Self::run), or [`ParexError::ThreadPool`] when called
+    /// outside a Tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn run_async(self) -> Result<crate::asynch::AsyncSearch, ParexError> {
+        crate::asynch::AsyncSearch::spawn(self.into_options()?)
+    }
+
+    /// Walk the source and check every matched file against `manifest`.
+    ///
+    /// Matched regular files are handed to a pool of `.threads()` hashing
+    /// workers while the walk continues, so hashing overlaps with traversal.
+    /// `hasher` computes the hash of one entry; its output is compared with
+    /// the manifest as an opaque string. Any sink set with `.sink()` still
+    /// sees every match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use parex::verify::Manifest;
+    /// # use parex::{Source, Entry, EntryKind, ParexError};
+    /// # use parex::engine::WalkConfig;
+    /// # struct Files(Vec<&'static str>);
+    /// # impl Source for Files {
+    /// #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+    /// #         let v: Vec<_> = self.0.iter().map(|n| Ok(Entry::new(n, EntryKind::File, 0))).collect();
+    /// #         Box::new(v.into_iter())
+    /// #     }
+    /// # }
+    ///
+    /// let manifest: Manifest = [("a.txt", "5"), ("b.txt", "5"), ("gone.txt", "8")]
+    ///     .into_iter()
+    ///     .collect();
+    ///
+    /// // A stand-in "hash": the length of the path
+    /// let report = parex::search()
+    ///     .source(Files(vec!["a.txt", "b.txt", "new.txt"]))
+    ///     .verify(manifest, |e| Ok(e.path.as_os_str().len().to_string()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(report.verified, 2);
+    /// assert_eq!(report.missing, vec![std::path::PathBuf::from("gone.txt")]);
+    /// assert_eq!(report.extra, vec![std::path::PathBuf::from("new.txt")]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` for the same fatal configuration errors as
+    /// [`run()`](Self::run), or if the hashing threads cannot be spawned.
+    pub fn verify(
+        mut self,
+        manifest: Manifest,
+        hasher: impl Fn(&Entry) -> Result<String, ParexError> + Send + Sync + 'static,
+    ) -> Result<Verification, ParexError> {
+        let (pool, sink) = Pool::spawn(
+            manifest,
+            Arc::new(hasher),
+            self.thread_count(),
+            self.sink.take(),
+        )?;
+        self.sink = Some(Box::new(sink));
+
+        // The engine drops the sink when the walk ends, which lets the pool drain
+        let results = run(self.into_options()?);
+        Ok(pool.finish(results))
+    }
+
+    /// The thread count `.threads()` set, or every logical core.
+    fn thread_count(&self) -> usize {
+        self.threads.unwrap_or_else(num_cpus)
+    }
+
+    /// Validate the configuration and hand it over to the engine.
+    #[cfg_attr(not(feature = "power"), allow(unused_mut))]
+    fn into_options(mut self) -> Result<EngineOptions, ParexError> {
+        if let Some(err) = self.invalid_pattern.take().or(self.invalid_config.take()) {
+            return Err(err);
+        }
+
+        #[cfg(feature = "power")]
+        let defer_on_power = match self.energy_policy {
+            Some(crate::power::EnergyPolicy::Defer(poll)) => Some(poll),
+            Some(crate::power::EnergyPolicy::Slow) if crate::power::detect().constrained() => {
+                self.threads = Some(1);
+                self.max_dir_reads = Some(1);
+                None
+            }
+            _ => None,
+        };
+
+        #[cfg(feature = "otel")]
+        let trace = crate::otel::Trace::new(
+            self.trace_context
+                .take()
+                .unwrap_or_else(opentelemetry::Context::current),
+            &self.explain(),
+        );
+
+        let audit = if self.on_audit.is_some() || !self.notifiers.is_empty() {
+            Some(Audit {
+                plan: self.explain(),
+                requester: self.requester,
+                pattern: self.pattern,
+                observer: self.on_audit,
+                notifiers: self.notifiers,
+            })
+        } else {
+            None
+        };
+
         let source = self
             .source
             .ok_or_else(|| ParexError::InvalidSource("no source provided".into()))?;
 
-        let matcher: Arc<dyn Matcher> = match self.matcher {
-            Some(m) => Arc::from(m),
-            None => Arc::new(AllMatcher),
+        let mut predicates: Vec<Box<dyn Matcher>> =
+            self.filters.into_iter().map(|(f, _)| f).collect();
+        if self.skip_common_bloat {
+            predicates.push(Box::new(SkipDirs::common()));
+        }
+        #[cfg(feature = "glob")]
+        if !self.include_globs.is_empty() || !self.exclude_globs.is_empty() {
+            let mut globs =
+                crate::matchers::GlobMatcher::new(&self.include_globs, &self.exclude_globs)?;
+            if let Some(root) = source.root() {
+                globs = globs.root(root);
+            }
+            predicates.push(Box::new(globs));
+        }
+        if let Some(m) = self.matcher {
+            predicates.insert(0, m);
+        }
+        // Cheapest first, so expensive checks only see entries the cheap ones let through
+        predicates.sort_by_key(|p| p.cost());
+
+        let matcher: Arc<dyn Matcher> = match predicates.len() {
+            0 => Arc::new(AllMatcher),
+            1 => Arc::from(predicates.remove(0)),
+            _ => Arc::new(predicates.into_iter().collect::<AndMatcher>()),
+        };
+
+        #[cfg(feature = "signals")]
+        let signal_guard = if self.cancel_on_signal {
+            Some(crate::signals::SignalGuard::register(&self.cancel)?)
+        } else {
+            None
         };
 
-        let opts = EngineOptions {
+        let special_mounts = if self.traverse_special_mounts {
+            None
+        } else {
+            Some(MountPoints::new(self.special_mounts.mount_points())).filter(|m| !m.is_empty())
+        };
+
+        #[cfg(all(target_os = "linux", feature = "cgroup"))]
+        let cgroup_guard = self
+            .io_cgroup
+            .as_ref()
+            .map(crate::cgroup::IoCgroup::enter)
+            .transpose()?;
+
+        Ok(EngineOptions {
             config: WalkConfig {
-                threads: self.threads,
+                threads: self.threads.unwrap_or_else(num_cpus),
                 max_depth: self.max_depth,
                 limit: self.limit,
+                cancel: self.cancel,
+                exclude: self.exclude,
+                special_mounts,
+                matcher: Arc::clone(&matcher),
+                dir_reads: self
+                    .max_dir_reads
+                    .map_or_else(DirReadLimit::unlimited, DirReadLimit::new),
+                metadata_mode: self.metadata_mode,
+                shuffle_seed: self.shuffle_seed,
+                warnings: Arc::new(Warnings::new(self.collect_warnings, self.on_warning)),
+                yield_points: YieldPoints::new(self.yield_every, self.on_yield),
             },
             source,
             matcher,
             collect_paths: self.collect_paths,
+            collect_entries: self.collect_entries,
             collect_errors: self.collect_errors,
-        };
-
-        Ok(run(opts))
+            sink: self.sink,
+            on_matches: self.on_matches,
+            overlay: self.overlay,
+            rerank: self.rerank,
+            scored: self.scored,
+            top_k: self.top_k,
+            tuning: self.tuning,
+            debounce: self.debounce,
+            accurate_stats: self.accurate_stats,
+            dir_summaries: self.dir_summaries,
+            depth_histogram: self.depth_histogram,
+            profile: self.profile,
+            slowest_dirs: self.slowest_dirs,
+            stat_ahead: self.stat_ahead,
+            #[cfg(feature = "prefetch")]
+            prefetch: self.prefetch,
+            audit,
+            rules: self.rules,
+            checkpoint: self.checkpoint,
+            #[cfg(feature = "otel")]
+            trace,
+            #[cfg(feature = "power")]
+            defer_on_power,
+            #[cfg(all(target_os = "linux", feature = "cgroup"))]
+            cgroup_guard,
+            #[cfg(feature = "signals")]
+            signal_guard,
+        })
     }
 }
 
+// ---------------------------------------------------------------------------
+// MatchCase
+// ---------------------------------------------------------------------------
+
+/// How [`SearchBuilder::matching`] compares letter case.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::MatchCase;
+///
+/// // "readme" finds README.md; "README" finds only README.md, not readme.txt
+/// # let _ =
+/// parex::search().matching("README").match_case(MatchCase::Smart);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchCase {
+    /// `"invoice"` matches `Invoice.pdf` and `INVOICE.PDF`.
+    #[default]
+    Insensitive,
+
+    /// `"invoice"` matches `invoice.pdf` only.
+    Sensitive,
+
+    /// Case-insensitive unless the pattern contains an uppercase letter,
+    /// as in ripgrep and fd.
+    Smart,
+}
+
 // ---------------------------------------------------------------------------
 // Built-in matchers
 // ---------------------------------------------------------------------------
 
-/// Case-insensitive substring matcher — zero allocation per entry.
+/// Substring matcher over file names, case-insensitive unless
+/// [`MatchCase`] says otherwise.
 ///
-/// Pattern is pre-lowercased at construction time. Matching uses a byte-level
-/// sliding window with `to_ascii_lowercase()` per byte — no heap allocation
-/// in the hot path.
+/// Nothing is allocated per entry. The pattern is folded to lowercase at
+/// construction time. For ASCII names and patterns — the common case —
+/// `memchr2` scans for either case of the pattern's first byte using SIMD,
+/// and only those candidates are compared in full. Other names are folded
+/// one character at a time as they are compared, with Unicode rules, so
+/// `"ärger"` still finds `ÄRGER.txt`. Case-sensitive matching is a plain
+/// `memmem` search.
 struct SubstringMatcher {
-    pattern: Vec<u8>,
+    pattern: String,
+    case_sensitive: bool,
+}
+
+impl SubstringMatcher {
+    fn new(pattern: &str, case: MatchCase) -> Self {
+        let case_sensitive = match case {
+            MatchCase::Insensitive => false,
+            MatchCase::Sensitive => true,
+            MatchCase::Smart => pattern.chars().any(char::is_uppercase),
+        };
+        let pattern = if case_sensitive {
+            pattern.to_owned()
+        } else {
+            pattern.chars().flat_map(char::to_lowercase).collect()
+        };
+        Self {
+            pattern,
+            case_sensitive,
+        }
+    }
 }
 
 impl Matcher for SubstringMatcher {
-    fn is_match(&self, entry: &crate::entry::Entry) -> bool {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
         if self.pattern.is_empty() {
             return true;
         }
 
-        let name = entry
-            .path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
-
-        let name = name.as_bytes();
-        let pat = &self.pattern;
-
-        if pat.len() > name.len() {
-            return false;
+        let name = ctx.name();
+        if self.case_sensitive {
+            memchr::memmem::find(name.as_bytes(), self.pattern.as_bytes()).is_some()
+        } else if name.is_ascii() && self.pattern.is_ascii() {
+            contains_ascii_lowercase(name.as_bytes(), self.pattern.as_bytes())
+        } else {
+            contains_lowercase(name, &self.pattern)
         }
+    }
 
-        name.windows(pat.len()).any(|w| {
-            w.iter()
-                .zip(pat.iter())
-                .all(|(a, b)| a.to_ascii_lowercase() == *b)
-        })
+    fn cost(&self) -> Cost {
+        Cost::Name
     }
 }
 
+/// Whether `haystack`, lowercased a character at a time, contains `needle`.
+///
+/// `needle` must be lowercased the same way.
+fn contains_lowercase(haystack: &str, needle: &str) -> bool {
+    haystack.char_indices().any(|(i, _)| {
+        let mut folded = haystack[i..].chars().flat_map(char::to_lowercase);
+        needle.chars().all(|c| folded.next() == Some(c))
+    })
+}
+
+/// Whether `haystack` contains `needle`, ignoring ASCII case.
+///
+/// `needle` must be non-empty and already lowercase.
+fn contains_ascii_lowercase(haystack: &[u8], needle: &[u8]) -> bool {
+    let Some(last_start) = haystack.len().checked_sub(needle.len()) else {
+        return false;
+    };
+    let (first, rest) = (needle[0], &needle[1..]);
+
+    memchr::memchr2_iter(first, first.to_ascii_uppercase(), &haystack[..=last_start])
+        .any(|i| haystack[i + 1..i + needle.len()].eq_ignore_ascii_case(rest))
+}
+
 /// Matches every entry. Used when no matcher is specified.
-struct AllMatcher;
+pub(crate) struct AllMatcher;
 
 impl Matcher for AllMatcher {
-    fn is_match(&self, _entry: &crate::entry::Entry) -> bool {
+    fn is_match(&self, _entry: &Entry) -> bool {
         true
     }
+
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -0,0 +1,150 @@
+//! Warm-start caching for sources that enumerate directories.
+//!
+//! parex never reads directories itself — that stays in the [`Source`](crate::Source).
+//! [`DirCache`] gives sources a shared place to remember what a directory
+//! contained, keyed by path and modification time, so consecutive searches
+//! over the same tree can skip `readdir` on directories that have not changed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use crate::entry::EntryKind;
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// DirCache
+// ---------------------------------------------------------------------------
+
+/// An opt-in, in-memory cache of directory listings shared across searches.
+///
+/// Cheap to clone — all clones share the same storage, so a source can keep
+/// one clone while the application holds another to [`clear()`](Self::clear)
+/// it. Listings are stored with the directory's modification time; a lookup
+/// with a different mtime treats the entry as stale and evicts it.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::cache::{DirCache, DirChild};
+/// use parex::EntryKind;
+/// use std::time::SystemTime;
+///
+/// let cache = DirCache::new();
+/// let mtime = SystemTime::UNIX_EPOCH;
+///
+/// let children = cache.get_or_read("/data".as_ref(), mtime, || {
+///     Ok(vec![DirChild { path: "/data/a.txt".into(), kind: EntryKind::File }])
+/// }).unwrap();
+/// assert_eq!(children.len(), 1);
+///
+/// // Same mtime — served from the cache, the closure is not called.
+/// let again = cache.get_or_read("/data".as_ref(), mtime, || unreachable!()).unwrap();
+/// assert_eq!(again.len(), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct DirCache {
+    dirs: Arc<RwLock<HashMap<PathBuf, CachedDir>>>,
+}
+
+/// A single child recorded in a cached directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirChild {
+    /// Full path to the child.
+    pub path: PathBuf,
+
+    /// What kind of entry the child is.
+    pub kind: EntryKind,
+}
+
+struct CachedDir {
+    mtime: SystemTime,
+    children: Arc<[DirChild]>,
+}
+
+impl DirCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached listing for `dir`, if present and recorded at `mtime`.
+    ///
+    /// A listing recorded at a different mtime is stale — it is evicted and
+    /// `None` is returned.
+    pub fn get(&self, dir: &Path, mtime: SystemTime) -> Option<Arc<[DirChild]>> {
+        {
+            let dirs = self.dirs.read().unwrap_or_else(|e| e.into_inner());
+            match dirs.get(dir) {
+                Some(cached) if cached.mtime == mtime => {
+                    return Some(Arc::clone(&cached.children));
+                }
+                Some(_) => {}
+                None => return None,
+            }
+        }
+
+        self.dirs
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(dir);
+        None
+    }
+
+    /// Record the listing of `dir` as of `mtime`, replacing any previous one.
+    pub fn insert(
+        &self,
+        dir: PathBuf,
+        mtime: SystemTime,
+        children: Vec<DirChild>,
+    ) -> Arc<[DirChild]> {
+        let children: Arc<[DirChild]> = children.into();
+        self.dirs.write().unwrap_or_else(|e| e.into_inner()).insert(
+            dir,
+            CachedDir {
+                mtime,
+                children: Arc::clone(&children),
+            },
+        );
+        children
+    }
+
+    /// Return the cached listing for `dir`, or call `read` and cache its result.
+    ///
+    /// Errors from `read` are passed through and nothing is cached.
+    pub fn get_or_read(
+        &self,
+        dir: &Path,
+        mtime: SystemTime,
+        read: impl FnOnce() -> Result<Vec<DirChild>, ParexError>,
+    ) -> Result<Arc<[DirChild]>, ParexError> {
+        if let Some(children) = self.get(dir, mtime) {
+            return Ok(children);
+        }
+        Ok(self.insert(dir.to_path_buf(), mtime, read()?))
+    }
+
+    /// Drop the cached listing for `dir`.
+    pub fn invalidate(&self, dir: &Path) {
+        self.dirs
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(dir);
+    }
+
+    /// Drop every cached listing.
+    pub fn clear(&self) {
+        self.dirs.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Number of directories currently cached.
+    pub fn len(&self) -> usize {
+        self.dirs.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether the cache holds no listings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,230 @@
+//! Hard IO limits for scans through a Linux cgroup v2 `io` controller.
+//!
+//! Enabled with the `cgroup` feature, on Linux. Cooperative throttling
+//! such as [`max_dir_reads`](crate::SearchBuilder::max_dir_reads) trusts the
+//! source; a cgroup is enforced by the kernel. The platform operator creates
+//! and delegates the cgroup, the application opens it as an [`IoCgroup`],
+//! and [`SearchBuilder::io_cgroup`](crate::SearchBuilder::io_cgroup) runs a
+//! search inside it.
+//!
+//! The `io` controller is a domain controller: the kernel charges IO to
+//! processes, not threads. While a search runs in an `IoCgroup`, the whole
+//! process is a member, and its other IO counts against the same limits.
+//! Applications that must not be slowed down should run their background
+//! scans in a helper process. Windows Job objects have no equivalent IO
+//! limits and are not supported.
+//!
+//! ```rust,no_run
+//! use parex::cgroup::{IoCgroup, IoMax};
+//!
+//! let cgroup = IoCgroup::open("/sys/fs/cgroup/indexer.slice/scan")?;
+//! let device = IoCgroup::device_of("/home")?;
+//! cgroup.limit(device, IoMax { read_bps: Some(20 << 20), ..IoMax::default() })?;
+//! # struct Files;
+//! # impl parex::Source for Files {
+//! #     fn walk(&self, _: &parex::engine::WalkConfig)
+//! #         -> Box<dyn Iterator<Item = Result<parex::Entry, parex::ParexError>>> {
+//! #         Box::new(std::iter::empty())
+//! #     }
+//! # }
+//!
+//! let results = parex::search().source(Files).io_cgroup(cgroup).run()?;
+//! # Ok::<(), parex::ParexError>(())
+//! ```
+
+use std::fs;
+use std::io;
+use std::os::linux::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// IoCgroup
+// ---------------------------------------------------------------------------
+
+/// A cgroup v2 directory whose `io` controller is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoCgroup {
+    path: PathBuf,
+}
+
+/// Limits for one block device, written to `io.max`. `None` is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoMax {
+    /// Bytes read per second.
+    pub read_bps: Option<u64>,
+
+    /// Bytes written per second.
+    pub write_bps: Option<u64>,
+
+    /// Read operations per second.
+    pub read_iops: Option<u64>,
+
+    /// Write operations per second.
+    pub write_iops: Option<u64>,
+}
+
+impl IoCgroup {
+    /// Use the cgroup at `path`, typically under `/sys/fs/cgroup`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if `path` is not a cgroup v2 directory or
+    /// its `io` controller is not enabled — the parent must list `io` in
+    /// `cgroup.subtree_control`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ParexError> {
+        let path = path.into();
+        let controllers = path.join("cgroup.controllers");
+        let list = fs::read_to_string(&controllers).map_err(|e| io_err(&controllers, e))?;
+        if !list.split_whitespace().any(|c| c == "io") {
+            return Err(io_err(
+                &controllers,
+                io::Error::other("the io controller is not enabled for this cgroup"),
+            ));
+        }
+        Ok(Self { path })
+    }
+
+    /// The cgroup directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Set the limits for the block device `(major, minor)`, replacing any
+    /// previous ones for it. Needs write access to the cgroup's `io.max`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if the kernel rejects the limits.
+    pub fn limit(&self, device: (u32, u32), max: IoMax) -> Result<(), ParexError> {
+        let value = |v: Option<u64>| v.map_or_else(|| "max".to_string(), |v| v.to_string());
+        let line = format!(
+            "{}:{} rbps={} wbps={} riops={} wiops={}\n",
+            device.0,
+            device.1,
+            value(max.read_bps),
+            value(max.write_bps),
+            value(max.read_iops),
+            value(max.write_iops),
+        );
+        let file = self.path.join("io.max");
+        fs::write(&file, line).map_err(|e| io_err(&file, e))
+    }
+
+    /// The `(major, minor)` number of the device holding `path`, for
+    /// [`limit`](Self::limit).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if `path` cannot be stat'd.
+    pub fn device_of(path: impl AsRef<Path>) -> Result<(u32, u32), ParexError> {
+        let path = path.as_ref();
+        let dev = fs::metadata(path).map_err(|e| io_err(path, e))?.st_dev();
+        // The glibc `major()` / `minor()` encoding
+        let major = ((dev >> 32) & 0xffff_f000) | ((dev >> 8) & 0x0fff);
+        let minor = ((dev >> 12) & 0xffff_ff00) | (dev & 0x00ff);
+        Ok((major as u32, minor as u32))
+    }
+
+    /// Move the process into this cgroup until the guard drops.
+    pub(crate) fn enter(&self) -> Result<CgroupGuard, ParexError> {
+        let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+        match &mut *active {
+            Some(a) if a.cgroup == self.path => a.searches += 1,
+            Some(a) => {
+                return Err(io_err(
+                    &self.path,
+                    io::Error::other(format!(
+                        "another search holds the process in {}",
+                        a.cgroup.display()
+                    )),
+                ));
+            }
+            None => {
+                let previous = current_cgroup()?;
+                join(&self.path)?;
+                *active = Some(Active {
+                    cgroup: self.path.clone(),
+                    previous,
+                    searches: 1,
+                });
+            }
+        }
+        Ok(CgroupGuard(()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Membership
+// ---------------------------------------------------------------------------
+
+/// The cgroup searches have moved the process into, shared by every search
+/// in flight: the first to start moves the process, the last to finish
+/// moves it back.
+static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+
+struct Active {
+    cgroup: PathBuf,
+    previous: PathBuf,
+    searches: usize,
+}
+
+/// Keeps the process in a search's cgroup for as long as it lives.
+pub(crate) struct CgroupGuard(());
+
+impl Drop for CgroupGuard {
+    fn drop(&mut self) {
+        let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(a) = &mut *active {
+            a.searches -= 1;
+            if a.searches == 0 {
+                // Nowhere to report a failure; the process stays capped
+                let _ = join(&a.previous);
+                *active = None;
+            }
+        }
+    }
+}
+
+fn join(cgroup: &Path) -> Result<(), ParexError> {
+    let procs = cgroup.join("cgroup.procs");
+    fs::write(&procs, std::process::id().to_string()).map_err(|e| io_err(&procs, e))
+}
+
+/// The cgroup v2 directory the process is in now.
+fn current_cgroup() -> Result<PathBuf, ParexError> {
+    let membership = Path::new("/proc/self/cgroup");
+    let relative = fs::read_to_string(membership)
+        .map_err(|e| io_err(membership, e))?
+        .lines()
+        .find_map(|l| l.strip_prefix("0::").map(str::to_owned));
+
+    let mounts = Path::new("/proc/self/mountinfo");
+    // Fields: id, parent, dev, root, mount point, options, [optional...], -, fstype, ...
+    let mount = fs::read_to_string(mounts)
+        .map_err(|e| io_err(mounts, e))?
+        .lines()
+        .find_map(|l| {
+            let (left, right) = l.split_once(" - ")?;
+            right
+                .starts_with("cgroup2 ")
+                .then(|| left.split(' ').nth(4).map(PathBuf::from))?
+        });
+
+    match (mount, relative) {
+        (Some(mount), Some(relative)) => Ok(mount.join(relative.trim_start_matches('/'))),
+        _ => Err(io_err(
+            membership,
+            io::Error::other("the process is not in a cgroup v2 hierarchy"),
+        )),
+    }
+}
+
+fn io_err(path: &Path, source: io::Error) -> ParexError {
+    ParexError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
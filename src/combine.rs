@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use crate::context::MatchContext;
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// AndMatcher
+// ---------------------------------------------------------------------------
+
+/// Matches when every one of its matchers matches, checking them in order
+/// and stopping at the first that does not.
+///
+/// With no matchers it matches every entry. A directory is pruned if any
+/// of the matchers prunes it. The [`MatchContext`] is shared, so names and
+/// metadata are derived once for all of them.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::{AndMatcher, NotMatcher, OrMatcher, SkipDirs};
+/// # use parex::{Entry, Matcher};
+/// # struct Ext(&'static str);
+/// # impl Matcher for Ext {
+/// #     fn is_match(&self, e: &Entry) -> bool { e.path.extension().is_some_and(|x| x == self.0) }
+/// # }
+///
+/// // Rust or TOML files outside `target/`
+/// let sources = AndMatcher::new()
+///     .and(OrMatcher::new().or(Ext("rs")).or(Ext("toml")))
+///     .and(SkipDirs::new(["target"]));
+/// # let _ =
+/// parex::search().with_matcher(sources);
+///
+/// // The same with builder shorthand, keeping lock files out
+/// # let _ =
+/// parex::search()
+///     .any_of([Box::new(Ext("rs")) as Box<dyn Matcher>, Box::new(Ext("toml"))])
+///     .excluding(Ext("lock"))
+///     .filter(SkipDirs::new(["target"]));
+/// # let _ = NotMatcher::new(Ext("lock"));
+/// ```
+#[derive(Default)]
+pub struct AndMatcher(Vec<Box<dyn Matcher>>);
+
+impl AndMatcher {
+    /// An empty conjunction, which matches everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also require `m` to match.
+    pub fn and<M: Matcher + 'static>(mut self, m: M) -> Self {
+        self.0.push(Box::new(m));
+        self
+    }
+}
+
+impl FromIterator<Box<dyn Matcher>> for AndMatcher {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Matcher>>>(matchers: I) -> Self {
+        Self(matchers.into_iter().collect())
+    }
+}
+
+impl Matcher for AndMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        self.0.iter().all(|m| m.is_match_ctx(entry, ctx))
+    }
+
+    /// The first error ends the check; later matchers are not tried.
+    fn try_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> Result<bool, ParexError> {
+        for m in &self.0 {
+            if !m.try_match_ctx(entry, ctx)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn cost(&self) -> Cost {
+        self.0.iter().map(|m| m.cost()).max().unwrap_or(Cost::Name)
+    }
+
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        self.0.iter().any(|m| m.prunes_dir(dir))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OrMatcher
+// ---------------------------------------------------------------------------
+
+/// Matches when any one of its matchers matches, checking them in order
+/// and stopping at the first that does.
+///
+/// With no matchers it matches nothing. A directory is pruned only if
+/// every matcher prunes it.
+#[derive(Default)]
+pub struct OrMatcher(Vec<Box<dyn Matcher>>);
+
+impl OrMatcher {
+    /// An empty disjunction, which matches nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also accept entries `m` matches.
+    pub fn or<M: Matcher + 'static>(mut self, m: M) -> Self {
+        self.0.push(Box::new(m));
+        self
+    }
+}
+
+impl FromIterator<Box<dyn Matcher>> for OrMatcher {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Matcher>>>(matchers: I) -> Self {
+        Self(matchers.into_iter().collect())
+    }
+}
+
+impl Matcher for OrMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        self.0.iter().any(|m| m.is_match_ctx(entry, ctx))
+    }
+
+    /// An entry a later matcher accepts still matches after an earlier one
+    /// failed; otherwise the first error is returned.
+    fn try_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> Result<bool, ParexError> {
+        let mut first_err = None;
+        for m in &self.0 {
+            match m.try_match_ctx(entry, ctx) {
+                Ok(true) => return Ok(true),
+                Ok(false) => {}
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        first_err.map_or(Ok(false), Err)
+    }
+
+    fn cost(&self) -> Cost {
+        self.0.iter().map(|m| m.cost()).max().unwrap_or(Cost::Name)
+    }
+
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        self.0.iter().all(|m| m.prunes_dir(dir))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// NotMatcher
+// ---------------------------------------------------------------------------
+
+/// Matches exactly the entries its matcher does not.
+///
+/// Never prunes directories: a matcher pruning a subtree says nothing
+/// below it matches, which means everything below it matches the negation.
+pub struct NotMatcher(Box<dyn Matcher>);
+
+impl NotMatcher {
+    /// Match whatever `m` rejects.
+    pub fn new<M: Matcher + 'static>(m: M) -> Self {
+        Self(Box::new(m))
+    }
+}
+
+impl Matcher for NotMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        !self.0.is_match_ctx(entry, ctx)
+    }
+
+    fn try_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> Result<bool, ParexError> {
+        self.0.try_match_ctx(entry, ctx).map(|matched| !matched)
+    }
+
+    fn cost(&self) -> Cost {
+        self.0.cost()
+    }
+}
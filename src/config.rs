@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::builder::{MatchCase, SearchBuilder};
+use crate::engine::{EngineTuning, MetadataMode};
+use crate::mounts::SpecialMounts;
+use crate::rules::Rule;
+
+// ---------------------------------------------------------------------------
+// SearchConfig
+// ---------------------------------------------------------------------------
+
+/// The structured settings of a [`SearchBuilder`], as plain data that can
+/// be saved, shared, and turned back into a builder — a named search a user
+/// built in a UI and wants to run again.
+///
+/// Taken with [`SearchBuilder::to_config`] and applied with
+/// [`SearchBuilder::from_config`]. With the `serde` feature it serializes
+/// to any serde format; fields missing from the input take their defaults,
+/// so configurations saved by older versions still load.
+///
+/// Captured: the [`.matching()`] and `.matching_regex()` patterns, the
+/// include and exclude globs, and every setting below. Not
+/// captured, so set them again on the rebuilt builder:
+///
+/// - the source, sinks, [`.on_matches()`] callbacks, and overlays;
+/// - warning and audit observers, notifiers, alert thresholds, and
+///   [`.on_yield()`];
+/// - the cancellation token;
+/// - feature-gated settings — `.prefetch()`, `.cancel_on_signal()`,
+///   `.energy_policy()`, `.io_cgroup()`, and `.trace_context()` — so a
+///   saved configuration loads in a build with any set of features.
+///
+/// Settings that decide what matches or how results are ranked but are
+/// code rather than data — matchers passed to `.with_matcher()`,
+/// `.filter()` and its shorthands, `.rerank()`, and `.rank_by_score()` —
+/// make [`to_config`](SearchBuilder::to_config) return an error instead.
+///
+/// [`.matching()`]: SearchBuilder::matching
+/// [`.on_matches()`]: SearchBuilder::on_matches
+/// [`.on_yield()`]: SearchBuilder::on_yield
+///
+/// # Example
+///
+/// ```rust
+/// use parex::{SearchBuilder, SearchConfig, SortOrder};
+///
+/// let saved = parex::search()
+///     .matching("invoice")
+///     .limit(10)
+///     .sort(SortOrder::Natural)
+///     .to_config()
+///     .unwrap();
+/// assert_eq!(saved.pattern.as_deref(), Some("invoice"));
+/// assert_eq!(saved.threads, None);
+///
+/// let again = SearchBuilder::from_config(saved.clone());
+/// assert_eq!(again.to_config().unwrap(), saved);
+/// # let _ = SearchConfig::default();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[non_exhaustive]
+pub struct SearchConfig {
+    /// The [`matching`](SearchBuilder::matching) pattern.
+    pub pattern: Option<String>,
+    /// The [`matching_regex`](SearchBuilder::matching_regex) pattern.
+    /// Restoring it needs the `regex` feature, and it replaces `pattern`
+    /// if both are set.
+    pub regex: Option<String>,
+    /// See [`SearchBuilder::match_case`].
+    pub match_case: MatchCase,
+    /// See [`SearchBuilder::limit`].
+    pub limit: Option<usize>,
+    /// See [`SearchBuilder::accurate_stats`].
+    pub accurate_stats: bool,
+    /// See [`SearchBuilder::threads`]. `None` uses every logical core of
+    /// the machine running the search, not the one that saved it.
+    pub threads: Option<usize>,
+    /// See [`SearchBuilder::max_depth`].
+    pub max_depth: Option<usize>,
+    /// See [`SearchBuilder::max_dir_reads`].
+    pub max_dir_reads: Option<usize>,
+    /// See [`SearchBuilder::metadata_mode`].
+    pub metadata_mode: MetadataMode,
+    /// See [`SearchBuilder::shuffle`].
+    pub shuffle_seed: Option<u64>,
+    /// See [`SearchBuilder::exclude_paths`]. Sorted.
+    pub exclude_paths: Vec<PathBuf>,
+    /// The `.include_glob()` patterns, in the order they were added.
+    /// Restoring them needs the `glob` feature.
+    pub include_globs: Vec<String>,
+    /// The `.exclude_glob()` patterns, in the order they were added.
+    /// Restoring them needs the `glob` feature.
+    pub exclude_globs: Vec<String>,
+    /// See [`SearchBuilder::skip_common_bloat`].
+    pub skip_common_bloat: bool,
+    /// See [`SearchBuilder::special_mounts`].
+    pub special_mounts: SpecialMounts,
+    /// See [`SearchBuilder::traverse_special_mounts`].
+    pub traverse_special_mounts: bool,
+    /// See [`SearchBuilder::collect_paths`].
+    pub collect_paths: bool,
+    /// See [`SearchBuilder::collect_entries`].
+    pub collect_entries: bool,
+    /// See [`SearchBuilder::collect_errors`].
+    pub collect_errors: bool,
+    /// See [`SearchBuilder::collect_warnings`].
+    pub collect_warnings: bool,
+    /// See [`SearchBuilder::sort`], as a [`SortOrder::name`](crate::SortOrder::name).
+    pub sort: Option<String>,
+    /// See [`SearchBuilder::rule`].
+    pub rules: Vec<Rule>,
+    /// See [`SearchBuilder::requester`].
+    pub requester: Option<String>,
+    /// See [`SearchBuilder::debounce`].
+    pub debounce: Duration,
+    /// See [`SearchBuilder::top_k`].
+    pub top_k: Option<usize>,
+    /// See [`SearchBuilder::dir_summaries`].
+    pub dir_summaries: bool,
+    /// See [`SearchBuilder::depth_histogram`].
+    pub depth_histogram: bool,
+    /// See [`SearchBuilder::profile`].
+    pub profile: bool,
+    /// See [`SearchBuilder::slowest_dirs`].
+    pub slowest_dirs: usize,
+    /// See [`SearchBuilder::prefetch_metadata`].
+    pub prefetch_metadata: usize,
+    /// See [`SearchBuilder::checkpoint`].
+    pub checkpoint: Option<PathBuf>,
+    /// See [`SearchBuilder::yield_every`].
+    pub yield_every: usize,
+    /// See [`SearchBuilder::tuning`].
+    pub tuning: EngineTuning,
+}
+
+/// The settings of a fresh [`search()`](crate::search).
+impl Default for SearchConfig {
+    fn default() -> Self {
+        SearchBuilder::default().settings()
+    }
+}
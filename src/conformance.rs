@@ -0,0 +1,631 @@
+//! Contract checks for third-party [`Source`] and [`Matcher`] implementations.
+//!
+//! Enabled with the `test-util` feature. [`check_source`] walks a source
+//! several times under different [`WalkConfig`]s and reports every way it
+//! departs from what parex expects of sources — panics, inconsistent
+//! depths, ignored traversal limits, and nondeterministic output. Call it
+//! from a source crate's own tests:
+//!
+//! ```rust
+//! use parex::conformance::check_source;
+//! # use parex::{Source, Entry, EntryKind, ParexError};
+//! # use parex::engine::WalkConfig;
+//! # struct MySource;
+//! # impl Source for MySource {
+//! #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+//! #         Box::new(std::iter::empty())
+//! #     }
+//! # }
+//!
+//! check_source(&MySource).assert_ok();
+//! ```
+//!
+//! [`check_matcher`] does the same for matchers: it runs one over generated
+//! entries and flags panics, answers that change between calls or threads,
+//! and calls slow enough to stall a walk.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::context::MatchContext;
+use crate::engine::WalkConfig;
+use crate::entry::{Entry, EntryKind};
+use crate::exclude::ExclusionSet;
+use crate::traits::{Matcher, Source};
+
+// ---------------------------------------------------------------------------
+// Rule / Violation
+// ---------------------------------------------------------------------------
+
+/// A part of the source or matcher contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Rule {
+    /// `walk()` or its iterator panicked instead of yielding an error, or
+    /// a matcher panicked on an entry.
+    Panicked,
+
+    /// The same path was yielded more than once in one walk.
+    DuplicateEntry,
+
+    /// An entry's depth is not its parent's depth plus one.
+    DepthMismatch,
+
+    /// An entry was yielded below something that is not a directory.
+    ChildOfNonDir,
+
+    /// Entries deeper than [`WalkConfig::max_depth`] were yielded. The
+    /// engine drops them, but reading them was wasted IO.
+    IgnoresMaxDepth,
+
+    /// Entries below a directory [`WalkConfig::skip_dir`] ruled out were
+    /// yielded. The engine drops them, but reading them was wasted IO.
+    IgnoresSkipDir,
+
+    /// Two walks with the same configuration yielded different sets of
+    /// paths, or a matcher answered differently for the same entry.
+    Nondeterministic,
+
+    /// A matcher's `is_match` and `is_match_ctx` disagreed on an entry.
+    ContextMismatch,
+
+    /// A single matcher call took longer than the allowed latency.
+    SlowCall,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Panicked => "panicked",
+            Self::DuplicateEntry => "duplicate entry",
+            Self::DepthMismatch => "depth mismatch",
+            Self::ChildOfNonDir => "child of non-directory",
+            Self::IgnoresMaxDepth => "ignores max_depth",
+            Self::IgnoresSkipDir => "ignores skip_dir",
+            Self::Nondeterministic => "nondeterministic",
+            Self::ContextMismatch => "is_match / is_match_ctx mismatch",
+            Self::SlowCall => "slow call",
+        })
+    }
+}
+
+/// One departure from the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The rule that was broken.
+    pub rule: Rule,
+
+    /// The first offending path, when there is one.
+    pub path: Option<PathBuf>,
+
+    /// What was observed.
+    pub detail: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.detail)?;
+        if let Some(path) = &self.path {
+            write!(f, " ({})", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SourceReport
+// ---------------------------------------------------------------------------
+
+/// The outcome of [`check_source`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceReport {
+    /// Entries yielded by the unrestricted walk.
+    pub entries: usize,
+
+    /// Errors yielded by the unrestricted walk.
+    pub errors: usize,
+
+    /// Every rule the source broke, at most one per rule.
+    pub violations: Vec<Violation>,
+}
+
+impl SourceReport {
+    /// Whether the source met every rule.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Whether `rule` was broken.
+    pub fn violated(&self, rule: Rule) -> bool {
+        self.violations.iter().any(|v| v.rule == rule)
+    }
+
+    /// Panic with every violation listed, unless the source met every rule.
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        assert_no_violations("source", &self.violations);
+    }
+
+    fn violation(&mut self, rule: Rule, path: Option<&Path>, detail: String) {
+        push_violation(&mut self.violations, rule, path, detail);
+    }
+}
+
+#[track_caller]
+fn assert_no_violations(what: &str, violations: &[Violation]) {
+    if !violations.is_empty() {
+        let list: Vec<String> = violations.iter().map(|v| format!("  - {v}")).collect();
+        panic!("{what} broke the parex contract:\n{}", list.join("\n"));
+    }
+}
+
+/// Record a violation, keeping only the first per rule.
+fn push_violation(
+    violations: &mut Vec<Violation>,
+    rule: Rule,
+    path: Option<&Path>,
+    detail: String,
+) {
+    if !violations.iter().any(|v| v.rule == rule) {
+        violations.push(Violation {
+            rule,
+            path: path.map(Path::to_path_buf),
+            detail,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// check_source()
+// ---------------------------------------------------------------------------
+
+/// Walk `source` under several configurations and report contract violations.
+///
+/// Checks, in order:
+///
+/// - the walk never panics, and yields no path twice;
+/// - each entry's depth is its parent's plus one, whenever the parent was
+///   also yielded, and nothing is yielded below a file;
+/// - a second walk yields the same set of paths as the first;
+/// - with `max_depth` one short of the deepest entry, nothing deeper is
+///   yielded;
+/// - with a directory ruled out by `skip_dir`, nothing below it is yielded.
+///
+/// The source is walked in full several times, so keep test fixtures small.
+pub fn check_source<S: Source + ?Sized>(source: &S) -> SourceReport {
+    let mut report = SourceReport::default();
+
+    let Some(first) = walk(source, config(None, None), &mut report) else {
+        return report;
+    };
+    report.entries = first.entries.len();
+    report.errors = first.errors;
+
+    let mut seen: HashMap<&Path, &Entry> = HashMap::with_capacity(first.entries.len());
+    for entry in &first.entries {
+        if seen.insert(&entry.path, entry).is_some() {
+            report.violation(
+                Rule::DuplicateEntry,
+                Some(&entry.path),
+                "path yielded more than once".into(),
+            );
+        }
+    }
+
+    for entry in &first.entries {
+        let Some(parent) = entry.path.parent().and_then(|p| seen.get(p)) else {
+            continue;
+        };
+        if parent.kind == EntryKind::File {
+            report.violation(
+                Rule::ChildOfNonDir,
+                Some(&entry.path),
+                format!("parent {} is a file", parent.path.display()),
+            );
+        }
+        if entry.depth != parent.depth + 1 {
+            report.violation(
+                Rule::DepthMismatch,
+                Some(&entry.path),
+                format!(
+                    "depth {} under a parent at depth {}",
+                    entry.depth, parent.depth
+                ),
+            );
+        }
+    }
+
+    if let Some(second) = walk(source, config(None, None), &mut report) {
+        let a: HashSet<&Path> = seen.keys().copied().collect();
+        let b: HashSet<&Path> = second.entries.iter().map(|e| e.path.as_path()).collect();
+        if a != b {
+            let path = a.symmetric_difference(&b).next().copied();
+            report.violation(
+                Rule::Nondeterministic,
+                path,
+                format!("{} paths, then {}", a.len(), b.len()),
+            );
+        }
+    }
+
+    let deepest = first.entries.iter().map(|e| e.depth).max();
+    let shallowest = first.entries.iter().map(|e| e.depth).min();
+    if let (Some(deepest), Some(shallowest)) = (deepest, shallowest)
+        && deepest > shallowest
+    {
+        let limit = deepest - 1;
+        if let Some(run) = walk(source, config(Some(limit), None), &mut report)
+            && let Some(e) = run.entries.iter().find(|e| e.depth > limit)
+        {
+            report.violation(
+                Rule::IgnoresMaxDepth,
+                Some(&e.path),
+                format!("depth {} yielded with max_depth {limit}", e.depth),
+            );
+        }
+    }
+
+    // A directory with something below it, so skipping it is observable
+    let skippable = first
+        .entries
+        .iter()
+        .filter_map(|e| seen.get(e.path.parent()?))
+        .find(|p| p.kind == EntryKind::Dir);
+    if let Some(dir) = skippable {
+        let exclude = ExclusionSet::new([dir.path.clone()]);
+        if let Some(run) = walk(source, config(None, Some(exclude)), &mut report)
+            && let Some(e) = run
+                .entries
+                .iter()
+                .find(|e| e.path != dir.path && e.path.starts_with(&dir.path))
+        {
+            report.violation(
+                Rule::IgnoresSkipDir,
+                Some(&e.path),
+                format!("yielded below skipped {}", dir.path.display()),
+            );
+        }
+    }
+
+    report
+}
+
+struct Walked {
+    entries: Vec<Entry>,
+    errors: usize,
+}
+
+/// Drain one walk, recording a panic as a violation.
+fn walk<S: Source + ?Sized>(
+    source: &S,
+    config: WalkConfig,
+    report: &mut SourceReport,
+) -> Option<Walked> {
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        let mut walked = Walked {
+            entries: Vec::new(),
+            errors: 0,
+        };
+        for item in source.walk(&config) {
+            match item {
+                Ok(entry) => walked.entries.push(entry),
+                Err(_) => walked.errors += 1,
+            }
+        }
+        walked
+    }));
+
+    match outcome {
+        Ok(walked) => Some(walked),
+        Err(panic) => {
+            report.violation(Rule::Panicked, None, panic_message(&*panic));
+            None
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".into())
+}
+
+/// Walks everything on two threads; only `exclude` drives `skip_dir`.
+fn config(max_depth: Option<usize>, exclude: Option<ExclusionSet>) -> WalkConfig {
+    WalkConfig {
+        max_depth,
+        exclude,
+        ..WalkConfig::default().with_threads(2)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// check_matcher()
+// ---------------------------------------------------------------------------
+
+/// The outcome of [`check_matcher`] or [`MatcherCheck::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatcherReport {
+    /// Entries the matcher was run on.
+    pub entries: usize,
+
+    /// How many of them matched on the first pass.
+    pub matches: usize,
+
+    /// The slowest single call.
+    pub slowest: Duration,
+
+    /// Mean time per call.
+    pub mean: Duration,
+
+    /// Every rule the matcher broke, at most one per rule.
+    pub violations: Vec<Violation>,
+}
+
+impl MatcherReport {
+    /// Whether the matcher met every rule.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Whether `rule` was broken.
+    pub fn violated(&self, rule: Rule) -> bool {
+        self.violations.iter().any(|v| v.rule == rule)
+    }
+
+    /// Panic with every violation listed, unless the matcher met every rule.
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        assert_no_violations("matcher", &self.violations);
+    }
+}
+
+/// Settings for a matcher check. [`check_matcher`] uses the defaults.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::conformance::MatcherCheck;
+/// use parex::{Entry, Matcher};
+/// use std::time::Duration;
+///
+/// struct HasExtension;
+///
+/// impl Matcher for HasExtension {
+///     fn is_match(&self, entry: &Entry) -> bool {
+///         entry.path.extension().is_some()
+///     }
+/// }
+///
+/// let report = MatcherCheck::new()
+///     .max_latency(Duration::from_millis(1))
+///     .run(&HasExtension);
+/// report.assert_ok();
+/// assert!(report.matches > 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MatcherCheck {
+    entries: Vec<Entry>,
+    repeats: usize,
+    max_latency: Duration,
+}
+
+impl Default for MatcherCheck {
+    fn default() -> Self {
+        Self {
+            entries: generated_entries(),
+            repeats: 3,
+            max_latency: Duration::from_millis(50),
+        }
+    }
+}
+
+impl MatcherCheck {
+    /// A check over the generated entry set, three passes, and a 50 ms
+    /// per-call budget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check against `entries` instead of the generated set — for example,
+    /// paths from a fixture directory for a matcher that reads files.
+    pub fn entries(mut self, entries: Vec<Entry>) -> Self {
+        self.entries = entries;
+        self
+    }
+
+    /// Run every entry this many times. At least two, so nondeterminism
+    /// can show.
+    pub fn repeats(mut self, n: usize) -> Self {
+        self.repeats = n;
+        self
+    }
+
+    /// Flag any single call slower than `latency`.
+    pub fn max_latency(mut self, latency: Duration) -> Self {
+        self.max_latency = latency;
+        self
+    }
+
+    /// Check `matcher`.
+    ///
+    /// The first pass calls both `is_match` and `is_match_ctx` on each entry
+    /// and requires them to agree. Further passes, sequential and then from
+    /// two threads at once, must give the same answers. Every call is timed.
+    pub fn run<M: Matcher + ?Sized>(&self, matcher: &M) -> MatcherReport {
+        let mut report = MatcherReport {
+            entries: self.entries.len(),
+            ..MatcherReport::default()
+        };
+        let mut total = Duration::ZERO;
+        let mut calls = 0u32;
+
+        let mut timed = |report: &mut MatcherReport, entry: &Entry, ctx: bool| {
+            let start = Instant::now();
+            let outcome = catch_unwind(AssertUnwindSafe(|| {
+                if ctx {
+                    matcher.is_match_ctx(entry, &MatchContext::new(entry))
+                } else {
+                    matcher.is_match(entry)
+                }
+            }));
+            let elapsed = start.elapsed();
+            total += elapsed;
+            calls += 1;
+            report.slowest = report.slowest.max(elapsed);
+            if elapsed > self.max_latency {
+                push_violation(
+                    &mut report.violations,
+                    Rule::SlowCall,
+                    Some(&entry.path),
+                    format!("{elapsed:?} exceeds {:?}", self.max_latency),
+                );
+            }
+            outcome.map_err(|panic| {
+                push_violation(
+                    &mut report.violations,
+                    Rule::Panicked,
+                    Some(&entry.path),
+                    panic_message(&*panic),
+                );
+            })
+        };
+
+        // First pass: establish each answer, and compare with the ctx variant
+        let mut answers = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            let plain = timed(&mut report, entry, false);
+            let ctx = timed(&mut report, entry, true);
+            if let (Ok(a), Ok(b)) = (plain, ctx)
+                && a != b
+            {
+                push_violation(
+                    &mut report.violations,
+                    Rule::ContextMismatch,
+                    Some(&entry.path),
+                    format!("is_match returned {a}, is_match_ctx {b}"),
+                );
+            }
+            answers.push(plain.ok());
+        }
+        report.matches = answers.iter().filter(|a| **a == Some(true)).count();
+
+        for _ in 1..self.repeats.max(2) {
+            for (entry, expected) in self.entries.iter().zip(&answers) {
+                if let (Ok(got), Some(expected)) = (timed(&mut report, entry, true), expected)
+                    && got != *expected
+                {
+                    push_violation(
+                        &mut report.violations,
+                        Rule::Nondeterministic,
+                        Some(&entry.path),
+                        format!("returned {expected}, then {got}"),
+                    );
+                }
+            }
+        }
+        if calls > 0 {
+            report.mean = total / calls;
+        }
+
+        // The engine calls matchers from several threads at once
+        if !report.violated(Rule::Panicked) {
+            let concurrent = |entries: &[Entry]| -> Vec<bool> {
+                entries
+                    .iter()
+                    .map(|e| matcher.is_match_ctx(e, &MatchContext::new(e)))
+                    .collect()
+            };
+            let (a, b) = std::thread::scope(|scope| {
+                let a = scope.spawn(|| concurrent(&self.entries));
+                let b = concurrent(&self.entries);
+                (a.join(), b)
+            });
+            let expected: Vec<bool> = answers.iter().map(|a| a.unwrap_or(false)).collect();
+            if let Some(i) = (0..expected.len())
+                .find(|&i| a.as_ref().map_or(true, |a| a[i] != expected[i]) || b[i] != expected[i])
+            {
+                push_violation(
+                    &mut report.violations,
+                    Rule::Nondeterministic,
+                    Some(&self.entries[i].path),
+                    "answer changed when called from two threads at once".into(),
+                );
+            }
+        }
+
+        report
+    }
+}
+
+/// Check `matcher` with the default [`MatcherCheck`] settings.
+pub fn check_matcher<M: Matcher + ?Sized>(matcher: &M) -> MatcherReport {
+    MatcherCheck::new().run(matcher)
+}
+
+/// Entries covering the shapes matchers trip over: no extension, dotfiles,
+/// several dots, non-ASCII and mixed-case names, very long names, the root,
+/// and every [`EntryKind`]. None of the paths exist on disk, so
+/// metadata-reading matchers must cope with a failed `stat()`.
+fn generated_entries() -> Vec<Entry> {
+    const NAMES: [&str; 12] = [
+        "report.txt",
+        "README",
+        ".hidden",
+        "archive.tar.gz",
+        "UPPER.TXT",
+        "ärger.md",
+        "日本語.txt",
+        "with space.log",
+        "trailing.",
+        "a",
+        "invoice_2024-01.pdf",
+        "__long__",
+    ];
+    const KINDS: [EntryKind; 4] = [
+        EntryKind::File,
+        EntryKind::Dir,
+        EntryKind::Symlink,
+        EntryKind::Other,
+    ];
+
+    let root = PathBuf::from("/parex-conformance");
+    let mut entries = vec![Entry {
+        path: root.clone(),
+        kind: EntryKind::Dir,
+        depth: 0,
+        metadata: None,
+        tags: None,
+        source_id: None,
+        id: None,
+    }];
+    for (i, name) in NAMES.iter().enumerate() {
+        let name = if *name == "__long__" {
+            "x".repeat(255)
+        } else {
+            name.to_string()
+        };
+        for depth in 1..=3 {
+            let mut path = root.clone();
+            for d in 1..depth {
+                path.push(format!("dir{d}"));
+            }
+            path.push(&name);
+            entries.push(Entry {
+                path,
+                kind: KINDS[(i + depth) % KINDS.len()].clone(),
+                depth,
+                metadata: None,
+                tags: None,
+                source_id: None,
+                id: None,
+            });
+        }
+    }
+    entries
+}
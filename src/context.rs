@@ -0,0 +1,199 @@
+use std::cell::{Cell, OnceCell};
+use std::fs::Metadata;
+use std::path::Path;
+
+use crate::engine::MetadataMode;
+use crate::entry::{Entry, EntryId};
+use crate::error::ParexError;
+use crate::profile::Profiler;
+use crate::summary::DirSummary;
+use crate::warning::{Warning, Warnings};
+
+// ---------------------------------------------------------------------------
+// MatchContext
+// ---------------------------------------------------------------------------
+
+/// Per-entry derivations shared by every matcher that looks at the entry.
+///
+/// The engine creates one context per entry and passes it to
+/// [`Matcher::is_match_ctx`](crate::Matcher::is_match_ctx). Each derivation
+/// is computed on first use and cached, so when several matchers run on the
+/// same entry — a `.matching()` pattern plus `.filter()` predicates — the
+/// work is done once instead of once per matcher.
+pub struct MatchContext<'a> {
+    entry: &'a Entry,
+    lowercase_name: OnceCell<String>,
+    metadata: OnceCell<Option<Metadata>>,
+    metadata_mode: MetadataMode,
+    warnings: Option<&'a Warnings>,
+    profiler: Option<&'a Profiler>,
+    dir_summary: Option<&'a DirSummary>,
+    /// Set by a [`ScoredMatcher`](crate::rank::ScoredMatcher) that matched.
+    score: Cell<Option<f32>>,
+    /// The first error a default [`Matcher::is_match_ctx`](crate::Matcher::is_match_ctx)
+    /// could not return, until the surrounding `try_match_ctx` takes it.
+    error: Cell<Option<ParexError>>,
+}
+
+impl<'a> MatchContext<'a> {
+    /// A fresh context for `entry`. Nothing is computed until asked for.
+    ///
+    /// Metadata follows symlinks; see [`with_metadata_mode`](Self::with_metadata_mode).
+    pub fn new(entry: &'a Entry) -> Self {
+        Self::with_metadata_mode(entry, MetadataMode::Follow)
+    }
+
+    /// A fresh context that reads metadata with `mode`.
+    pub fn with_metadata_mode(entry: &'a Entry, mode: MetadataMode) -> Self {
+        Self {
+            entry,
+            lowercase_name: OnceCell::new(),
+            metadata: OnceCell::new(),
+            metadata_mode: mode,
+            warnings: None,
+            profiler: None,
+            dir_summary: None,
+            score: Cell::new(None),
+            error: Cell::new(None),
+        }
+    }
+
+    /// Time `stat()` calls into the search's profile.
+    pub(crate) fn with_profiler(mut self, profiler: &'a Profiler) -> Self {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Attach the summary of the directory this context describes.
+    pub(crate) fn with_dir_summary(mut self, summary: &'a DirSummary) -> Self {
+        self.dir_summary = Some(summary);
+        self
+    }
+
+    /// Route [`warn()`](Self::warn) to the search's warning channel.
+    pub(crate) fn with_warnings(mut self, warnings: &'a Warnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    /// Record the score a [`ScoredMatcher`](crate::rank::ScoredMatcher)
+    /// gave the entry, so the engine can carry it to `Results::ranked`.
+    pub(crate) fn set_score(&self, score: f32) {
+        self.score.set(Some(score));
+    }
+
+    /// The score recorded with [`set_score()`](Self::set_score), if any.
+    pub(crate) fn score(&self) -> Option<f32> {
+        self.score.get()
+    }
+
+    /// Hold a matcher error for [`take_error()`](Self::take_error), keeping
+    /// the first if several fail.
+    pub(crate) fn fail(&self, err: ParexError) {
+        let first = self.error.take().unwrap_or(err);
+        self.error.set(Some(first));
+    }
+
+    /// The error held by [`fail()`](Self::fail), if any.
+    pub(crate) fn take_error(&self) -> Option<ParexError> {
+        self.error.take()
+    }
+
+    /// The entry this context describes.
+    pub fn entry(&self) -> &'a Entry {
+        self.entry
+    }
+
+    /// The entry's file name, or `""` if it has none or is not valid UTF-8.
+    pub fn name(&self) -> &'a str {
+        self.entry
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+    }
+
+    /// [`name()`](Self::name) lowercased with Unicode rules. Cached.
+    pub fn lowercase_name(&self) -> &str {
+        self.lowercase_name
+            .get_or_init(|| self.name().to_lowercase())
+    }
+
+    /// The entry's path relative to the traversal root.
+    ///
+    /// Derived from [`Entry::depth`]: the last `depth` components of the
+    /// path, so it is exact for any source that follows the "root = 0"
+    /// convention and needs no allocation. Empty for the root itself.
+    pub fn relative_path(&self) -> &'a Path {
+        let path = self.entry.path.as_path();
+        path.ancestors()
+            .nth(self.entry.depth)
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+    }
+
+    /// The entry's metadata — the copy the source cached in
+    /// [`Entry::metadata`] if there is one, otherwise a `stat()` made on
+    /// first use and cached. `None` if the entry cannot be stat'ed.
+    ///
+    /// The `stat()` follows symlinks or not according to the context's
+    /// [`MetadataMode`]; a copy cached by the source is used as-is.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        if let Some(meta) = &self.entry.metadata {
+            return Some(meta);
+        }
+        self.metadata
+            .get_or_init(|| {
+                let start = std::time::Instant::now();
+                let meta = self.metadata_mode.metadata(&self.entry.path).ok();
+                if let Some(profiler) = self.profiler {
+                    profiler.stat(start.elapsed());
+                }
+                meta
+            })
+            .as_ref()
+    }
+
+    /// What the search found below this directory, in searches built with
+    /// [`dir_summaries`](crate::SearchBuilder::dir_summaries). `None` for
+    /// other entries and searches.
+    pub fn dir_summary(&self) -> Option<&'a DirSummary> {
+        self.dir_summary
+    }
+
+    /// The entry's [`stable_id`](Entry::stable_id), falling back to the
+    /// device and inode from [`metadata()`](Self::metadata) — a `stat()` on
+    /// first use when the source cached none.
+    pub fn stable_id(&self) -> Option<EntryId> {
+        self.entry
+            .id
+            .clone()
+            .or_else(|| self.metadata().and_then(EntryId::from_metadata))
+    }
+
+    /// Bytes actually allocated on disk for the entry, from the cached
+    /// [`metadata()`](Self::metadata).
+    ///
+    /// Smaller than the apparent size (`len()`) for sparse files, and
+    /// possibly larger for small files rounded up to whole blocks. Use this
+    /// rather than `len()` when reporting real disk usage.
+    #[cfg(unix)]
+    pub fn allocated_size(&self) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt;
+
+        // st_blocks is always in 512-byte units, whatever the filesystem block size
+        self.metadata().map(|m| m.blocks() * 512)
+    }
+
+    /// Report a [`Warning`] about this entry.
+    ///
+    /// Delivered to [`Results::warnings`](crate::Results::warnings) and any
+    /// [`on_warning`](crate::SearchBuilder::on_warning) observer. Dropped
+    /// when the context was not created by the engine or the search did not
+    /// ask for warnings.
+    pub fn warn(&self, warning: Warning) {
+        if let Some(warnings) = self.warnings {
+            warnings.emit(warning);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! Running saved searches on a schedule.
+//!
+//! Enabled with the `daemon` feature. A [`Daemon`] holds [`Job`]s — a saved
+//! [`SearchConfig`], how often to run it, and how many snapshots of its
+//! matches to keep — and runs each one when it falls due, so inventory and
+//! monitoring deployments need no cron wrapper around parex. Matches are
+//! saved as [`Snapshot`]s under a [`StateDir`], which also records when each
+//! job last ran: a restarted daemon carries on with the schedule instead of
+//! running every job at once. [`Daemon::reload`] swaps the jobs — their
+//! exclusions, tuning, or schedules — while the daemon runs, without
+//! dropping the caches its sources keep warm.
+//!
+//! A [`SearchConfig`] has no source, so the daemon is given a function that
+//! finishes each job's builder — adding the source and anything else that
+//! is code rather than data, such as notifiers.
+//!
+//! ```rust,no_run
+//! use parex::daemon::{Daemon, Job};
+//! use parex::engine::CancelToken;
+//! use parex::state::StateDir;
+//! use parex::SearchBuilder;
+//! use std::time::Duration;
+//! # use parex::{Source, Entry, ParexError};
+//! # use parex::engine::WalkConfig;
+//! # struct Files;
+//! # impl Source for Files {
+//! #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+//! #         Box::new(std::iter::empty())
+//! #     }
+//! # }
+//!
+//! let saved = parex::search().matching("invoice").to_config()?;
+//! let jobs = vec![Job::new("invoices", saved, Duration::from_secs(6 * 60 * 60)).keep(7)];
+//! let state = StateDir::for_app("ldx").expect("no cache directory");
+//! let daemon = Daemon::new(state, jobs, |_job: &Job, search: SearchBuilder| {
+//!     search.source(Files)
+//! })?;
+//!
+//! daemon.run(&CancelToken::default(), |run| {
+//!     if let Err(e) = &run.results {
+//!         eprintln!("{}: {e}", run.job);
+//!     }
+//! });
+//! # Ok::<(), ParexError>(())
+//! ```
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::builder::SearchBuilder;
+use crate::config::SearchConfig;
+use crate::engine::CancelToken;
+use crate::error::ParexError;
+use crate::results::{Completion, Results};
+use crate::snapshot::Snapshot;
+use crate::state::{StateDir, write_atomic};
+
+/// Snapshots a [`Job`] keeps unless told otherwise.
+pub const DEFAULT_KEEP: usize = 7;
+
+/// Longest [`Daemon::run`] sleeps before checking its cancel token.
+const POLL: Duration = Duration::from_secs(1);
+
+/// Directory of the state dir holding one subdirectory per job.
+const JOBS_DIR: &str = "jobs";
+
+/// File in a job's directory holding when it last started, in nanoseconds
+/// since the Unix epoch.
+const LAST_RUN: &str = "last_run";
+
+const SNAPSHOT_EXT: &str = "snapshot";
+
+// ---------------------------------------------------------------------------
+// Job
+// ---------------------------------------------------------------------------
+
+/// A saved search the [`Daemon`] runs every `every`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Job {
+    /// Names the job's directory in the state dir, so it must be a plain
+    /// file name, unique among the daemon's jobs.
+    pub name: String,
+
+    /// The search.
+    pub config: SearchConfig,
+
+    /// Time from the start of one run to the start of the next.
+    pub every: Duration,
+
+    /// How many snapshots of the job's matches to keep, newest first. `0`
+    /// saves none.
+    pub keep: usize,
+}
+
+impl Job {
+    /// A job running `config` every `every` and keeping
+    /// [`DEFAULT_KEEP`] snapshots.
+    pub fn new(name: impl Into<String>, config: SearchConfig, every: Duration) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            every,
+            keep: DEFAULT_KEEP,
+        }
+    }
+
+    /// Keep the last `n` snapshots.
+    pub fn keep(mut self, n: usize) -> Self {
+        self.keep = n;
+        self
+    }
+}
+
+/// One run of a [`Job`].
+#[non_exhaustive]
+pub struct Run {
+    /// The job's name.
+    pub job: String,
+
+    /// When the run started.
+    pub started: SystemTime,
+
+    /// The search's results, or why the search or saving its snapshot
+    /// failed.
+    pub results: Result<Results, ParexError>,
+
+    /// The snapshot saved, if any. Only searches that
+    /// [finished](Completion::Finished) are saved, so a cancelled or failed
+    /// run never passes for one where everything was deleted.
+    pub snapshot: Option<PathBuf>,
+}
+
+// ---------------------------------------------------------------------------
+// Daemon
+// ---------------------------------------------------------------------------
+
+type Prepare = dyn Fn(&Job, SearchBuilder) -> SearchBuilder + Send + Sync;
+
+/// Runs [`Job`]s when they fall due and keeps their snapshots.
+///
+/// Job `name` keeps its state in `jobs/<name>/` of the [`StateDir`]: the
+/// time it last started, and its snapshots, named by the time their run
+/// started so they sort oldest first. A job that never ran, or whose
+/// state was lost, is due at once.
+///
+/// [`reload`](Self::reload) swaps the jobs of a running daemon; share it
+/// with an [`Arc`](std::sync::Arc) to call it from another thread.
+pub struct Daemon {
+    state: StateDir,
+    jobs: Mutex<Vec<Job>>,
+    /// Wakes [`run`](Self::run) when the jobs are reloaded.
+    reloaded: Condvar,
+    prepare: Box<Prepare>,
+}
+
+impl Daemon {
+    /// A daemon for `jobs`, keeping state in `state`. `prepare` finishes
+    /// the builder [restored](SearchBuilder::from_config) from each job's
+    /// config before it runs; at the least it sets the source. The daemon
+    /// sets the builder's [cancel token](SearchBuilder::cancel_token), and
+    /// has it [collect entries](SearchBuilder::collect_entries) for jobs
+    /// that keep snapshots.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if a job's name is not a plain file name
+    /// or two jobs share one.
+    pub fn new(
+        state: StateDir,
+        jobs: Vec<Job>,
+        prepare: impl Fn(&Job, SearchBuilder) -> SearchBuilder + Send + Sync + 'static,
+    ) -> Result<Self, ParexError> {
+        let daemon = Self {
+            state,
+            jobs: Mutex::default(),
+            reloaded: Condvar::new(),
+            prepare: Box::new(prepare),
+        };
+        daemon.reload(jobs)?;
+        Ok(daemon)
+    }
+
+    /// The daemon's jobs.
+    pub fn jobs(&self) -> Vec<Job> {
+        self.lock().clone()
+    }
+
+    /// Replace the daemon's jobs without stopping it — after an operator
+    /// edits exclusions or tuning, say. Call it from a `SIGHUP` handler or
+    /// an admin API.
+    ///
+    /// Schedules follow job names: a job whose name is unchanged keeps its
+    /// last start time and snapshots, and a new interval counts from that
+    /// start. A run in progress finishes with the settings it started with.
+    /// Whatever `prepare` holds, such as a [`DirCache`](crate::cache::DirCache)
+    /// its sources share, is untouched, so caches stay warm. Removed jobs
+    /// leave their state behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if a job's name is not a plain file name
+    /// or two jobs share one; the daemon keeps its current jobs.
+    pub fn reload(&self, jobs: Vec<Job>) -> Result<(), ParexError> {
+        self.check(&jobs)?;
+        *self.lock() = jobs;
+        self.reloaded.notify_all();
+        Ok(())
+    }
+
+    /// When the job `name` last started, if it has run.
+    pub fn last_run(&self, name: &str) -> Option<SystemTime> {
+        let text = std::fs::read_to_string(self.job_dir(name).join(LAST_RUN)).ok()?;
+        let nanos = text.trim().parse().ok()?;
+        Some(UNIX_EPOCH + Duration::from_nanos(nanos))
+    }
+
+    /// The snapshots the job `name` has kept, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if the job's directory cannot be listed.
+    pub fn snapshots(&self, name: &str) -> Result<Vec<PathBuf>, ParexError> {
+        let dir = self.job_dir(name);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_err(&dir, e)),
+        };
+        let mut snapshots = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| io_err(&dir, e))?.path();
+            if path.extension().is_some_and(|ext| ext == SNAPSHOT_EXT) {
+                snapshots.push(path);
+            }
+        }
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
+    /// Run every job that is due, in order, and return what happened.
+    pub fn tick(&self) -> Vec<Run> {
+        self.run_due(&self.jobs(), &CancelToken::default())
+    }
+
+    /// Run jobs as they fall due until `cancel` is cancelled, passing each
+    /// [`Run`] to `each`. Cancelling also stops a search in progress; the
+    /// daemon notices within a second while idle. A
+    /// [`reload`](Self::reload) takes effect at once.
+    pub fn run(&self, cancel: &CancelToken, mut each: impl FnMut(Run)) {
+        while !cancel.is_cancelled() {
+            for run in self.run_due(&self.jobs(), cancel) {
+                each(run);
+            }
+            let jobs = self.lock();
+            let wait = self.until_due(&jobs).min(POLL);
+            drop(self.reloaded.wait_timeout(jobs, wait));
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Job>> {
+        // A panic elsewhere cannot leave a half-replaced Vec behind
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn check(&self, jobs: &[Job]) -> Result<(), ParexError> {
+        let mut names = HashSet::new();
+        for job in jobs {
+            let mut parts = Path::new(&job.name).components();
+            let plain = matches!(
+                (parts.next(), parts.next()),
+                (Some(Component::Normal(_)), None)
+            ) && !job.name.starts_with('.');
+            let problem = if !plain {
+                "job name must be a plain file name"
+            } else if !names.insert(job.name.as_str()) {
+                "two jobs share this name"
+            } else {
+                continue;
+            };
+            return Err(io_err(
+                &self.job_dir(&job.name),
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, problem),
+            ));
+        }
+        Ok(())
+    }
+
+    fn job_dir(&self, name: &str) -> PathBuf {
+        self.state.file(JOBS_DIR).join(name)
+    }
+
+    /// Time until the first of `jobs` falls due.
+    fn until_due(&self, jobs: &[Job]) -> Duration {
+        let now = SystemTime::now();
+        jobs.iter()
+            .map(|job| self.wait(job, now))
+            .min()
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Time from `now` until `job` falls due; zero if it is due.
+    fn wait(&self, job: &Job, now: SystemTime) -> Duration {
+        match self.last_run(&job.name) {
+            Some(last) => last.checked_add(job.every).map_or(Duration::MAX, |next| {
+                next.duration_since(now).unwrap_or_default()
+            }),
+            None => Duration::ZERO,
+        }
+    }
+
+    fn run_due(&self, jobs: &[Job], cancel: &CancelToken) -> Vec<Run> {
+        let mut runs = Vec::new();
+        for job in jobs {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let started = SystemTime::now();
+            if self.wait(job, started).is_zero() {
+                runs.push(self.run_job(job, started, cancel));
+            }
+        }
+        runs
+    }
+
+    fn run_job(&self, job: &Job, started: SystemTime, cancel: &CancelToken) -> Run {
+        let mut snapshot = None;
+        let results = (|| {
+            // Record the start first, so a job that brings the process
+            // down waits out its interval instead of running again at once
+            let dir = self.job_dir(&job.name);
+            std::fs::create_dir_all(&dir).map_err(|e| io_err(&dir, e))?;
+            let since_epoch = started.duration_since(UNIX_EPOCH).unwrap_or_default();
+            write_atomic(&dir.join(LAST_RUN), |out| {
+                write!(out, "{}", since_epoch.as_nanos()).map_err(|e| io_err(&dir, e))
+            })?;
+
+            let mut search = (self.prepare)(job, SearchBuilder::from_config(job.config.clone()))
+                .cancel_token(cancel.clone());
+            if job.keep > 0 {
+                search = search.collect_entries(true);
+            }
+            let results = search.run()?;
+
+            if job.keep > 0 && matches!(results.completion, Completion::Finished) {
+                let path = dir.join(format!("{:020}.{SNAPSHOT_EXT}", since_epoch.as_nanos()));
+                Snapshot::from_entries(&results.entries).save(&path)?;
+                snapshot = Some(path);
+                let kept = self.snapshots(&job.name)?;
+                for old in &kept[..kept.len().saturating_sub(job.keep)] {
+                    std::fs::remove_file(old).map_err(|e| io_err(old, e))?;
+                }
+            }
+            Ok(results)
+        })();
+        Run {
+            job: job.name.clone(),
+            started,
+            results,
+            snapshot,
+        }
+    }
+}
+
+fn io_err(path: &Path, source: std::io::Error) -> ParexError {
+    ParexError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
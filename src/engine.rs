@@ -1,10 +1,28 @@
-use std::path::PathBuf;
-use std::sync::Arc;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, mpsc};
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::audit_log::Audit;
+use crate::batch::MatchBatches;
+use crate::builder::AllMatcher;
+use crate::context::MatchContext;
+use crate::entry::{Entry, EntryKind};
 use crate::error::ParexError;
-use crate::results::{Results, ScanStats};
-use crate::traits::Matcher;
+use crate::exclude::ExclusionSet;
+use crate::mounts::MountPoints;
+use crate::overlay::{Overlay, OverlayIter};
+use crate::plan::Cost;
+use crate::profile::{DirTally, Profiler};
+use crate::rank::{Rerank, ScoredEntry, keep_best, sort_by_score};
+use crate::results::{Completion, Results, ScanStats, StopReason};
+use crate::rules::{Rule, Tally};
+use crate::scheduler::Scheduler;
+use crate::snapshot::{Record, Snapshot};
+use crate::stat_ahead::StatAheadIter;
+use crate::summary::{DirSummaries, DirSummary};
+use crate::traits::{Matcher, Sink};
+use crate::warning::{Warning, Warnings};
 
 // ---------------------------------------------------------------------------
 // WalkConfig
@@ -14,10 +32,471 @@ use crate::traits::Matcher;
 ///
 /// Sources receive this so they can honour depth limits, thread counts,
 /// and result limits during their own traversal logic.
+///
+/// Searches build their own. To drive a source directly — in its unit
+/// tests, say — start from [`WalkConfig::default()`], which walks
+/// everything on one thread, and adjust it with the `with_*` methods:
+///
+/// ```rust
+/// use parex::engine::WalkConfig;
+///
+/// let config = WalkConfig::default().with_threads(4).with_max_depth(2);
+/// assert_eq!(config.max_depth, Some(2));
+/// assert!(!config.skip_dir("src".as_ref()));
+/// ```
+///
+/// New options are added as private fields with accessors, so sources
+/// written against this version keep compiling.
+#[non_exhaustive]
 pub struct WalkConfig {
+    /// Threads the source may traverse with.
     pub threads: usize,
+    /// Deepest [`Entry::depth`] the search wants, if limited.
     pub max_depth: Option<usize>,
     pub(crate) limit: Option<usize>,
+    pub(crate) cancel: CancelToken,
+    pub(crate) exclude: Option<ExclusionSet>,
+    pub(crate) special_mounts: Option<MountPoints>,
+    pub(crate) matcher: Arc<dyn Matcher>,
+    pub(crate) dir_reads: DirReadLimit,
+    pub(crate) metadata_mode: MetadataMode,
+    pub(crate) shuffle_seed: Option<u64>,
+    pub(crate) warnings: Arc<Warnings>,
+    pub(crate) yield_points: YieldPoints,
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            threads: 1,
+            max_depth: None,
+            limit: None,
+            cancel: CancelToken::default(),
+            exclude: None,
+            special_mounts: None,
+            matcher: Arc::new(AllMatcher),
+            dir_reads: DirReadLimit::unlimited(),
+            metadata_mode: MetadataMode::default(),
+            shuffle_seed: None,
+            warnings: Default::default(),
+            yield_points: YieldPoints::default(),
+        }
+    }
+}
+
+impl WalkConfig {
+    /// Traverse with `n` threads.
+    pub fn with_threads(mut self, n: usize) -> Self {
+        self.threads = n;
+        self
+    }
+
+    /// Stop descending below depth `d`.
+    pub fn with_max_depth(mut self, d: usize) -> Self {
+        self.max_depth = Some(d);
+        self
+    }
+
+    /// Observe `token` for cancellation.
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Skip `set` and everything under it.
+    pub fn with_exclude(mut self, set: ExclusionSet) -> Self {
+        self.exclude = Some(set);
+        self
+    }
+
+    /// Let the source prune directories `matcher` rules out.
+    pub fn with_matcher(mut self, matcher: impl Matcher + 'static) -> Self {
+        self.matcher = Arc::new(matcher);
+        self
+    }
+
+    /// Read at most `n` directories at once.
+    pub fn with_max_dir_reads(mut self, n: usize) -> Self {
+        self.dir_reads = DirReadLimit::new(n);
+        self
+    }
+
+    /// Read symlink metadata with `mode`.
+    pub fn with_metadata_mode(mut self, mode: MetadataMode) -> Self {
+        self.metadata_mode = mode;
+        self
+    }
+
+    /// Shuffle sibling order with `seed`.
+    pub fn with_shuffle(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+
+    /// The cancellation token for this search.
+    ///
+    /// Sources that traverse on their own threads should clone this and stop
+    /// producing entries once [`CancelToken::is_cancelled`] returns `true` —
+    /// the consumer has gone away and nothing will read them.
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// The in-flight directory-read limit for this search.
+    ///
+    /// Sources should hold a [`DirReadPermit`] from
+    /// [`DirReadLimit::acquire`] while reading a directory. Unlimited unless
+    /// the caller set [`SearchBuilder::max_dir_reads`](crate::SearchBuilder::max_dir_reads).
+    pub fn dir_read_limit(&self) -> &DirReadLimit {
+        &self.dir_reads
+    }
+
+    /// Whether metadata for symlinks describes the link or its target.
+    ///
+    /// Sources that fill in [`Entry::metadata`] should read it with
+    /// [`MetadataMode::metadata`] so it agrees with what matchers see.
+    pub fn metadata_mode(&self) -> MetadataMode {
+        self.metadata_mode
+    }
+
+    /// The seed set with [`SearchBuilder::shuffle`](crate::SearchBuilder::shuffle),
+    /// if sibling order should be randomized.
+    pub fn shuffle_seed(&self) -> Option<u64> {
+        self.shuffle_seed
+    }
+
+    /// Reorder the children of `dir` before visiting them.
+    ///
+    /// A no-op unless the search was built with
+    /// [`shuffle`](crate::SearchBuilder::shuffle). The order depends only on
+    /// the seed and the path of `dir`, so the same seed over the same tree
+    /// visits it in the same order, while each directory still gets its own
+    /// permutation. Sources call this on each listing before descending.
+    pub fn shuffle<T>(&self, dir: &Path, children: &mut [T]) {
+        let Some(seed) = self.shuffle_seed else {
+            return;
+        };
+
+        // FNV-1a of the path, so the permutation is stable across runs and builds
+        let dir_hash = dir
+            .as_os_str()
+            .as_encoded_bytes()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+                (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        let mut rng = SplitMix64(seed ^ dir_hash);
+
+        // Fisher–Yates
+        for i in (1..children.len()).rev() {
+            let j = (rng.next() % (i as u64 + 1)) as usize;
+            children.swap(i, j);
+        }
+    }
+
+    /// Report a [`Warning`] — a caveat that is not an error, such as a
+    /// throttled request or a lossily converted path.
+    ///
+    /// Delivered to [`Results::warnings`] and any
+    /// [`on_warning`](crate::SearchBuilder::on_warning) observer; a no-op
+    /// unless the search asked for warnings. Safe to call from any thread.
+    pub fn warn(&self, warning: Warning) {
+        self.warnings.emit(warning);
+    }
+
+    /// Whether anyone receives [`warn()`](Self::warn)ed warnings, so sources
+    /// can skip building messages nobody will read.
+    pub fn warnings_enabled(&self) -> bool {
+        self.warnings.enabled()
+    }
+
+    /// Whether the source may skip `dir` and everything below it.
+    ///
+    /// `true` when `dir` is excluded, is a mount point the search skips
+    /// (see [`SearchBuilder::special_mounts`](crate::SearchBuilder::special_mounts)),
+    /// or the matcher guarantees nothing under it can match (see
+    /// [`Matcher::prunes_dir`]). Sources that check this before descending
+    /// avoid reading directories whose entries would be dropped anyway;
+    /// sources that ignore it still produce correct results.
+    pub fn skip_dir(&self, dir: &Path) -> bool {
+        self.exclude.as_ref().is_some_and(|ex| ex.contains(dir))
+            || self
+                .special_mounts
+                .as_ref()
+                .is_some_and(|m| m.is_mount_point(dir))
+            || self.matcher.prunes_dir(dir)
+    }
+
+    /// A fresh counter of this search's yield points, for one traversal
+    /// thread of the source.
+    ///
+    /// Sources that traverse on their own threads should take one per
+    /// thread and [`tick()`](YieldPoints::tick) it for every entry, so the
+    /// whole search gives up the CPU as the caller asked. Never yields
+    /// unless the search was built with
+    /// [`yield_every`](crate::SearchBuilder::yield_every).
+    pub fn yield_points(&self) -> YieldPoints {
+        self.yield_points.clone()
+    }
+}
+
+/// A tiny, fast PRNG — plenty for shuffling, and no dependency.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MetadataMode
+// ---------------------------------------------------------------------------
+
+/// How metadata is read for symlinks.
+///
+/// Size and modification time of a link and of its target differ. Backups
+/// usually care about the link itself; disk-usage and audit reports usually
+/// care about what it points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetadataMode {
+    /// `stat()` — describe the target. A dangling link has no metadata.
+    #[default]
+    Follow,
+
+    /// `lstat()` — describe the link itself.
+    NoFollow,
+}
+
+impl MetadataMode {
+    /// Read the metadata of `path` with these semantics.
+    pub fn metadata(self, path: &Path) -> std::io::Result<std::fs::Metadata> {
+        match self {
+            Self::Follow => std::fs::metadata(path),
+            Self::NoFollow => std::fs::symlink_metadata(path),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CancelToken
+// ---------------------------------------------------------------------------
+
+/// A shared flag that tells the engine and the source to stop walking.
+///
+/// Cheap to clone — all clones observe the same flag. Cancellation is
+/// cooperative: the engine checks it before pulling each entry, and sources
+/// are expected to check it in their own traversal loops.
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Request that the search stop as soon as possible.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Whether both tokens share the same flag.
+    #[cfg_attr(not(feature = "signals"), allow(dead_code))]
+    pub(crate) fn same_as(&self, other: &CancelToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DirReadLimit
+// ---------------------------------------------------------------------------
+
+/// Caps how many directories a source reads at once, independent of threads.
+///
+/// Network filesystems degrade when hundreds of `readdir` calls hit the same
+/// export concurrently, even though a high thread count still helps with
+/// matching. Cheap to clone — all clones share the same slots, so a source
+/// can hand one to each of its worker threads.
+#[derive(Clone, Default, Debug)]
+pub struct DirReadLimit(Option<Arc<Slots>>);
+
+#[derive(Debug)]
+struct Slots {
+    free: Mutex<usize>,
+    released: Condvar,
+}
+
+impl DirReadLimit {
+    /// Allow at most `max` concurrent directory reads. `0` is treated as `1`.
+    pub fn new(max: usize) -> Self {
+        Self(Some(Arc::new(Slots {
+            free: Mutex::new(max.max(1)),
+            released: Condvar::new(),
+        })))
+    }
+
+    /// No limit — [`acquire`](Self::acquire) never blocks.
+    pub fn unlimited() -> Self {
+        Self(None)
+    }
+
+    /// Block until a read slot is free. The slot is released when the
+    /// permit is dropped.
+    pub fn acquire(&self) -> DirReadPermit {
+        if let Some(slots) = &self.0 {
+            let mut free = slots.free.lock().unwrap_or_else(|e| e.into_inner());
+            while *free == 0 {
+                free = slots.released.wait(free).unwrap_or_else(|e| e.into_inner());
+            }
+            *free -= 1;
+        }
+        DirReadPermit(self.0.clone())
+    }
+}
+
+/// A held directory-read slot. Released on drop.
+#[must_use = "the slot is released as soon as the permit is dropped"]
+pub struct DirReadPermit(Option<Arc<Slots>>);
+
+impl Drop for DirReadPermit {
+    fn drop(&mut self) {
+        if let Some(slots) = &self.0 {
+            *slots.free.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+            slots.released.notify_one();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// YieldPoints
+// ---------------------------------------------------------------------------
+
+/// Gives up the CPU every so many entries, so a search can share cores with
+/// work that needs bounded scheduling latency — a game loop, an audio
+/// thread.
+///
+/// Each clone counts on its own; keep one per thread. Built with
+/// [`SearchBuilder::yield_every`](crate::SearchBuilder::yield_every) and
+/// [`on_yield`](crate::SearchBuilder::on_yield); the default never yields.
+#[derive(Clone, Default)]
+pub struct YieldPoints {
+    every: usize,
+    hook: Option<Arc<dyn Fn() + Send + Sync>>,
+    since: usize,
+}
+
+impl YieldPoints {
+    pub(crate) fn new(every: usize, hook: Option<Arc<dyn Fn() + Send + Sync>>) -> Self {
+        Self {
+            every,
+            hook,
+            since: 0,
+        }
+    }
+
+    /// Count one entry, yielding if it completes a stretch of `every`:
+    /// the [`on_yield`](crate::SearchBuilder::on_yield) hook if one was
+    /// set, [`std::thread::yield_now`] otherwise.
+    #[inline]
+    pub fn tick(&mut self) {
+        if self.every == 0 {
+            return;
+        }
+        self.since += 1;
+        if self.since >= self.every {
+            self.since = 0;
+            match &self.hook {
+                Some(hook) => hook(),
+                None => std::thread::yield_now(),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for YieldPoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("YieldPoints")
+            .field("every", &self.every)
+            .field("hook", &self.hook.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// EngineTuning
+// ---------------------------------------------------------------------------
+
+/// Internal buffer and batch sizes.
+///
+/// The defaults suit most workloads. Large deployments can trade memory for
+/// fewer round trips — bigger overlay batches for a remote tag store, a
+/// bounded stream so a slow consumer cannot let matches pile up. Set with
+/// [`SearchBuilder::tuning`](crate::SearchBuilder::tuning); start from
+/// `EngineTuning::default()` and override individual fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct EngineTuning {
+    /// Entries tagged per [`Overlay::lookup`] call. Default: 256.
+    pub overlay_batch: usize,
+
+    /// Matches a `MatchStream` buffers before the walk
+    /// waits for the consumer. `None` buffers without limit. Default: `None`.
+    pub stream_capacity: Option<usize>,
+
+    /// Initial capacity of [`Results::paths`] when paths are collected.
+    /// Default: 1024.
+    pub path_capacity: usize,
+
+    /// Initial capacity of [`Results::errors`] when errors are collected.
+    /// Default: 64.
+    pub error_capacity: usize,
+
+    /// Entries handed to a matcher thread at a time when matching runs in
+    /// parallel. Larger batches mean less scheduling overhead, smaller ones
+    /// spread uneven work more evenly. Default: 64.
+    pub match_batch: usize,
+
+    /// Hand emptied batch buffers back to the thread reading the source
+    /// instead of freeing them and allocating new ones. Cuts allocator
+    /// traffic — and the contention it causes at high thread counts — at
+    /// the cost of keeping a few idle buffers alive. Default: `false`.
+    pub recycle_batches: bool,
+
+    /// IO threads issuing `stat()` calls ahead of the matcher when
+    /// [`prefetch_metadata`](crate::SearchBuilder::prefetch_metadata) is
+    /// on. Default: 4.
+    pub stat_threads: usize,
+
+    /// Most `stat()` requests handed to an IO thread at once. A batch only
+    /// holds entries of one directory, so a thread works through a
+    /// directory's inodes together. `1` sends every request on its own.
+    /// Default: 16.
+    pub stat_batch: usize,
+}
+
+impl Default for EngineTuning {
+    fn default() -> Self {
+        Self {
+            overlay_batch: 256,
+            stream_capacity: None,
+            path_capacity: 1024,
+            error_capacity: 64,
+            match_batch: 64,
+            recycle_batches: false,
+            stat_threads: 4,
+            stat_batch: 16,
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -30,7 +509,57 @@ pub(crate) struct EngineOptions {
     pub source: Box<dyn crate::traits::Source>,
     pub matcher: Arc<dyn Matcher>,
     pub collect_paths: bool,
+    /// Keep whole matched entries in `Results::entries`.
+    pub collect_entries: bool,
     pub collect_errors: bool,
+    pub sink: Option<Box<dyn Sink>>,
+    /// Hands matches to a callback in batches before they reach `on_match`.
+    pub on_matches: Option<MatchBatches>,
+    pub overlay: Option<Box<dyn Overlay>>,
+    /// Post-walk ranking stage. Only [`run()`] honours it.
+    pub rerank: Option<Box<dyn Rerank>>,
+    /// A `ScoredMatcher` is among the filters; its scores go into
+    /// `Results::ranked`. Only [`run()`] honours it.
+    pub scored: bool,
+    /// Keep only this many of the best-ranked matches.
+    pub top_k: Option<usize>,
+    pub tuning: EngineTuning,
+    /// Time the walk into `Results::profile`.
+    pub profile: bool,
+    /// Report this many costly directories in `Results::dir_report`.
+    pub slowest_dirs: usize,
+    /// Count matches per depth into `Results::match_depths`.
+    pub depth_histogram: bool,
+    /// Walk the whole source even after the match limit is reached.
+    pub accurate_stats: bool,
+    /// Match directories after the walk, with summaries of their contents.
+    pub dir_summaries: bool,
+    /// Delay before the walk starts, abandoned on cancellation.
+    pub debounce: Duration,
+    /// Entries to `stat()` ahead of the matcher. `0` disables it.
+    pub stat_ahead: usize,
+    /// Files to hint ahead of the matcher. `0` disables prefetching.
+    #[cfg(feature = "prefetch")]
+    pub prefetch: usize,
+    /// Wait for the host to leave battery power or thermal throttling,
+    /// checking at this interval.
+    #[cfg(feature = "power")]
+    pub defer_on_power: Option<Duration>,
+    /// Spans for the search and its phases.
+    #[cfg(feature = "otel")]
+    pub trace: crate::otel::Trace,
+    /// Reports the search to an audit observer once it ends.
+    pub audit: Option<Audit>,
+    /// Checked against the matches once the walk ends.
+    pub rules: Vec<Rule>,
+    /// Where to save the matches of a search that does not finish.
+    pub checkpoint: Option<PathBuf>,
+    /// Keeps the process in the search's IO cgroup until it finishes.
+    #[cfg(all(target_os = "linux", feature = "cgroup"))]
+    pub cgroup_guard: Option<crate::cgroup::CgroupGuard>,
+    /// Keeps the search registered for SIGINT / SIGTERM until it finishes.
+    #[cfg(feature = "signals")]
+    pub signal_guard: Option<crate::signals::SignalGuard>,
 }
 
 // ---------------------------------------------------------------------------
@@ -40,86 +569,889 @@ pub(crate) struct EngineOptions {
 /// Execute a search using the source's iterator.
 ///
 /// Iterates `Result<Entry, ParexError>` items from `source.walk()`.
-/// `Ok` entries are matched and collected. `Err` entries are counted as
-/// recoverable errors and stored in `Results::errors` when
-/// `collect_errors` is enabled.
+/// `Ok` entries are matched and collected. Recoverable `Err` entries are
+/// stored in `Results::errors` when `collect_errors` is enabled; a fatal
+/// `Err` halts the walk and is reported through `Results::completion`.
 ///
-/// Uses plain locals instead of `Arc<Mutex>` / `Arc<AtomicUsize>` — the
-/// engine is single-consumer, so shared-state primitives add overhead with
-/// no benefit.
-pub(crate) fn run(opts: EngineOptions) -> Results {
-    let start = Instant::now();
-
-    let entries = opts.source.walk(&opts.config);
-
-    let limit = opts.config.limit;
+/// Counts, paths, and errors are plain locals of the consuming thread —
+/// matcher threads hand matches back over a channel rather than sharing
+/// `Arc<Mutex>` / `Arc<AtomicUsize>` state.
+pub(crate) fn run(mut opts: EngineOptions) -> Results {
     let collect_paths = opts.collect_paths;
-    let collect_errors = opts.collect_errors;
-    let matcher = opts.matcher;
-
-    let mut matches = 0usize;
-    let mut files = 0usize;
-    let mut dirs = 0usize;
     let mut paths: Vec<PathBuf> = if collect_paths {
-        Vec::with_capacity(1024)
+        Vec::with_capacity(opts.tuning.path_capacity)
     } else {
         Vec::new()
     };
-    let mut errors: Vec<ParexError> = if collect_errors {
-        Vec::with_capacity(64)
+    let collect_entries = opts.collect_entries;
+    let mut entries: Vec<Entry> = if collect_entries {
+        Vec::with_capacity(opts.tuning.path_capacity)
     } else {
         Vec::new()
     };
 
-    for item in entries {
-        // Enforce limit before processing next item
-        if let Some(lim) = limit && matches >= lim {
+    let rerank = opts.rerank.take();
+    let ranking = rerank.is_some() || opts.scored;
+    let top_k = opts.top_k;
+    let mut ranked = Vec::new();
+
+    let mut results = execute(opts, |entry, score| {
+        // Clone only what more than one collection keeps
+        if !ranking && !collect_entries {
+            if collect_paths {
+                paths.push(entry.path);
+            }
+            return;
+        }
+        if collect_paths {
+            paths.push(entry.path.clone());
+        }
+        if !ranking {
+            entries.push(entry);
+            return;
+        }
+        if collect_entries {
+            entries.push(entry.clone());
+        }
+        ranked.push(ScoredEntry {
+            entry,
+            score: score.unwrap_or(0.0),
+        });
+        // Scores are final without a rerank stage, so the rest can go early
+        if let Some(k) = top_k
+            && rerank.is_none()
+            && ranked.len() >= k.saturating_mul(2).max(64)
+        {
+            keep_best(&mut ranked, k);
+        }
+    });
+
+    if ranking {
+        if let Some(rerank) = rerank {
+            rerank.rerank(&mut ranked);
+        }
+        sort_by_score(&mut ranked);
+        if let Some(k) = top_k {
+            ranked.truncate(k);
+        }
+        results.ranked = ranked;
+    }
+    results.paths = paths;
+    results.entries = entries;
+    results
+}
+
+/// The engine loop shared by [`run()`] and the streaming APIs.
+///
+/// Every matched entry is handed to `on_match` by value, so streaming
+/// callers can forward it without cloning, along with the score a
+/// `ScoredMatcher` gave it. The returned [`Results`] carries
+/// counts, stats, and errors — `paths` is left empty for the caller to fill.
+///
+/// With more than one thread and a matcher that does more than look at
+/// names, matching runs on a work-stealing pool (see [`Scheduler`]); name
+/// checks are cheaper than handing the entry to another thread, so they
+/// stay on the consuming thread.
+pub(crate) fn execute(
+    mut opts: EngineOptions,
+    mut on_match: impl FnMut(Entry, Option<f32>),
+) -> Results {
+    #[cfg(feature = "otel")]
+    let scan = opts.trace.start();
+    let audit = opts.audit.take();
+    let checkpoint = opts.checkpoint.take();
+    let mut tally = Tally::new(std::mem::take(&mut opts.rules));
+    let mut batches = opts.on_matches.take();
+    let mut matched = Snapshot::new();
+    let (started, clock) = (SystemTime::now(), Instant::now());
+
+    let mut results = walk_source(opts, |entry, score| {
+        if checkpoint.is_some() {
+            matched.insert(entry.path.clone(), Record::from_entry(&entry));
+        }
+        if let Some(tally) = &mut tally {
+            tally.add(&entry);
+        }
+        match &mut batches {
+            Some(batches) => batches.push(entry, score, &mut on_match),
+            None => on_match(entry, score),
+        }
+    });
+    if let Some(batches) = &mut batches {
+        batches.flush(&mut on_match);
+    }
+
+    if let Some(path) = checkpoint
+        && let Err(err) = save_checkpoint(&path, &matched, &results.completion)
+    {
+        results.errors.push(err);
+    }
+    if let Some(tally) = tally {
+        results.violations = tally.evaluate(&results);
+    }
+    if let Some(audit) = audit {
+        audit.finish(started, clock.elapsed(), &results);
+    }
+    #[cfg(feature = "otel")]
+    scan.finish(&results);
+    results
+}
+
+/// [`execute()`] without the audit trail and scan span.
+fn walk_source(opts: EngineOptions, mut on_match: impl FnMut(Entry, Option<f32>)) -> Results {
+    #[cfg(feature = "otel")]
+    let wait = {
+        let waits = !opts.debounce.is_zero();
+        #[cfg(feature = "power")]
+        let waits = waits || opts.defer_on_power.is_some();
+        waits.then(|| opts.trace.phase("parex.wait"))
+    };
+    let go = debounce(opts.debounce, &opts.config.cancel);
+    #[cfg(feature = "power")]
+    let go = go && defer_on_power(opts.defer_on_power, &opts.config.cancel);
+    #[cfg(feature = "otel")]
+    drop(wait);
+    if !go {
+        return Results {
+            matches: 0,
+            matched_files: 0,
+            matched_dirs: 0,
+            matched_symlinks: 0,
+            matched_other: 0,
+            match_depths: Vec::new(),
+            paths: Vec::new(),
+            entries: Vec::new(),
+            stats: ScanStats::compute(0, 0, Duration::ZERO, Some(StopReason::Cancelled)),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            ranked: Vec::new(),
+            violations: Vec::new(),
+            dir_report: None,
+            profile: None,
+            completion: Completion::Cancelled,
+        };
+    }
+
+    let start = Instant::now();
+
+    // Sources and matchers on this thread see the walk as their context
+    #[cfg(feature = "otel")]
+    let walk_phase = opts.trace.phase("parex.walk");
+    #[cfg(feature = "otel")]
+    let _attached = walk_phase.attach();
+
+    let entries = opts.source.walk(&opts.config);
+    let entries: Box<dyn Iterator<Item = Result<Entry, ParexError>> + '_> = match &opts.overlay {
+        Some(overlay) => Box::new(OverlayIter::new(
+            entries,
+            overlay.as_ref(),
+            opts.tuning.overlay_batch,
+        )),
+        None => entries,
+    };
+    // Name-only matchers never look at metadata
+    let entries: Box<dyn Iterator<Item = Result<Entry, ParexError>> + '_> =
+        if opts.stat_ahead > 0 && (opts.matcher.cost() >= Cost::Metadata || opts.dir_summaries) {
+            Box::new(StatAheadIter::new(
+                entries,
+                opts.stat_ahead,
+                opts.tuning.stat_threads,
+                opts.tuning.stat_batch,
+                opts.config.metadata_mode,
+            ))
+        } else {
+            entries
+        };
+    // Hints only pay off when the matcher actually reads contents
+    #[cfg(feature = "prefetch")]
+    let entries: Box<dyn Iterator<Item = Result<Entry, ParexError>> + '_> =
+        if opts.prefetch > 0 && opts.matcher.cost() >= Cost::Content {
+            Box::new(crate::prefetch::PrefetchIter::new(entries, opts.prefetch))
+        } else {
+            entries
+        };
+
+    #[cfg(feature = "signals")]
+    let _signal_guard = opts.signal_guard;
+    #[cfg(all(target_os = "linux", feature = "cgroup"))]
+    let _cgroup_guard = opts.cgroup_guard;
+
+    let profiler = opts.profile.then(Profiler::new);
+    let mut walk = Walk {
+        limit: opts.config.limit,
+        max_depth: opts.config.max_depth,
+        exclude: opts.config.exclude.as_ref(),
+        special_mounts: opts.config.special_mounts.as_ref(),
+        cancel: &opts.config.cancel,
+        collect_errors: opts.collect_errors,
+        accurate_stats: opts.accurate_stats,
+        saturated: false,
+        matches: 0,
+        matched_kinds: [0; 4],
+        match_depths: opts.depth_histogram.then(Vec::new),
+        start,
+        first_match: None,
+        files: 0,
+        dirs: 0,
+        errors: if opts.collect_errors {
+            Vec::with_capacity(opts.tuning.error_capacity)
+        } else {
+            Vec::new()
+        },
+        completion: Completion::Finished,
+        stopped_reason: None,
+        profiler: profiler.as_ref(),
+        dir_tally: (opts.profile || opts.slowest_dirs > 0).then(DirTally::default),
+        summaries: opts.dir_summaries.then(DirSummaries::default),
+    };
+    let matching = Matching {
+        matcher: opts.matcher.as_ref(),
+        sink: opts.sink.as_deref(),
+        metadata_mode: opts.config.metadata_mode,
+        warnings: &opts.config.warnings,
+        profiler: profiler.as_ref(),
+        yield_points: &opts.config.yield_points,
+        #[cfg(feature = "otel")]
+        trace: walk_phase.context(),
+    };
+
+    let threads = opts.config.threads;
+    let idle_percent = if threads > 1 && matching.matcher.cost() >= Cost::Metadata {
+        Some(walk_parallel(
+            &mut walk,
+            entries,
+            &matching,
+            threads,
+            &opts.tuning,
+            &mut on_match,
+        ))
+    } else {
+        walk_serial(&mut walk, entries, &matching, &mut on_match);
+        None
+    };
+    match_held_dirs(&mut walk, &matching, &mut on_match);
+
+    let duration = start.elapsed();
+
+    let matches = match walk.limit {
+        Some(lim) => walk.matches.min(lim),
+        None => walk.matches,
+    };
+
+    let [matched_files, matched_dirs, matched_symlinks, matched_other] = walk.matched_kinds;
+    let dir_tally = walk.dir_tally.unwrap_or_default();
+    Results {
+        matches,
+        matched_files,
+        matched_dirs,
+        matched_symlinks,
+        matched_other,
+        match_depths: walk.match_depths.unwrap_or_default(),
+        paths: Vec::new(),
+        entries: Vec::new(),
+        stats: ScanStats {
+            idle_percent,
+            time_to_first_match: walk.first_match,
+            ..ScanStats::compute(walk.files, walk.dirs, duration, walk.stopped_reason)
+        },
+        errors: walk.errors,
+        warnings: opts.config.warnings.take(),
+        ranked: Vec::new(),
+        violations: Vec::new(),
+        completion: walk.completion,
+        dir_report: (opts.slowest_dirs > 0).then(|| dir_tally.report(opts.slowest_dirs)),
+        // Last, once nothing borrows the profiler
+        profile: profiler.map(|p| p.finish(&dir_tally)),
+    }
+}
+
+/// Save `matched` to `path` if the search stopped early, or remove the
+/// checkpoint of an earlier search if this one ran to the end.
+fn save_checkpoint(
+    path: &Path,
+    matched: &Snapshot,
+    completion: &Completion,
+) -> Result<(), ParexError> {
+    match completion {
+        Completion::Finished => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(ParexError::Io {
+                path: path.to_path_buf(),
+                source: e,
+            }),
+            _ => Ok(()),
+        },
+        _ => matched.save(path),
+    }
+}
+
+/// Sleep for `delay` unless `cancel` fires first. `true` if the search
+/// should go ahead.
+fn debounce(delay: Duration, cancel: &CancelToken) -> bool {
+    // CancelToken is a bare flag, so poll it in short slices
+    const SLICE: Duration = Duration::from_millis(5);
+
+    let deadline = Instant::now() + delay;
+    loop {
+        if cancel.is_cancelled() {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(SLICE));
+    }
+}
+
+/// Wait while the host is on battery or throttled, polling every `poll`.
+/// `true` if the search should go ahead.
+#[cfg(feature = "power")]
+fn defer_on_power(poll: Option<Duration>, cancel: &CancelToken) -> bool {
+    let Some(poll) = poll else {
+        return true;
+    };
+    while crate::power::detect().constrained() {
+        if !debounce(poll, cancel) {
+            return false;
+        }
+    }
+    !cancel.is_cancelled()
+}
+
+type Entries<'a> = Box<dyn Iterator<Item = Result<Entry, ParexError>> + 'a>;
+
+/// Match the directories held back for their summaries, once the source
+/// is exhausted. Summaries of a walk that stopped early are incomplete, so
+/// its directories are never matched.
+fn match_held_dirs(
+    walk: &mut Walk<'_>,
+    matching: &Matching<'_>,
+    on_match: &mut impl FnMut(Entry, Option<f32>),
+) {
+    let Some(summaries) = walk.summaries.take() else {
+        return;
+    };
+    for (dir, summary) in summaries.finish() {
+        if walk.should_stop() || walk.saturated {
             break;
         }
+        match matching.check_summarized(&dir, &summary) {
+            Ok(Some(verdict)) => walk.matched(dir, verdict, on_match),
+            Ok(None) => {}
+            Err(err) => walk.error(err),
+        }
+    }
+}
+
+/// Match every entry on the calling thread.
+fn walk_serial(
+    walk: &mut Walk<'_>,
+    mut entries: Entries<'_>,
+    matching: &Matching<'_>,
+    on_match: &mut impl FnMut(Entry, Option<f32>),
+) {
+    let mut yields = matching.yield_points.clone();
+    while let Some(item) = walk.pull(&mut entries) {
+        yields.tick();
+        if walk.should_stop() {
+            break;
+        }
+        let Some(entry) = walk.admit(item) else {
+            if walk.stopped_reason.is_some() {
+                break;
+            }
+            continue;
+        };
+        if walk.saturated {
+            continue;
+        }
+
+        match matching.check(&entry) {
+            Ok(Some(verdict)) => walk.matched(entry, verdict, on_match),
+            Ok(None) => continue,
+            Err(err) => walk.error(err),
+        }
+        if walk.stopped_reason.is_some() {
+            break;
+        }
+    }
+}
+
+/// Pull and filter entries on the calling thread, match them on `threads`
+/// workers, and take the matches back in whatever order they finish.
+///
+/// Returns the share of worker time, in percent, spent parked waiting for
+/// entries — high when the source, not matching, is the bottleneck.
+fn walk_parallel(
+    walk: &mut Walk<'_>,
+    mut entries: Entries<'_>,
+    matching: &Matching<'_>,
+    threads: usize,
+    tuning: &EngineTuning,
+    on_match: &mut impl FnMut(Entry, Option<f32>),
+) -> f64 {
+    let start = Instant::now();
+    let batch_size = tuning.match_batch.max(1);
+    // Enough queued work to keep every worker busy without buffering the source
+    let scheduler = Scheduler::new(threads, threads * 4, tuning.recycle_batches);
+    let halt = AtomicBool::new(false);
+    // Matches claimed by workers, so they stop once `limit` are found
+    let claimed = AtomicUsize::new(0);
+    let idle_nanos = AtomicU64::new(0);
+    let limit = walk.limit;
 
+    std::thread::scope(|scope| {
+        // A match with its score, or the matcher's error. The sink is only
+        // handed matches this thread counts, so it never sees one the
+        // results leave out.
+        let (tx, rx) = mpsc::channel::<Result<(Entry, Option<f32>), ParexError>>();
+        for me in 0..threads {
+            let (tx, scheduler, halt, claimed, idle_nanos) =
+                (tx.clone(), &scheduler, &halt, &claimed, &idle_nanos);
+            scope.spawn(move || {
+                // Lets the producer stop waiting for space if this worker panics
+                struct Exit<'a>(&'a Scheduler);
+                impl Drop for Exit<'_> {
+                    fn drop(&mut self) {
+                        self.0.worker_exited();
+                    }
+                }
+                let _exit = Exit(scheduler);
+                #[cfg(feature = "otel")]
+                let _attached = matching.trace.clone().attach();
+                let mut yields = matching.yield_points.clone();
+
+                let mut idle = Duration::ZERO;
+                loop {
+                    let waiting = Instant::now();
+                    let Some(mut batch) = scheduler.next(me) else {
+                        break;
+                    };
+                    idle += waiting.elapsed();
+
+                    for entry in batch.drain(..) {
+                        yields.tick();
+                        if halt.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let score = match matching.is_match(&entry) {
+                            Ok(Some(score)) => score,
+                            Ok(None) => continue,
+                            Err(err) => {
+                                if tx.send(Err(err)).is_err() {
+                                    break;
+                                }
+                                continue;
+                            }
+                        };
+                        if limit.is_some_and(|lim| claimed.fetch_add(1, Ordering::Relaxed) >= lim) {
+                            halt.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                        if tx.send(Ok((entry, score))).is_err() {
+                            break;
+                        }
+                    }
+                    scheduler.recycle(batch);
+                }
+                idle_nanos.fetch_add(idle.as_nanos() as u64, Ordering::Relaxed);
+            });
+        }
+        drop(tx);
+
+        let mut yields = matching.yield_points.clone();
+        let mut batch = scheduler.buffer(batch_size);
+        while let Some(item) = walk.pull(&mut entries) {
+            yields.tick();
+            while let Ok(found) = rx.try_recv() {
+                match found {
+                    Ok((entry, score)) if walk.takes_matches() => {
+                        let verdict = matching.accept(&entry, score);
+                        walk.matched(entry, verdict, on_match);
+                    }
+                    Ok(_) => {}
+                    Err(err) => walk.error(err),
+                }
+            }
+            if walk.should_stop() {
+                break;
+            }
+            let Some(entry) = walk.admit(item) else {
+                if walk.stopped_reason.is_some() {
+                    break;
+                }
+                continue;
+            };
+            if walk.saturated {
+                continue;
+            }
+
+            batch.push(entry);
+            if batch.len() >= batch_size {
+                scheduler.push(std::mem::replace(&mut batch, scheduler.buffer(batch_size)));
+            }
+        }
+        if walk.stopped_reason.is_some() {
+            halt.store(true, Ordering::Relaxed);
+        } else if !batch.is_empty() {
+            scheduler.push(batch);
+        }
+        scheduler.close();
+
+        for found in rx {
+            let (entry, score) = match found {
+                Ok(matched) => matched,
+                Err(err) => {
+                    walk.error(err);
+                    continue;
+                }
+            };
+            let Some(reason) = walk.stopped_reason else {
+                if walk.takes_matches() {
+                    let verdict = matching.accept(&entry, score);
+                    walk.matched(entry, verdict, on_match);
+                }
+                continue;
+            };
+            // Workers may still be finishing a batch; tell them to stop
+            halt.store(true, Ordering::Relaxed);
+            // Cancelling drains what was already matched, up to the limit;
+            // a limit or a failure drops it before the sink sees it
+            if reason == StopReason::Cancelled && walk.takes_drained() {
+                let verdict = matching.accept(&entry, score);
+                walk.drained(entry, verdict, on_match);
+            }
+        }
+    });
+
+    let available = start.elapsed().as_nanos() as f64 * threads as f64;
+    if available > 0.0 {
+        (idle_nanos.load(Ordering::Relaxed) as f64 / available * 100.0).min(100.0)
+    } else {
+        0.0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Walk state
+// ---------------------------------------------------------------------------
+
+/// Counts and outcome of a walk, owned by the consuming thread.
+struct Walk<'a> {
+    limit: Option<usize>,
+    max_depth: Option<usize>,
+    exclude: Option<&'a ExclusionSet>,
+    special_mounts: Option<&'a MountPoints>,
+    cancel: &'a CancelToken,
+    collect_errors: bool,
+    /// Keep counting entries after the limit is reached.
+    accurate_stats: bool,
+    /// The limit is reached but the walk goes on to count entries.
+    saturated: bool,
+    matches: usize,
+    /// Matches by kind: file, dir, symlink, other.
+    matched_kinds: [usize; 4],
+    /// Matches per depth, when a histogram was asked for.
+    match_depths: Option<Vec<usize>>,
+    start: Instant,
+    first_match: Option<Duration>,
+    files: usize,
+    dirs: usize,
+    errors: Vec<ParexError>,
+    completion: Completion,
+    stopped_reason: Option<StopReason>,
+    profiler: Option<&'a Profiler>,
+    /// Per-directory costs, for the profile or a slow-directory report.
+    dir_tally: Option<DirTally>,
+    /// Directory rollups, and the directories waiting for theirs.
+    summaries: Option<DirSummaries>,
+}
+
+impl Walk<'_> {
+    /// The next item from the source, timed when profiling.
+    fn pull(&mut self, entries: &mut Entries<'_>) -> Option<Result<Entry, ParexError>> {
+        if self.profiler.is_none() && self.dir_tally.is_none() {
+            return entries.next();
+        }
+        let start = Instant::now();
+        let item = entries.next();
+        let elapsed = start.elapsed();
+        if item.is_some()
+            && let Some(profiler) = self.profiler
+        {
+            profiler.source_pull(elapsed);
+        }
+        if let Some(Ok(entry)) = &item
+            && let Some(parent) = entry.path.parent()
+            && let Some(tally) = &mut self.dir_tally
+        {
+            tally.add(parent, elapsed);
+        }
+        item
+    }
+
+    /// Whether to stop before pulling the next entry.
+    fn should_stop(&mut self) -> bool {
+        if self.stopped_reason.is_some() {
+            return true;
+        }
+        if self.cancel.is_cancelled() {
+            self.completion = Completion::Cancelled;
+            self.stopped_reason = Some(StopReason::Cancelled);
+            return true;
+        }
+        // Enforce limit before processing next item
+        if let Some(lim) = self.limit
+            && self.matches >= lim
+        {
+            self.reach_limit();
+            return self.stopped_reason.is_some();
+        }
+        false
+    }
+
+    /// Stop the walk at the match limit — or, for accurate stats, stop
+    /// matching but keep counting.
+    fn reach_limit(&mut self) {
+        if self.accurate_stats {
+            self.saturated = true;
+        } else {
+            self.stopped_reason = Some(StopReason::Limit);
+        }
+    }
+
+    /// Turn an item from the source into an entry to match, counting it.
+    ///
+    /// `None` for errors and for entries dropped before matching. A fatal
+    /// error also sets `stopped_reason`.
+    fn admit(&mut self, item: Result<Entry, ParexError>) -> Option<Entry> {
         let entry = match item {
             Ok(e) => e,
             Err(err) => {
-                if collect_errors && err.is_recoverable() {
-                    errors.push(err);
-                }
-                continue;
+                self.error(err);
+                return None;
             }
         };
 
+        // Enforced here too, so every source honours the limit the same way
+        if let Some(max) = self.max_depth
+            && entry.depth > max
+        {
+            return None;
+        }
+
+        // Excluded entries are dropped as if the source never produced them
+        if let Some(ex) = self.exclude
+            && ex.contains(&entry.path)
+        {
+            return None;
+        }
+        if let Some(mounts) = self.special_mounts
+            && mounts.covers(&entry.path, entry.depth)
+        {
+            return None;
+        }
+
+        if let Some(profiler) = self.profiler {
+            profiler.entry();
+        }
+
         // Count by kind
         match entry.kind {
-            crate::entry::EntryKind::Dir => dirs += 1,
-            crate::entry::EntryKind::File => files += 1,
+            EntryKind::Dir => self.dirs += 1,
+            EntryKind::File => self.files += 1,
             _ => {}
         }
 
-        if !matcher.is_match(&entry) {
-            continue;
+        if let Some(summaries) = &mut self.summaries {
+            summaries.add(&entry);
+            // Matched once everything below it has been seen
+            if entry.kind == EntryKind::Dir {
+                summaries.hold(entry);
+                return None;
+            }
         }
 
-        matches += 1;
+        Some(entry)
+    }
 
-        if collect_paths {
-            paths.push(entry.path.clone());
+    /// Whether [`matched()`](Self::matched) would count a match, so it is
+    /// only handed to the sink if so.
+    fn takes_matches(&self) -> bool {
+        self.stopped_reason.is_none() && !self.saturated
+    }
+
+    /// Whether [`drained()`](Self::drained) would count a match.
+    fn takes_drained(&self) -> bool {
+        !self.saturated && self.limit.is_none_or(|lim| self.matches < lim)
+    }
+
+    /// Record a match and its verdict, setting `stopped_reason` if the walk
+    /// should end.
+    fn matched(
+        &mut self,
+        entry: Entry,
+        Verdict { score, sink_err }: Verdict,
+        on_match: &mut impl FnMut(Entry, Option<f32>),
+    ) {
+        if !self.takes_matches() {
+            return;
         }
+        self.count(entry, score, on_match);
 
-        if let Some(lim) = limit && matches >= lim {
-            break;
+        match sink_err {
+            Some(err) if err.is_fatal() => {
+                self.fail(err);
+                return;
+            }
+            Some(err) if self.collect_errors => self.errors.push(err),
+            _ => {}
+        }
+
+        if let Some(lim) = self.limit
+            && self.matches >= lim
+        {
+            self.reach_limit();
         }
     }
 
-    let duration = start.elapsed();
+    /// Record a match a worker finished after the search was cancelled.
+    ///
+    /// Sink errors are kept but cannot fail a search that is already
+    /// stopping.
+    fn drained(
+        &mut self,
+        entry: Entry,
+        Verdict { score, sink_err }: Verdict,
+        on_match: &mut impl FnMut(Entry, Option<f32>),
+    ) {
+        if !self.takes_drained() {
+            return;
+        }
+        self.count(entry, score, on_match);
+        if let Some(err) = sink_err
+            && self.collect_errors
+        {
+            self.errors.push(err);
+        }
+    }
 
-    let matches = match limit {
-        Some(lim) => matches.min(lim),
-        None => matches,
-    };
+    /// Tally a match and hand it on.
+    fn count(
+        &mut self,
+        entry: Entry,
+        score: Option<f32>,
+        on_match: &mut impl FnMut(Entry, Option<f32>),
+    ) {
+        if self.matches == 0 {
+            self.first_match = Some(self.start.elapsed());
+        }
+        self.matches += 1;
+        self.matched_kinds[match entry.kind {
+            EntryKind::File => 0,
+            EntryKind::Dir => 1,
+            EntryKind::Symlink => 2,
+            EntryKind::Other => 3,
+        }] += 1;
+        if let Some(depths) = &mut self.match_depths {
+            if depths.len() <= entry.depth {
+                depths.resize(entry.depth + 1, 0);
+            }
+            depths[entry.depth] += 1;
+        }
+        on_match(entry, score);
+    }
 
-    Results {
-        matches,
-        paths,
-        stats: ScanStats::compute(files, dirs, duration),
-        errors,
+    /// Record an error from the source or the matcher. A fatal one stops
+    /// the walk, unless it is already stopping.
+    fn error(&mut self, err: ParexError) {
+        if err.is_fatal() && self.stopped_reason.is_none() {
+            self.fail(err);
+        } else if self.collect_errors {
+            self.errors.push(err);
+        }
+    }
+
+    fn fail(&mut self, err: ParexError) {
+        self.completion = Completion::Failed(err);
+        self.stopped_reason = Some(StopReason::Failed);
+    }
+}
+
+/// What the consuming thread learns about a match besides the entry.
+struct Verdict {
+    /// The score a `ScoredMatcher` gave the entry.
+    score: Option<f32>,
+    /// The sink's error, if it failed to accept the entry.
+    sink_err: Option<ParexError>,
+}
+
+/// The per-entry work done on whichever thread matches the entry.
+struct Matching<'a> {
+    matcher: &'a dyn Matcher,
+    sink: Option<&'a dyn Sink>,
+    metadata_mode: MetadataMode,
+    warnings: &'a Warnings,
+    profiler: Option<&'a Profiler>,
+    /// Cloned for each thread that pulls or matches entries.
+    yield_points: &'a YieldPoints,
+    /// Attached on matcher threads, so remote matchers can propagate it.
+    #[cfg(feature = "otel")]
+    trace: &'a opentelemetry::Context,
+}
+
+impl Matching<'_> {
+    /// `Ok(Some)` with the entry's score, if it was given one, when `entry`
+    /// matches.
+    fn is_match(&self, entry: &Entry) -> Result<Option<Option<f32>>, ParexError> {
+        self.is_match_with(entry, None)
+    }
+
+    fn is_match_with(
+        &self,
+        entry: &Entry,
+        summary: Option<&DirSummary>,
+    ) -> Result<Option<Option<f32>>, ParexError> {
+        let mut ctx = MatchContext::with_metadata_mode(entry, self.metadata_mode)
+            .with_warnings(self.warnings);
+        if let Some(summary) = summary {
+            ctx = ctx.with_dir_summary(summary);
+        }
+        let Some(profiler) = self.profiler else {
+            return Ok(self
+                .matcher
+                .try_match_ctx(entry, &ctx)?
+                .then(|| ctx.score()));
+        };
+        let ctx = ctx.with_profiler(profiler);
+        let start = Instant::now();
+        let matched = self.matcher.try_match_ctx(entry, &ctx);
+        profiler.matched(start.elapsed());
+        Ok(matched?.then(|| ctx.score()))
+    }
+
+    /// Hand a match to the sink, returning its verdict.
+    fn accept(&self, entry: &Entry, score: Option<f32>) -> Verdict {
+        Verdict {
+            score,
+            sink_err: self.sink.and_then(|s| s.accept(entry).err()),
+        }
+    }
+
+    /// `Ok(Some)` with the match's verdict when `entry` matches; `Err`
+    /// when the matcher failed.
+    fn check(&self, entry: &Entry) -> Result<Option<Verdict>, ParexError> {
+        Ok(self.is_match(entry)?.map(|score| self.accept(entry, score)))
+    }
+
+    /// [`check()`](Self::check) for a directory and its summary.
+    fn check_summarized(
+        &self,
+        dir: &Entry,
+        summary: &DirSummary,
+    ) -> Result<Option<Verdict>, ParexError> {
+        Ok(self
+            .is_match_with(dir, Some(summary))?
+            .map(|score| self.accept(dir, score)))
     }
 }
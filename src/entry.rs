@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// A single item produced by a [`Source`](crate::traits::Source) during traversal.
 ///
@@ -13,6 +15,12 @@ use std::path::PathBuf;
 /// `metadata` is populated lazily — only when a matcher explicitly requests it
 /// (e.g. a `StaleMatcher`). This avoids unnecessary `stat()` syscalls on every
 /// entry when no metadata-aware matcher is in use.
+///
+/// Outside parex, build entries with [`Entry::new`] and the `with_*`
+/// methods; the struct is `#[non_exhaustive]`, so new optional fields are
+/// not breaking changes.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Entry {
     /// Full path to the entry.
     pub path: PathBuf,
@@ -20,20 +28,156 @@ pub struct Entry {
     /// What kind of entry this is.
     pub kind: EntryKind,
 
-    /// How deep in the traversal this entry was found. Root = 0.
+    /// How deep in the traversal this entry was found.
+    ///
+    /// The root the source was asked to walk is depth 0, its direct
+    /// children are depth 1, and so on. Every source must follow this —
+    /// `max_depth`, [`MatchContext::relative_path`](crate::MatchContext::relative_path),
+    /// and depth-based matchers all rely on it. Walkers that number the
+    /// first children 0 should add one, or use [`Entry::depth_under`].
     pub depth: usize,
 
     /// Filesystem metadata, populated on demand.
     /// Matchers that need it (e.g. for modification time) call
     /// `std::fs::metadata(&entry.path)` themselves and cache the result here.
     pub metadata: Option<std::fs::Metadata>,
+
+    /// Application-level tags attached by an [`Overlay`](crate::Overlay).
+    /// Sources normally leave this `None`; the engine fills it in when
+    /// `.overlay()` is set on the builder.
+    pub tags: Option<Arc<Tags>>,
+
+    /// Which source or root produced this entry, for searches that combine
+    /// several. Set by a [`MultiSource`](crate::multi::MultiSource), or by a
+    /// source that walks more than one root; `None` otherwise.
+    ///
+    /// Lets consumers attribute matches without comparing path prefixes,
+    /// which fails when roots overlap or are not paths at all.
+    pub source_id: Option<Arc<str>>,
+
+    /// An identity that survives renames, set by sources that know one —
+    /// a database row id, an object store version id, a Windows file ID.
+    /// `None` otherwise; see [`Entry::stable_id`] for the Unix fallback.
+    pub id: Option<EntryId>,
 }
 
+impl Entry {
+    /// An entry at `path`, `depth` levels below the root, with every
+    /// optional field `None`.
+    ///
+    /// ```rust
+    /// use parex::{Entry, EntryKind};
+    ///
+    /// let entry = Entry::new("docs/readme.md", EntryKind::File, 2).with_source_id("docs");
+    /// assert_eq!(entry.source_id.as_deref(), Some("docs"));
+    /// ```
+    pub fn new(path: impl Into<PathBuf>, kind: EntryKind, depth: usize) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+            depth,
+            metadata: None,
+            tags: None,
+            source_id: None,
+            id: None,
+        }
+    }
+
+    /// Attach metadata the source already has, sparing matchers a `stat()`.
+    pub fn with_metadata(mut self, metadata: std::fs::Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Attach application-level tags.
+    pub fn with_tags(mut self, tags: Arc<Tags>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Record which source or root produced the entry.
+    pub fn with_source_id(mut self, source_id: impl Into<Arc<str>>) -> Self {
+        self.source_id = Some(source_id.into());
+        self
+    }
+
+    /// Set an identity that survives renames.
+    pub fn with_id(mut self, id: EntryId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// The depth of `path` below `root`, per the [`depth`](Self::depth)
+    /// convention: `root` itself is 0, its children are 1.
+    ///
+    /// `None` if `path` is not under `root`.
+    pub fn depth_under(root: &Path, path: &Path) -> Option<usize> {
+        path.strip_prefix(root)
+            .ok()
+            .map(|rel| rel.components().count())
+    }
+
+    /// The entry's identity across scans: [`id`](Self::id) if the source
+    /// set one, otherwise the device and inode from
+    /// [`metadata`](Self::metadata) on Unix.
+    ///
+    /// `None` when neither is available. Matchers that need an identity for
+    /// entries without cached metadata can use
+    /// [`MatchContext::stable_id`](crate::MatchContext::stable_id), which
+    /// `stat()`s on demand.
+    pub fn stable_id(&self) -> Option<EntryId> {
+        self.id
+            .clone()
+            .or_else(|| self.metadata.as_ref().and_then(EntryId::from_metadata))
+    }
+}
+
+/// What makes an entry the same entry across renames and scans.
+///
+/// Two entries with equal ids are the same file (or record), whatever their
+/// paths. Ids are only comparable within one source: inode numbers are
+/// reused after a file is deleted, and keys mean whatever their source says.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum EntryId {
+    /// A Unix device and inode number.
+    Inode { dev: u64, ino: u64 },
+
+    /// A key chosen by the source.
+    Key(String),
+}
+
+impl EntryId {
+    /// The device and inode of the file `meta` describes. `None` on
+    /// platforms without inode numbers.
+    pub fn from_metadata(meta: &std::fs::Metadata) -> Option<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Some(Self::Inode {
+                dev: meta.dev(),
+                ino: meta.ino(),
+            })
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = meta;
+            None
+        }
+    }
+}
+
+/// Application-level key/value labels for an entry, such as those loaded
+/// from a sidecar database by an [`Overlay`](crate::Overlay).
+pub type Tags = BTreeMap<String, String>;
+
 /// The kind of a traversed entry.
 ///
 /// Kept generic so parex can represent non-filesystem sources cleanly.
 /// Filesystem sources map `DirEntry` file types to these variants.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntryKind {
     /// A regular file.
     File,
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -24,10 +25,16 @@ pub enum ParexError {
     #[error("invalid thread count: {0}")]
     InvalidThreadCount(usize),
 
+    #[error("cannot be saved in a search configuration: {0}")]
+    Unrepresentable(String),
+
     // Runtime
     #[error("thread pool failure: {0}")]
     ThreadPool(String),
 
+    #[error("signal handler error: {0}")]
+    Signal(String),
+
     #[error("IO error at {path}")]
     Io {
         path: PathBuf,
@@ -35,6 +42,20 @@ pub enum ParexError {
         source: std::io::Error,
     },
 
+    // Persistence
+    #[error("corrupt file {path}: {reason}")]
+    Corrupt { path: PathBuf, reason: String },
+
+    #[error("{path} uses format version {found}; this build reads up to {supported}")]
+    UnsupportedFormat {
+        path: PathBuf,
+        found: u32,
+        supported: u32,
+    },
+
+    #[error("{0} is encrypted; a key is required to read it")]
+    KeyRequired(PathBuf),
+
     // Third-party extensibility — Box<dyn Error> preserves original error type
     // and enables proper chaining via thiserror's #[source]
     #[error("source error: {0}")]
@@ -53,21 +74,52 @@ impl ParexError {
             | Self::NotFound(p)
             | Self::InvalidSource(p)
             | Self::SymlinkLoop(p)
-            | Self::Io { path: p, .. } => Some(p),
+            | Self::KeyRequired(p)
+            | Self::Io { path: p, .. }
+            | Self::Corrupt { path: p, .. }
+            | Self::UnsupportedFormat { path: p, .. } => Some(p),
             _ => None,
         }
     }
 
+    /// The stable category of this error.
+    ///
+    /// Branch on this instead of matching variants — `ParexError` is
+    /// `#[non_exhaustive]`, and new variants map onto existing codes.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::PermissionDenied(_) => ErrorCode::Permission,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::SymlinkLoop(_) => ErrorCode::Loop,
+            Self::Io { .. } => ErrorCode::Io,
+            Self::Corrupt { .. } => ErrorCode::Corrupt,
+            Self::UnsupportedFormat { .. } => ErrorCode::Unsupported,
+            Self::InvalidSource(_)
+            | Self::InvalidPattern(_)
+            | Self::InvalidThreadCount(_)
+            | Self::Unrepresentable(_)
+            | Self::KeyRequired(_) => ErrorCode::Config,
+            Self::ThreadPool(_) | Self::Signal(_) => ErrorCode::Runtime,
+            Self::Source(_) => ErrorCode::Source,
+            Self::Matcher(_) => ErrorCode::Matcher,
+        }
+    }
+
     /// Whether the search can continue after this error.
     ///
-    /// Recoverable errors (permission denied, not found, symlink loops, IO)
-    /// are collected and surfaced after the search completes — the walk keeps going.
+    /// Recoverable errors (permission denied, not found, symlink loops, IO,
+    /// matcher errors) are collected and surfaced after the search
+    /// completes — the walk keeps going.
     ///
     /// Fatal errors (invalid source, thread pool failure) should halt immediately.
     pub fn is_recoverable(&self) -> bool {
         matches!(
             self,
-            Self::PermissionDenied(_) | Self::NotFound(_) | Self::SymlinkLoop(_) | Self::Io { .. }
+            Self::PermissionDenied(_)
+                | Self::NotFound(_)
+                | Self::SymlinkLoop(_)
+                | Self::Io { .. }
+                | Self::Matcher(_)
         )
     }
 
@@ -92,3 +144,79 @@ impl ParexError {
         Self::Matcher(Box::new(e))
     }
 }
+
+// ---------------------------------------------------------------------------
+// ErrorCode
+// ---------------------------------------------------------------------------
+
+/// A stable error category, returned by [`ParexError::code`].
+///
+/// Numeric values and names never change between releases, so programs and
+/// FFI consumers can persist them or switch on them across versions. New
+/// codes may be added; existing ones are never renumbered or reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u16)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// Access to a path was denied.
+    Permission = 1,
+
+    /// A path disappeared or never existed.
+    NotFound = 2,
+
+    /// A symlink cycle was detected.
+    Loop = 3,
+
+    /// Any other IO failure at a path.
+    Io = 4,
+
+    /// The search was misconfigured — no or invalid source, bad pattern,
+    /// bad thread count.
+    Config = 5,
+
+    /// The runtime failed — threads could not be spawned, a signal handler
+    /// could not be installed.
+    Runtime = 6,
+
+    /// A third-party source failed.
+    Source = 7,
+
+    /// A third-party matcher failed.
+    Matcher = 8,
+
+    /// A file parex wrote earlier is damaged or truncated.
+    Corrupt = 9,
+
+    /// A file parex wrote earlier uses a format this build cannot read —
+    /// usually one written by a newer release.
+    Unsupported = 10,
+}
+
+impl ErrorCode {
+    /// The stable numeric value — the same as `code as u16`.
+    pub fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// The stable upper-case name, e.g. `"PERMISSION"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Permission => "PERMISSION",
+            Self::NotFound => "NOT_FOUND",
+            Self::Loop => "LOOP",
+            Self::Io => "IO",
+            Self::Config => "CONFIG",
+            Self::Runtime => "RUNTIME",
+            Self::Source => "SOURCE",
+            Self::Matcher => "MATCHER",
+            Self::Corrupt => "CORRUPT",
+            Self::Unsupported => "UNSUPPORTED",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// ---------------------------------------------------------------------------
+// ExclusionSet
+// ---------------------------------------------------------------------------
+
+/// A set of paths to exclude from a search, built for very large blocklists.
+///
+/// An entry is excluded when its path — or any of its ancestors — is in the
+/// set. Every lookup first consults a compact bloom filter; only paths the
+/// filter cannot rule out reach the exact `HashSet` check. With tens of
+/// thousands of excluded paths the filter stays cache-resident, keeping the
+/// per-entry cost near-constant for the common case of a path that is not
+/// excluded.
+///
+/// Cheap to clone — clones share the same filter and set, so one blocklist
+/// can be built once and reused across searches.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::ExclusionSet;
+/// use std::path::Path;
+///
+/// let set = ExclusionSet::new(["/data/cache", "/data/tmp/big.iso"]);
+///
+/// assert!(set.contains(Path::new("/data/cache/blob.bin")));
+/// assert!(set.contains(Path::new("/data/tmp/big.iso")));
+/// assert!(!set.contains(Path::new("/data/report.txt")));
+/// ```
+#[derive(Clone)]
+pub struct ExclusionSet {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    bloom: Bloom,
+    exact: HashSet<PathBuf>,
+}
+
+impl ExclusionSet {
+    /// Build a set from any collection of paths.
+    pub fn new<I, P>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let exact: HashSet<PathBuf> = paths.into_iter().map(Into::into).collect();
+        let mut bloom = Bloom::with_capacity(exact.len());
+        for path in &exact {
+            bloom.insert(path);
+        }
+        Self {
+            inner: Arc::new(Inner { bloom, exact }),
+        }
+    }
+
+    /// Whether `path` or any of its ancestors is excluded.
+    pub fn contains(&self, path: &Path) -> bool {
+        if self.inner.exact.is_empty() {
+            return false;
+        }
+        path.ancestors().any(|p| {
+            !p.as_os_str().is_empty()
+                && self.inner.bloom.may_contain(p)
+                && self.inner.exact.contains(p)
+        })
+    }
+
+    /// The excluded paths, in no particular order.
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.inner.exact.iter().map(PathBuf::as_path)
+    }
+
+    /// Number of excluded paths.
+    pub fn len(&self) -> usize {
+        self.inner.exact.len()
+    }
+
+    /// Whether the set excludes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.inner.exact.is_empty()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Bloom filter
+// ---------------------------------------------------------------------------
+
+/// Bits allocated per inserted path — ~1% false positives with `HASHES`.
+const BITS_PER_ITEM: usize = 10;
+
+/// Probes per lookup, derived from one 64-bit hash by double hashing.
+const HASHES: u64 = 7;
+
+struct Bloom {
+    bits: Vec<u64>,
+}
+
+impl Bloom {
+    fn with_capacity(items: usize) -> Self {
+        let words = (items * BITS_PER_ITEM).div_ceil(64).max(1);
+        Self {
+            bits: vec![0; words],
+        }
+    }
+
+    fn insert(&mut self, path: &Path) {
+        let len = self.len();
+        for bit in probes(path, len) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    fn may_contain(&self, path: &Path) -> bool {
+        probes(path, self.len()).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    fn len(&self) -> u64 {
+        self.bits.len() as u64 * 64
+    }
+}
+
+/// Bit positions for `path` — Kirsch–Mitzenmacher double hashing.
+fn probes(path: &Path, len: u64) -> impl Iterator<Item = usize> {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    let hash = hasher.finish();
+    let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+    (0..HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+}
@@ -0,0 +1,160 @@
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::thread::JoinHandle;
+
+use crate::engine::{CancelToken, EngineOptions, execute};
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::results::Results;
+
+// ---------------------------------------------------------------------------
+// MatchIter
+// ---------------------------------------------------------------------------
+
+/// A blocking [`Iterator`] of matched entries, produced by a background
+/// search.
+///
+/// Created via [`SearchBuilder::run_iter()`](crate::SearchBuilder::run_iter).
+/// The walk runs on a dedicated worker thread and each match is sent to
+/// the iterator as soon as it is found, so processing starts before the
+/// walk ends. `next()` blocks until the next match arrives, and returns
+/// `None` once the walk is over. Results are unordered, as with
+/// [`run()`](crate::SearchBuilder::run).
+///
+/// With [`EngineTuning::stream_capacity`](crate::engine::EngineTuning::stream_capacity)
+/// set, the worker pauses once that many matches are waiting, so a slow
+/// consumer applies backpressure to the walk.
+///
+/// Dropping the iterator cancels the search: the worker stops before
+/// pulling its next entry, so breaking out of a `for` loop never leaves a
+/// walk running in the background. Call [`finish()`](Self::finish) instead
+/// to let the walk complete and get its [`Results`].
+///
+/// # Example
+///
+/// ```rust
+/// # use parex::{Source, Entry, EntryKind, ParexError};
+/// # use parex::engine::WalkConfig;
+/// # struct Files(Vec<&'static str>);
+/// # impl Source for Files {
+/// #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+/// #         let v: Vec<_> = self.0.iter().map(|n| Ok(Entry::new(n, EntryKind::File, 1))).collect();
+/// #         Box::new(v.into_iter())
+/// #     }
+/// # }
+/// let files = Files(vec!["a.log", "b.txt", "c.log", "d.log"]);
+///
+/// // Stop at the first log; the rest of the walk is abandoned
+/// let first = parex::search()
+///     .source(files)
+///     .matching(".log")
+///     .run_iter()?
+///     .next();
+/// assert!(first.is_some());
+/// # Ok::<(), ParexError>(())
+/// ```
+pub struct MatchIter {
+    rx: Option<Receiver<Entry>>,
+    worker: Option<JoinHandle<Results>>,
+    /// The worker's outcome, once the iterator has run dry and joined it.
+    ended: Option<std::thread::Result<Results>>,
+    cancel: CancelToken,
+}
+
+/// The sending half, bounded when `stream_capacity` is set.
+enum Tx {
+    Unbounded(Sender<Entry>),
+    Bounded(SyncSender<Entry>),
+}
+
+impl Tx {
+    /// Send a match. An iterator that has gone away is ignored — the walk
+    /// still counts the match, and stops on cancellation by itself.
+    fn send(&self, entry: Entry) {
+        let _ = match self {
+            Self::Unbounded(tx) => tx.send(entry),
+            Self::Bounded(tx) => tx.send(entry),
+        };
+    }
+}
+
+impl MatchIter {
+    /// Spawn the worker thread and start walking.
+    pub(crate) fn spawn(opts: EngineOptions) -> Result<Self, ParexError> {
+        let cancel = opts.config.cancel.clone();
+        let (tx, rx) = match opts.tuning.stream_capacity {
+            // A zero-capacity channel would make every match a rendezvous
+            Some(cap) => {
+                let (tx, rx) = mpsc::sync_channel(cap.max(1));
+                (Tx::Bounded(tx), rx)
+            }
+            None => {
+                let (tx, rx) = mpsc::channel();
+                (Tx::Unbounded(tx), rx)
+            }
+        };
+
+        let worker = std::thread::Builder::new()
+            .name("parex-iter".into())
+            .spawn(move || execute(opts, |entry, _| tx.send(entry)))
+            .map_err(|e| ParexError::ThreadPool(e.to_string()))?;
+
+        Ok(Self {
+            rx: Some(rx),
+            worker: Some(worker),
+            ended: None,
+            cancel,
+        })
+    }
+
+    /// Stop the search early, keeping any matches already sent.
+    ///
+    /// The iterator yields what was found so far and then ends.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Wait for the walk to end and return its [`Results`] — counts,
+    /// stats, errors, and how it completed. `paths` is empty; matches not
+    /// yet taken from the iterator are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Resumes a panic raised by the source or matcher on the worker thread.
+    pub fn finish(mut self) -> Results {
+        // Unblocks a worker waiting for room in a bounded channel
+        drop(self.rx.take());
+        let ended = match self.ended.take() {
+            Some(ended) => ended,
+            None => self.worker.take().expect("joined once").join(),
+        };
+        ended.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+impl Iterator for MatchIter {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Entry> {
+        match self.rx.as_ref()?.recv() {
+            Ok(entry) => Some(entry),
+            // The worker dropped its sender: the walk is over
+            Err(_) => {
+                if let Some(worker) = self.worker.take() {
+                    self.ended = Some(worker.join());
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Drop for MatchIter {
+    fn drop(&mut self) {
+        // Not joined — the worker may be blocked inside the source. A
+        // search already over leaves the token alone, since the caller may
+        // share it with other searches.
+        if self.worker.is_some() {
+            self.cancel();
+        }
+    }
+}
@@ -18,12 +18,7 @@
 //!
 //! impl Source for NameSource {
 //!     fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
-//!         let entries = self.0.iter().map(|name| Ok(Entry {
-//!             path:     name.into(),
-//!             kind:     EntryKind::File,
-//!             depth:    0,
-//!             metadata: None,
-//!         })).collect::<Vec<_>>();
+//!         let entries = self.0.iter().map(|name| Ok(Entry::new(name, EntryKind::File, 0))).collect::<Vec<_>>();
 //!         Box::new(entries.into_iter())
 //!     }
 //! }
@@ -54,12 +49,7 @@
 //!
 //! impl Source for VecSource {
 //!     fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
-//!         let entries = self.0.iter().map(|name| Ok(Entry {
-//!             path:     name.into(),
-//!             kind:     EntryKind::File,
-//!             depth:    0,
-//!             metadata: None,
-//!         })).collect::<Vec<_>>();
+//!         let entries = self.0.iter().map(|name| Ok(Entry::new(name, EntryKind::File, 0))).collect::<Vec<_>>();
 //!         Box::new(entries.into_iter())
 //!     }
 //! }
@@ -84,21 +74,97 @@
 
 #![forbid(unsafe_code)]
 
+pub mod aggregate;
+#[cfg(feature = "tokio")]
+pub mod asynch;
+pub mod audit_log;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod cache;
+#[cfg(all(target_os = "linux", feature = "cgroup"))]
+pub mod cgroup;
+#[cfg(feature = "test-util")]
+pub mod conformance;
+#[cfg(feature = "daemon")]
+pub mod daemon;
 pub mod engine;
+pub mod matchers;
+pub mod mounts;
+pub mod multi;
+pub mod notify;
+#[cfg(feature = "power")]
+pub mod power;
+pub mod profile;
+#[cfg(feature = "queue")]
+pub mod queue;
+pub mod rank;
+pub mod rules;
+pub mod snapshot;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod state;
+#[cfg(feature = "storage")]
+pub mod storage;
+pub mod verify;
 
+#[cfg(feature = "futures")]
+mod async_source;
+mod batch;
 mod builder;
+mod combine;
+mod config;
+mod context;
 mod entry;
 mod error;
+mod exclude;
+mod iter;
+#[cfg(feature = "otel")]
+mod otel;
+mod overlay;
+mod persist;
+mod plan;
+#[cfg(feature = "prefetch")]
+mod prefetch;
 mod results;
+mod scheduler;
+#[cfg(feature = "encryption")]
+mod seal;
+#[cfg(feature = "signals")]
+mod signals;
+mod skip;
+mod sort;
+mod stat_ahead;
+#[cfg(feature = "futures")]
+mod stream;
+mod summary;
 mod traits;
+mod warning;
 
 // ── Public re-exports ─────────────────────────────────────────────────────────
 
-pub use builder::SearchBuilder;
-pub use entry::{Entry, EntryKind};
-pub use error::ParexError;
-pub use results::{Results, ScanStats};
-pub use traits::{Matcher, Source};
+#[cfg(feature = "futures")]
+pub use async_source::{AsyncSource, EntryStream};
+pub use builder::{MatchCase, SearchBuilder};
+pub use combine::{AndMatcher, NotMatcher, OrMatcher};
+pub use config::SearchConfig;
+pub use context::MatchContext;
+pub use entry::{Entry, EntryId, EntryKind, Tags};
+pub use error::{ErrorCode, ParexError};
+pub use exclude::ExclusionSet;
+pub use iter::MatchIter;
+pub use mounts::{PSEUDO_FILESYSTEMS, SpecialMounts};
+pub use overlay::Overlay;
+pub use plan::{Cost, Plan};
+pub use results::{Completion, Page, Results, ScanStats, StopReason};
+pub use skip::{COMMON_BLOAT, SkipDirs};
+#[cfg(feature = "collation")]
+pub use sort::Collation;
+pub use sort::SortOrder;
+#[cfg(feature = "futures")]
+pub use stream::MatchStream;
+pub use summary::DirSummary;
+pub use traits::{Matcher, Sink, Source};
+pub use warning::{Warning, WarningKind};
 
 // ── Entry point ───────────────────────────────────────────────────────────────
 
@@ -114,10 +180,7 @@ pub use traits::{Matcher, Source};
 ///
 /// impl Source for NameSource {
 ///     fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
-///         let entries = self.0.iter().map(|name| Ok(Entry {
-///             path: name.into(),
-///             kind: EntryKind::File, depth: 0, metadata: None,
-///         })).collect::<Vec<_>>();
+///         let entries = self.0.iter().map(|name| Ok(Entry::new(name, EntryKind::File, 0))).collect::<Vec<_>>();
 ///         Box::new(entries.into_iter())
 ///     }
 /// }
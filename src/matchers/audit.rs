@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+
+use crate::context::MatchContext;
+use crate::entry::{Entry, EntryKind};
+use crate::error::ParexError;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+const S_ISUID: u32 = 0o4000;
+const S_ISGID: u32 = 0o2000;
+const S_IWOTH: u32 = 0o0002;
+
+// ---------------------------------------------------------------------------
+// Permission bits
+// ---------------------------------------------------------------------------
+
+/// Matches files and directories writable by any user (`o+w`).
+///
+/// Symlinks are skipped — their own mode is always `0777` and says nothing
+/// about the target.
+pub struct WorldWritable;
+
+impl Matcher for WorldWritable {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        entry.kind != EntryKind::Symlink
+            && ctx
+                .metadata()
+                .is_some_and(|m| m.permissions().mode() & S_IWOTH != 0)
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
+
+/// Matches regular files with the setuid or setgid bit set.
+pub struct SetuidSetgid;
+
+impl Matcher for SetuidSetgid {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        entry.kind == EntryKind::File
+            && ctx
+                .metadata()
+                .is_some_and(|m| m.permissions().mode() & (S_ISUID | S_ISGID) != 0)
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OrphanedOwner
+// ---------------------------------------------------------------------------
+
+/// Matches entries owned by a UID that no known account uses.
+///
+/// The set of known UIDs is captured once at construction — typically from
+/// `/etc/passwd` via [`from_passwd()`](Self::from_passwd). Accounts resolved
+/// only through NSS (LDAP, SSSD) are not visible there; use
+/// [`with_known_uids()`](Self::with_known_uids) to supply them.
+pub struct OrphanedOwner {
+    known: HashSet<u32>,
+}
+
+impl OrphanedOwner {
+    /// Load known UIDs from `/etc/passwd`.
+    pub fn from_passwd() -> Result<Self, ParexError> {
+        Self::from_passwd_file(Path::new("/etc/passwd"))
+    }
+
+    /// Load known UIDs from a passwd-format file.
+    pub fn from_passwd_file(path: &Path) -> Result<Self, ParexError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ParexError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        // name:password:uid:gid:gecos:home:shell
+        let known = text
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .filter_map(|l| l.split(':').nth(2)?.parse().ok())
+            .collect();
+
+        Ok(Self { known })
+    }
+
+    /// Use an explicit set of known UIDs.
+    pub fn with_known_uids(uids: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            known: uids.into_iter().collect(),
+        }
+    }
+}
+
+impl Matcher for OrphanedOwner {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        ctx.metadata()
+            .is_some_and(|m| !self.known.contains(&m.uid()))
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::context::MatchContext;
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// GlobMatcher
+// ---------------------------------------------------------------------------
+
+/// Include / exclude filtering by glob patterns, compiled into one
+/// [`GlobSet`] for each list.
+///
+/// Patterns are matched against the entry's path relative to the search
+/// root, with `/` as the separator on every platform:
+///
+/// - `*` and `?` stop at `/`; `**` crosses directories, so `src/**/*.rs`
+///   finds every Rust file under `src/`.
+/// - A pattern without a `/` matches the file name at any depth, so `*.rs`
+///   behaves like `**/*.rs`.
+/// - `{a,b}` alternates and `[a-z]` is a character class.
+///
+/// An entry matches when it matches an include pattern — or there are
+/// none — and neither it nor any directory above it matches an exclude
+/// pattern. Excluding a directory excludes everything in it, so a trailing
+/// `/**` on an exclude pattern is dropped — `target/**` still names only the
+/// top-level `target` — and excluded directories are
+/// [pruned](Matcher::prunes_dir) once [`root`](Self::root) is known.
+///
+/// Used by [`SearchBuilder::include_glob`](crate::SearchBuilder::include_glob)
+/// and [`exclude_glob`](crate::SearchBuilder::exclude_glob).
+///
+/// # Example
+///
+/// ```rust
+/// use parex::matchers::GlobMatcher;
+///
+/// let rust = GlobMatcher::new(["**/*.rs", "Cargo.toml"], ["target/**", ".git"])?;
+/// # let _ =
+/// parex::search().with_matcher(rust);
+/// # Ok::<(), parex::ParexError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlobMatcher {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    root: Option<PathBuf>,
+}
+
+impl GlobMatcher {
+    /// Match entries against `include`, dropping those under `exclude`.
+    /// Either list may be empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::InvalidPattern`] for the first pattern that
+    /// does not compile.
+    pub fn new<I, E, S, T>(include: I, exclude: E) -> Result<Self, ParexError>
+    where
+        I: IntoIterator<Item = S>,
+        E: IntoIterator<Item = T>,
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let include = compile(include, false)?;
+        let exclude = compile(exclude, true)?;
+        Ok(Self {
+            include: (!include.is_empty()).then_some(include),
+            exclude,
+            root: None,
+        })
+    }
+
+    /// The search root, so excluded directories can be pruned before the
+    /// source reads them. The builder sets it from
+    /// [`Source::root`](crate::Source::root).
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Whether `rel`, relative to the root, or a directory above it is
+    /// excluded.
+    fn excluded(&self, rel: &Path) -> bool {
+        !self.exclude.is_empty()
+            && rel
+                .ancestors()
+                .take_while(|p| !p.as_os_str().is_empty())
+                .any(|p| self.exclude.is_match(p))
+    }
+}
+
+/// Build one set from `patterns`, anchoring slash-less ones at any depth.
+///
+/// Anchoring is decided before `strip_dir_suffix` drops a trailing `/**`,
+/// so `target/**` stays rooted at `target` rather than becoming `**/target`.
+fn compile<I, S>(patterns: I, strip_dir_suffix: bool) -> Result<GlobSet, ParexError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut set = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        let glob = match pattern.strip_suffix("/**") {
+            Some(dir) if strip_dir_suffix => dir,
+            _ => pattern,
+        };
+        let full = if pattern.contains('/') {
+            glob.to_owned()
+        } else {
+            format!("**/{glob}")
+        };
+        let glob = GlobBuilder::new(&full)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| ParexError::InvalidPattern(format!("glob {pattern:?}: {e}")))?;
+        set.add(glob);
+    }
+    set.build()
+        .map_err(|e| ParexError::InvalidPattern(format!("glob set: {e}")))
+}
+
+impl Matcher for GlobMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        let rel = ctx.relative_path();
+        let included = self.include.as_ref().is_none_or(|set| set.is_match(rel));
+        included && !self.excluded(rel)
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
+
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        self.root
+            .as_deref()
+            .and_then(|root| dir.strip_prefix(root).ok())
+            .is_some_and(|rel| self.excluded(rel))
+    }
+}
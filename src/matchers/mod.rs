@@ -0,0 +1,76 @@
+//! Opt-in, feature-gated [`Matcher`](crate::Matcher) implementations.
+//!
+//! parex stays domain-neutral by default — every matcher here lives behind
+//! its own Cargo feature, so a plain `parex` dependency compiles none of them.
+
+#[cfg(all(unix, feature = "audit"))]
+mod audit;
+#[cfg(feature = "glob")]
+mod glob;
+#[cfg(feature = "pii")]
+mod pii;
+#[cfg(feature = "regex")]
+mod regex;
+#[cfg(feature = "retention")]
+mod retention;
+#[cfg(feature = "secrets")]
+mod secrets;
+#[cfg(all(unix, feature = "sparse"))]
+mod sparse;
+
+#[cfg(all(unix, feature = "audit"))]
+pub use audit::{OrphanedOwner, SetuidSetgid, WorldWritable};
+#[cfg(feature = "glob")]
+pub use glob::GlobMatcher;
+#[cfg(feature = "pii")]
+pub use pii::{PiiCategory, PiiMatcher};
+#[cfg(feature = "regex")]
+pub use regex::RegexMatcher;
+#[cfg(feature = "retention")]
+pub use retention::{LargerThan, OlderThan, RetentionPolicy, UnderPath};
+#[cfg(feature = "secrets")]
+pub use secrets::SecretsMatcher;
+#[cfg(all(unix, feature = "sparse"))]
+pub use sparse::SparseFileMatcher;
+
+/// Content matchers skip files larger than this by default.
+#[cfg(any(feature = "secrets", feature = "pii"))]
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Read a regular file for content matching.
+///
+/// `None` if the entry is not a regular file, is larger than `max_size`,
+/// cannot be read, or looks binary (a NUL byte in the first 8 KiB). Files
+/// skipped for size or as binary are reported through
+/// [`MatchContext::warn`](crate::MatchContext::warn).
+#[cfg(any(feature = "secrets", feature = "pii"))]
+fn read_text(ctx: &crate::MatchContext<'_>, max_size: u64) -> Option<Vec<u8>> {
+    use crate::warning::{Warning, WarningKind};
+
+    const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+    let entry = ctx.entry();
+    if entry.kind != crate::EntryKind::File {
+        return None;
+    }
+    let len = ctx.metadata()?.len();
+    if len > max_size {
+        ctx.warn(Warning::new(
+            WarningKind::SkippedTooLarge,
+            &entry.path,
+            format!("{len} bytes exceeds the {max_size}-byte limit"),
+        ));
+        return None;
+    }
+    let content = std::fs::read(&entry.path).ok()?;
+    let sniff = &content[..content.len().min(BINARY_SNIFF_LEN)];
+    if sniff.contains(&0) {
+        ctx.warn(Warning::new(
+            WarningKind::SkippedBinary,
+            &entry.path,
+            "NUL byte in the first 8 KiB",
+        ));
+        return None;
+    }
+    Some(content)
+}
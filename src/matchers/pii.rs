@@ -0,0 +1,204 @@
+use regex::bytes::Regex;
+
+use super::{DEFAULT_MAX_FILE_SIZE, read_text};
+use crate::context::MatchContext;
+use crate::entry::Entry;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// Categories
+// ---------------------------------------------------------------------------
+
+/// A kind of personally identifiable information detected by [`PiiMatcher`].
+///
+/// National identifiers are locale-specific; each is validated beyond its
+/// shape (reserved ranges, check digits) to cut false positives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PiiCategory {
+    /// An email address.
+    Email,
+
+    /// A phone number with 10–15 digits, optionally in `+` international form.
+    Phone,
+
+    /// A US Social Security Number (`AAA-GG-SSSS`), excluding never-issued ranges.
+    UsSsn,
+
+    /// A Canadian Social Insurance Number, validated with the Luhn check digit.
+    CaSin,
+
+    /// A Dutch citizen service number (BSN), validated with the eleven-test.
+    NlBsn,
+}
+
+impl PiiCategory {
+    /// Every supported category.
+    pub const ALL: [PiiCategory; 5] = [
+        PiiCategory::Email,
+        PiiCategory::Phone,
+        PiiCategory::UsSsn,
+        PiiCategory::CaSin,
+        PiiCategory::NlBsn,
+    ];
+
+    fn pattern(self) -> &'static str {
+        match self {
+            Self::Email => r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b",
+            // `[0-9]`, not `\d`: Unicode digits would reach `validate()`
+            Self::Phone => r"(?:\+|\b)[0-9][0-9 \-().]{8,18}[0-9]\b",
+            Self::UsSsn => r"\b[0-9]{3}-[0-9]{2}-[0-9]{4}\b",
+            Self::CaSin => r"\b[0-9]{3}[ -]?[0-9]{3}[ -]?[0-9]{3}\b",
+            Self::NlBsn => r"\b[0-9]{9}\b",
+        }
+    }
+
+    /// Validate a candidate beyond its shape. Candidates without the
+    /// category's digit count are rejected rather than indexed into.
+    fn validate(self, candidate: &[u8]) -> bool {
+        let digits: Vec<u32> = candidate
+            .iter()
+            .filter(|b| b.is_ascii_digit())
+            .map(|b| u32::from(b - b'0'))
+            .collect();
+
+        match self {
+            Self::Email => true,
+            Self::Phone => (10..=15).contains(&digits.len()),
+            Self::UsSsn | Self::CaSin | Self::NlBsn if digits.len() != 9 => false,
+            Self::UsSsn => {
+                let area = digits[0] * 100 + digits[1] * 10 + digits[2];
+                let group = digits[3] * 10 + digits[4];
+                let serial = digits[5..].iter().fold(0, |acc, d| acc * 10 + d);
+                area != 0 && area != 666 && area < 900 && group != 0 && serial != 0
+            }
+            Self::CaSin => digits[0] != 0 && digits[0] != 8 && luhn(&digits),
+            Self::NlBsn => {
+                // Eleven-test: 9·d1 + 8·d2 + … + 2·d8 − 1·d9 ≡ 0 (mod 11)
+                let sum: i64 = digits[..8]
+                    .iter()
+                    .zip((2..=9).rev())
+                    .map(|(&d, w)| i64::from(d) * w)
+                    .sum::<i64>()
+                    - i64::from(digits[8]);
+                sum != 0 && sum % 11 == 0
+            }
+        }
+    }
+}
+
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+// ---------------------------------------------------------------------------
+// PiiMatcher
+// ---------------------------------------------------------------------------
+
+/// Matches text files whose content contains personally identifiable information.
+///
+/// Only regular text files up to [`max_file_size`](Self::max_file_size) are
+/// read. Use [`scan_entry()`](Self::scan_entry) to report which categories
+/// were found in a given file.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::matchers::{PiiCategory, PiiMatcher};
+///
+/// let pii = PiiMatcher::new([PiiCategory::Email, PiiCategory::CaSin]);
+/// assert_eq!(pii.scan(b"contact: jane@example.com"), vec![PiiCategory::Email]);
+/// assert_eq!(pii.scan(b"SIN 130 692 544"), vec![PiiCategory::CaSin]);
+/// assert!(pii.scan(b"SIN 130 692 545").is_empty()); // bad check digit
+/// ```
+pub struct PiiMatcher {
+    rules: Vec<(PiiCategory, Regex)>,
+    max_file_size: u64,
+}
+
+impl PiiMatcher {
+    /// Detect the given categories.
+    pub fn new(categories: impl IntoIterator<Item = PiiCategory>) -> Self {
+        Self {
+            rules: categories
+                .into_iter()
+                .map(|c| {
+                    (
+                        c,
+                        Regex::new(c.pattern()).expect("built-in PII pattern is valid"),
+                    )
+                })
+                .collect(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+
+    /// Detect every supported category.
+    pub fn all() -> Self {
+        Self::new(PiiCategory::ALL)
+    }
+
+    /// Skip files larger than `bytes`. Defaults to 1 MiB.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// The categories found in `content`, in configuration order.
+    pub fn scan(&self, content: &[u8]) -> Vec<PiiCategory> {
+        self.rules
+            .iter()
+            .filter(|(category, regex)| {
+                regex
+                    .find_iter(content)
+                    .any(|m| category.validate(m.as_bytes()))
+            })
+            .map(|(category, _)| *category)
+            .collect()
+    }
+
+    /// The categories found in the entry's content.
+    ///
+    /// Empty if the entry is not a regular file, is too large, looks binary,
+    /// or cannot be read.
+    pub fn scan_entry(&self, entry: &Entry) -> Vec<PiiCategory> {
+        match read_text(&MatchContext::new(entry), self.max_file_size) {
+            Some(content) => self.scan(&content),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Matcher for PiiMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        read_text(ctx, self.max_file_size).is_some_and(|content| {
+            self.rules.iter().any(|(category, regex)| {
+                regex
+                    .find_iter(&content)
+                    .any(|m| category.validate(m.as_bytes()))
+            })
+        })
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Content
+    }
+}
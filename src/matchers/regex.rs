@@ -0,0 +1,90 @@
+use ::regex::bytes::Regex;
+
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// RegexMatcher
+// ---------------------------------------------------------------------------
+
+/// Matches entries whose file name — or, with [`full_path`](Self::full_path),
+/// whole path — contains a match for a regular expression.
+///
+/// The pattern is compiled once, in [`new()`](Self::new), and uses the
+/// [`regex`](https://docs.rs/regex) crate's syntax. Unlike `.matching()` it
+/// is case-sensitive unless the pattern says otherwise with `(?i)`; anchor
+/// it with `^` and `$` to match the whole name. Names are matched as raw
+/// bytes, so entries whose names are not valid UTF-8 are still checked.
+///
+/// [`SearchBuilder::matching_regex`](crate::SearchBuilder::matching_regex)
+/// is shorthand for the name form.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::matchers::RegexMatcher;
+///
+/// // Dated logs anywhere under a `logs` directory
+/// let logs = RegexMatcher::new(r"/logs/.*\d{4}-\d{2}-\d{2}\.log$")?.full_path(true);
+/// # let _ =
+/// parex::search().with_matcher(logs);
+/// # Ok::<(), parex::ParexError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegexMatcher {
+    regex: Regex,
+    full_path: bool,
+}
+
+impl RegexMatcher {
+    /// Match file names against `pattern`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::InvalidPattern`] if `pattern` does not compile.
+    pub fn new(pattern: &str) -> Result<Self, ParexError> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| ParexError::InvalidPattern(format!("regex {pattern:?}: {e}")))?;
+        Ok(Self {
+            regex,
+            full_path: false,
+        })
+    }
+
+    /// Match the whole path, as the source reports it, instead of only the
+    /// file name. Separators are the platform's. Default: `false`.
+    pub fn full_path(mut self, yes: bool) -> Self {
+        self.full_path = yes;
+        self
+    }
+}
+
+impl From<Regex> for RegexMatcher {
+    /// Match file names against an already compiled byte regex.
+    fn from(regex: Regex) -> Self {
+        Self {
+            regex,
+            full_path: false,
+        }
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        let haystack = if self.full_path {
+            entry.path.as_os_str()
+        } else {
+            match entry.path.file_name() {
+                Some(name) => name,
+                None => return false,
+            }
+        };
+        self.regex.is_match(haystack.as_encoded_bytes())
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
+}
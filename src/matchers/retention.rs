@@ -0,0 +1,200 @@
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::context::MatchContext;
+use crate::entry::Entry;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Time since last modification. `None` if unknown or in the future.
+fn age(meta: &Metadata) -> Option<Duration> {
+    meta.modified()
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+}
+
+/// Whether nothing at or below `dir` can be under `root`.
+fn outside(dir: &Path, root: &Path) -> bool {
+    !dir.starts_with(root) && !root.starts_with(dir)
+}
+
+// ---------------------------------------------------------------------------
+// Single-condition matchers
+// ---------------------------------------------------------------------------
+
+/// Matches entries last modified more than the given duration ago.
+///
+/// Entries whose modification time cannot be read never match.
+pub struct OlderThan(pub Duration);
+
+impl OlderThan {
+    /// Matches entries older than `n` days.
+    pub fn days(n: u64) -> Self {
+        Self(Duration::from_secs(n * 86_400))
+    }
+}
+
+impl Matcher for OlderThan {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        ctx.metadata().and_then(age).is_some_and(|a| a > self.0)
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
+
+/// Matches entries strictly larger than the given number of bytes.
+///
+/// Entries whose size cannot be read never match.
+pub struct LargerThan(pub u64);
+
+impl Matcher for LargerThan {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        ctx.metadata().is_some_and(|m| m.len() > self.0)
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
+
+/// Matches entries located under the given path (or equal to it).
+///
+/// Compared component-wise, so `/var/log` does not match `/var/logs/a`.
+pub struct UnderPath(pub PathBuf);
+
+impl Matcher for UnderPath {
+    fn is_match(&self, entry: &Entry) -> bool {
+        entry.path.starts_with(&self.0)
+    }
+
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        outside(dir, &self.0)
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RetentionPolicy
+// ---------------------------------------------------------------------------
+
+/// A retention rule: every configured condition must hold for an entry to match.
+///
+/// Conditions that are not set are ignored, so `RetentionPolicy::new()` on
+/// its own matches everything. Path checks run first, so entries outside the
+/// policy's scope never pay for a `stat()`.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::matchers::RetentionPolicy;
+///
+/// // Logs under /var/log older than 30 days and larger than 10 MiB.
+/// let policy = RetentionPolicy::new()
+///     .under("/var/log")
+///     .older_than_days(30)
+///     .larger_than(10 * 1024 * 1024);
+/// # let _ = policy;
+/// ```
+#[derive(Default)]
+pub struct RetentionPolicy {
+    under: Option<PathBuf>,
+    older_than: Option<Duration>,
+    larger_than: Option<u64>,
+}
+
+impl RetentionPolicy {
+    /// A policy with no conditions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only entries under `path`.
+    pub fn under(mut self, path: impl Into<PathBuf>) -> Self {
+        self.under = Some(path.into());
+        self
+    }
+
+    /// Only entries last modified more than `age` ago.
+    pub fn older_than(mut self, age: Duration) -> Self {
+        self.older_than = Some(age);
+        self
+    }
+
+    /// Only entries last modified more than `n` days ago.
+    pub fn older_than_days(self, n: u64) -> Self {
+        self.older_than(Duration::from_secs(n * 86_400))
+    }
+
+    /// Only entries larger than `bytes`.
+    pub fn larger_than(mut self, bytes: u64) -> Self {
+        self.larger_than = Some(bytes);
+        self
+    }
+}
+
+impl Matcher for RetentionPolicy {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        if let Some(root) = &self.under
+            && !entry.path.starts_with(root)
+        {
+            return false;
+        }
+
+        if self.older_than.is_none() && self.larger_than.is_none() {
+            return true;
+        }
+
+        // One stat() serves both metadata conditions
+        let Some(meta) = ctx.metadata() else {
+            return false;
+        };
+
+        if let Some(bytes) = self.larger_than
+            && meta.len() <= bytes
+        {
+            return false;
+        }
+
+        if let Some(min_age) = self.older_than
+            && age(meta).is_none_or(|a| a <= min_age)
+        {
+            return false;
+        }
+
+        true
+    }
+
+    fn cost(&self) -> Cost {
+        if self.older_than.is_none() && self.larger_than.is_none() {
+            Cost::Name
+        } else {
+            Cost::Metadata
+        }
+    }
+
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        self.under.as_ref().is_some_and(|root| outside(dir, root))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::context::MatchContext;
+use crate::entry::{Entry, EntryKind};
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// SparseFileMatcher
+// ---------------------------------------------------------------------------
+
+/// Matches regular files whose allocated size is well below their apparent size.
+///
+/// Sparse files — VM images, database preallocations, core dumps — report a
+/// large `len()` while occupying little disk. Finding them explains
+/// mismatches between `du` and `ls`, and flags files that will balloon if
+/// copied by a tool that does not preserve holes.
+///
+/// By default a file matches when it is at least 1 MiB and at least half of
+/// its apparent size is unallocated.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::matchers::SparseFileMatcher;
+///
+/// // Files of 100 MiB or more that are at least 90% holes.
+/// let matcher = SparseFileMatcher::new()
+///     .min_size(100 * 1024 * 1024)
+///     .min_sparse_ratio(0.9);
+/// # let _ = matcher;
+/// ```
+pub struct SparseFileMatcher {
+    min_size: u64,
+    min_sparse_ratio: f64,
+}
+
+impl Default for SparseFileMatcher {
+    fn default() -> Self {
+        Self {
+            min_size: 1024 * 1024,
+            min_sparse_ratio: 0.5,
+        }
+    }
+}
+
+impl SparseFileMatcher {
+    /// A matcher with the default thresholds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ignore files whose apparent size is below `bytes`.
+    pub fn min_size(mut self, bytes: u64) -> Self {
+        self.min_size = bytes;
+        self
+    }
+
+    /// Require at least this fraction of the apparent size to be
+    /// unallocated, from `0.0` to `1.0`.
+    pub fn min_sparse_ratio(mut self, ratio: f64) -> Self {
+        self.min_sparse_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Matcher for SparseFileMatcher {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        if entry.kind != EntryKind::File {
+            return false;
+        }
+        let (Some(meta), Some(allocated)) = (ctx.metadata(), ctx.allocated_size()) else {
+            return false;
+        };
+        let apparent = meta.len();
+        if apparent == 0 || apparent < self.min_size {
+            return false;
+        }
+
+        let holes = apparent.saturating_sub(allocated) as f64;
+        holes / apparent as f64 >= self.min_sparse_ratio
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Metadata
+    }
+}
@@ -0,0 +1,338 @@
+//! Mount-table enumeration and the filesystem types a search skips.
+//!
+//! Whole-host scans cross many filesystems, and few are worth walking
+//! alike: pseudo filesystems expose kernel state, network shares are
+//! latency-bound, FUSE mounts may block on a remote peer. [`under`] lists
+//! what is mounted below a root, so callers can plan per-mount work, and
+//! [`SpecialMounts`] decides which types a search stays out of.
+//!
+//! Mounts come from `/proc/self/mountinfo` on Linux. Other Unix systems
+//! report only their conventional `/dev` and `/proc` mounts, and other
+//! platforms report none.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// ---------------------------------------------------------------------------
+// Pseudo filesystems
+// ---------------------------------------------------------------------------
+
+/// Filesystem types that expose kernel state rather than files: process
+/// tables, device nodes, sysfs attributes, cgroup controls. Walking them
+/// is slow at best — reads can block indefinitely — and never finds
+/// anything worth matching. Skipped by default; see
+/// [`SearchBuilder::special_mounts`](crate::SearchBuilder::special_mounts).
+pub const PSEUDO_FILESYSTEMS: &[&str] = &[
+    // Processes and kernel state
+    "proc",
+    "procfs",
+    "sysfs",
+    "securityfs",
+    "debugfs",
+    "tracefs",
+    "configfs",
+    "pstore",
+    "efivarfs",
+    "selinuxfs",
+    "bpf",
+    "nsfs",
+    "binfmt_misc",
+    "fusectl",
+    // Devices
+    "devtmpfs",
+    "devfs",
+    "devpts",
+    "mqueue",
+    "hugetlbfs",
+    // Control groups
+    "cgroup",
+    "cgroup2",
+    // Automount triggers — descending mounts them
+    "autofs",
+    "rpc_pipefs",
+];
+
+// ---------------------------------------------------------------------------
+// SpecialMounts
+// ---------------------------------------------------------------------------
+
+/// The filesystem types a search stays out of.
+///
+/// Directories where a skipped filesystem is mounted are pruned, and
+/// entries below them dropped, wherever they appear below the search root.
+/// A root that is itself inside such a mount is still walked: asking for
+/// `/proc` explicitly searches it.
+///
+/// Types are named as in the mount table. A name without a dot also
+/// covers its subtypes, so `"fuse"` matches `fuse.sshfs` and
+/// `fuse.rclone`. Mounts are looked up when the search starts; see the
+/// [module docs](self) for where they come from.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::SpecialMounts;
+///
+/// // The usual pseudo filesystems, plus network shares and FUSE
+/// let policy = SpecialMounts::pseudo().with("nfs").with("cifs").with("fuse");
+/// assert!(policy.skips("fuse.sshfs"));
+///
+/// // Only local disks, for an inventory of what this host stores
+/// let local = SpecialMounts::pseudo().only(["ext4", "xfs", "btrfs"]);
+/// assert!(local.skips("tmpfs"));
+/// # let _ =
+/// parex::search().special_mounts(policy);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpecialMounts {
+    types: HashSet<String>,
+    /// When set, every type outside it is skipped as well.
+    only: Option<HashSet<String>>,
+}
+
+impl SpecialMounts {
+    /// Skip mounts of any of `types`, as named in the mount table.
+    pub fn new<I, S>(types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            types: types.into_iter().map(Into::into).collect(),
+            only: None,
+        }
+    }
+
+    /// Skip the filesystems in [`PSEUDO_FILESYSTEMS`].
+    pub fn pseudo() -> Self {
+        Self::new(PSEUDO_FILESYSTEMS.iter().copied())
+    }
+
+    /// Also skip mounts of type `fs_type`.
+    pub fn with(mut self, fs_type: impl Into<String>) -> Self {
+        self.types.insert(fs_type.into());
+        self
+    }
+
+    /// Stop skipping mounts of type `fs_type`.
+    pub fn without(mut self, fs_type: &str) -> Self {
+        self.types.remove(fs_type);
+        self
+    }
+
+    /// Skip every mount whose type is not one of `types`, too. Types
+    /// skipped explicitly stay skipped.
+    pub fn only<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.only = Some(types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Whether mounts of type `fs_type` are skipped.
+    pub fn skips(&self, fs_type: &str) -> bool {
+        let listed = |set: &HashSet<String>| {
+            set.contains(fs_type)
+                || fs_type
+                    .split_once('.')
+                    .is_some_and(|(family, _)| set.contains(family))
+        };
+        listed(&self.types) || self.only.as_ref().is_some_and(|only| !listed(only))
+    }
+
+    /// Where skipped filesystems are mounted right now.
+    pub fn mount_points(&self) -> Vec<PathBuf> {
+        all()
+            .into_iter()
+            .filter(|m| self.skips(&m.fs_type))
+            .map(|m| m.point)
+            .collect()
+    }
+}
+
+impl Default for SpecialMounts {
+    fn default() -> Self {
+        Self::pseudo()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MountPoints
+// ---------------------------------------------------------------------------
+
+/// The mount points one search skips.
+pub(crate) struct MountPoints {
+    points: HashSet<PathBuf>,
+    /// Components in the longest mount point, bounding the ancestors worth
+    /// looking up.
+    deepest: usize,
+}
+
+impl MountPoints {
+    pub(crate) fn new(points: Vec<PathBuf>) -> Self {
+        let deepest = points
+            .iter()
+            .map(|p| p.components().count())
+            .max()
+            .unwrap_or(0);
+        Self {
+            points: points.into_iter().collect(),
+            deepest,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Whether `dir` is one of the mount points.
+    pub(crate) fn is_mount_point(&self, dir: &Path) -> bool {
+        self.points.contains(dir)
+    }
+
+    /// Whether `path`, `depth` levels below the search root, is or lies
+    /// inside a mount point that is itself below the root.
+    pub(crate) fn covers(&self, path: &Path, depth: usize) -> bool {
+        let len = path.components().count();
+        let root = len.saturating_sub(depth);
+        let deepest = len.min(self.deepest);
+        // Longest first: skip ancestors deeper than any mount point
+        path.ancestors()
+            .skip(len - deepest)
+            .take(deepest.saturating_sub(root))
+            .any(|p| self.points.contains(p))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Mount table
+// ---------------------------------------------------------------------------
+
+/// One mounted filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    /// Where it is mounted.
+    pub point: PathBuf,
+
+    /// Its type as the mount table names it — `ext4`, `tmpfs`, `nfs4`,
+    /// `fuse.sshfs`.
+    pub fs_type: String,
+
+    /// `major:minor` of the backing device, where known.
+    #[cfg_attr(not(feature = "storage"), allow(dead_code))]
+    pub(crate) device: String,
+}
+
+/// Every filesystem mounted right now, in mount order.
+pub fn all() -> Vec<Mount> {
+    imp::table()
+}
+
+/// The filesystems mounted at or below `root`, sorted by mount point, so a
+/// mount's parent comes before it.
+///
+/// `root` is canonicalized first; if that fails the list is empty. Mounts
+/// stacked on the same point are all listed, the topmost last.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// for mount in parex::mounts::under("/") {
+///     println!("{} ({})", mount.point.display(), mount.fs_type);
+/// }
+/// ```
+pub fn under(root: impl AsRef<Path>) -> Vec<Mount> {
+    let Ok(root) = root.as_ref().canonicalize() else {
+        return Vec::new();
+    };
+    let mut mounts: Vec<Mount> = all()
+        .into_iter()
+        .filter(|m| m.point.starts_with(&root))
+        .collect();
+    // Stable, so stacked mounts keep their mount order
+    mounts.sort_by(|a, b| a.point.cmp(&b.point));
+    mounts
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::Mount;
+
+    pub(super) fn table() -> Vec<Mount> {
+        std::fs::read_to_string("/proc/self/mountinfo")
+            .map(|info| info.lines().filter_map(parse_mount).collect())
+            .unwrap_or_default()
+    }
+
+    /// Parse one line: `id parent major:minor root mount-point opts... - type source superopts`.
+    fn parse_mount(line: &str) -> Option<Mount> {
+        let (pre, post) = line.split_once(" - ")?;
+        let mut pre = pre.split(' ');
+        let device = pre.nth(2)?.to_owned();
+        let point = unescape(pre.nth(1)?);
+        let fs_type = post.split(' ').next()?.to_owned();
+        Some(Mount {
+            point: point.into(),
+            device,
+            fs_type,
+        })
+    }
+
+    /// Undo the kernel's octal escaping of spaces, tabs, and backslashes.
+    fn unescape(field: &str) -> String {
+        let mut out = String::with_capacity(field.len());
+        let mut rest = field;
+        while let Some(i) = rest.find('\\') {
+            out.push_str(&rest[..i]);
+            let code = rest
+                .get(i + 1..i + 4)
+                .and_then(|o| u8::from_str_radix(o, 8).ok());
+            match code {
+                Some(byte) => {
+                    out.push(byte as char);
+                    rest = &rest[i + 4..];
+                }
+                None => {
+                    out.push('\\');
+                    rest = &rest[i + 1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+mod imp {
+    use std::path::Path;
+
+    use super::Mount;
+
+    /// Where BSDs and macOS conventionally mount their pseudo filesystems.
+    const CONVENTIONAL: &[(&str, &str)] = &[("/dev", "devfs"), ("/proc", "procfs")];
+
+    pub(super) fn table() -> Vec<Mount> {
+        CONVENTIONAL
+            .iter()
+            .filter(|(point, _)| Path::new(point).is_dir())
+            .map(|&(point, fs_type)| Mount {
+                point: point.into(),
+                device: String::new(),
+                fs_type: fs_type.to_owned(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use super::Mount;
+
+    pub(super) fn table() -> Vec<Mount> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,107 @@
+//! Searching several sources as one, with each match attributed to the
+//! source that produced it.
+//!
+//! A [`MultiSource`] walks its sources in the order they were added and
+//! stamps each entry's [`source_id`](crate::Entry::source_id) with the id
+//! its source was added under, so results from overlapping roots — `/home`
+//! and `/home/shared`, or a local tree and a remote bucket — can be told
+//! apart.
+//!
+//! ```rust
+//! use parex::multi::MultiSource;
+//! use parex::{Entry, ParexError};
+//! # use parex::{EntryKind, Source};
+//! # use parex::engine::WalkConfig;
+//! # struct Files(&'static [&'static str]);
+//! # impl Source for Files {
+//! #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+//! #         Box::new(self.0.iter().map(|p| Ok(Entry::new(p, EntryKind::File, 1))).collect::<Vec<_>>().into_iter())
+//! #     }
+//! # }
+//!
+//! // Only what the backup holds
+//! struct FromBackup;
+//! impl parex::Matcher for FromBackup {
+//!     fn is_match(&self, entry: &Entry) -> bool {
+//!         entry.source_id.as_deref() == Some("backup")
+//!     }
+//! }
+//!
+//! let source = MultiSource::new()
+//!     .add("laptop", Files(&["/home/me/invoice.pdf", "/home/me/notes.txt"]))
+//!     .add("backup", Files(&["/home/me/invoice.pdf"]));
+//!
+//! let results = parex::search().source(source).with_matcher(FromBackup).run()?;
+//! assert_eq!(results.matches, 1);
+//! # Ok::<(), ParexError>(())
+//! ```
+
+use std::sync::Arc;
+
+use crate::engine::WalkConfig;
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::traits::Source;
+
+// ---------------------------------------------------------------------------
+// MultiSource
+// ---------------------------------------------------------------------------
+
+/// Several sources searched as one, each under an id.
+#[derive(Default)]
+pub struct MultiSource {
+    sources: Vec<(Arc<str>, Box<dyn Source>)>,
+}
+
+impl MultiSource {
+    /// A source with nothing in it yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Walk `source` after the ones already added, marking its entries
+    /// with `id`. Entries the source already marked keep their own id.
+    pub fn add(mut self, id: impl Into<Arc<str>>, source: impl Source + 'static) -> Self {
+        self.sources.push((id.into(), Box::new(source)));
+        self
+    }
+}
+
+impl Source for MultiSource {
+    /// The root every source shares, if they all report the same one.
+    fn root(&self) -> Option<&std::path::Path> {
+        let mut roots = self.sources.iter().map(|(_, source)| source.root());
+        let first = roots.next()??;
+        roots.all(|root| root == Some(first)).then_some(first)
+    }
+
+    /// Every source starts its walk at once, so sources that traverse on
+    /// their own threads read ahead while earlier ones are consumed;
+    /// entries are still yielded one source after another.
+    fn walk(&self, config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+        let walks: Vec<_> = self
+            .sources
+            .iter()
+            .map(|(id, source)| (Arc::clone(id), source.walk(config)))
+            .collect();
+        Box::new(walks.into_iter().flat_map(|(id, walk)| {
+            walk.map(move |item| {
+                item.map(|mut entry| {
+                    entry.source_id.get_or_insert_with(|| Arc::clone(&id));
+                    entry
+                })
+            })
+        }))
+    }
+}
+
+impl std::fmt::Debug for MultiSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiSource")
+            .field(
+                "sources",
+                &self.sources.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,277 @@
+//! Notifications when a search ends or breaches a threshold.
+//!
+//! For unattended deployments — a nightly inventory, a disk monitor — where
+//! nobody reads the results unless something tells them to. Register a
+//! [`Notifier`] with [`SearchBuilder::notify`](crate::SearchBuilder::notify)
+//! and it receives an [`Event::Completed`] when each search ends, preceded by
+//! an [`Event::Breached`] for every
+//! [`alert_when`](crate::SearchBuilder::alert_when) threshold it exceeded.
+//! Events describe the search with the same [`AuditRecord`] the audit trail
+//! uses.
+//!
+//! Any `Fn(&Event)` is a notifier. With the `webhook` feature, [`Webhook`]
+//! posts each event as JSON to an HTTP endpoint.
+
+use std::time::Duration;
+
+use crate::audit_log::{AuditRecord, json_str};
+use crate::rules::{Action, Violation};
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+/// A limit that, when exceeded, raises an [`Event::Breached`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Threshold {
+    /// More than this many matches.
+    Matches(usize),
+
+    /// More than this many collected errors. Needs
+    /// [`collect_errors`](crate::SearchBuilder::collect_errors).
+    Errors(usize),
+
+    /// The search took longer than this.
+    Elapsed(Duration),
+}
+
+impl Threshold {
+    /// Whether the search described by `record` exceeded this threshold.
+    pub fn breached_by(&self, record: &AuditRecord) -> bool {
+        match *self {
+            Self::Matches(n) => record.matches > n,
+            Self::Errors(n) => record.errors > n,
+            Self::Elapsed(d) => record.elapsed > d,
+        }
+    }
+
+    fn to_json(self) -> String {
+        match self {
+            Self::Matches(n) => format!("{{\"kind\":\"matches\",\"limit\":{n}}}"),
+            Self::Errors(n) => format!("{{\"kind\":\"errors\",\"limit\":{n}}}"),
+            Self::Elapsed(d) => {
+                format!("{{\"kind\":\"elapsed\",\"limit_ns\":{}}}", d.as_nanos())
+            }
+        }
+    }
+}
+
+/// Something a [`Notifier`] is told about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// The search exceeded `threshold`.
+    Breached {
+        threshold: Threshold,
+        record: &'a AuditRecord,
+    },
+
+    /// The search's matches broke a [`Rule`](crate::rules::Rule) whose
+    /// action is [`Action::Notify`].
+    Violated {
+        violation: &'a Violation,
+        record: &'a AuditRecord,
+    },
+
+    /// The search ended — finished, cancelled, or failed; see
+    /// [`AuditRecord::outcome`].
+    Completed(&'a AuditRecord),
+}
+
+impl Event<'_> {
+    /// The search this event is about.
+    pub fn record(&self) -> &AuditRecord {
+        match self {
+            Self::Breached { record, .. }
+            | Self::Violated { record, .. }
+            | Self::Completed(record) => record,
+        }
+    }
+
+    /// The event as a single-line JSON object: `event` is `"breached"`,
+    /// `"violated"`, or `"completed"`, `search` is the
+    /// [`AuditRecord::to_json`] of the search, breaches add the `threshold`,
+    /// and violations the `rule`, its `value`, and the `dir` if any.
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Breached { threshold, record } => format!(
+                "{{\"event\":\"breached\",\"threshold\":{},\"search\":{}}}",
+                threshold.to_json(),
+                record.to_json(),
+            ),
+            Self::Violated { violation, record } => format!(
+                "{{\"event\":\"violated\",\"rule\":{},\"value\":{},\"dir\":{},\"search\":{}}}",
+                violation.rule.to_json(),
+                violation.value,
+                violation
+                    .dir
+                    .as_ref()
+                    .map_or_else(|| "null".into(), |d| json_str(&d.to_string_lossy())),
+                record.to_json(),
+            ),
+            Self::Completed(record) => format!(
+                "{{\"event\":\"completed\",\"search\":{}}}",
+                record.to_json()
+            ),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Notifier
+// ---------------------------------------------------------------------------
+
+/// Receives [`Event`]s on the thread that ran the search.
+///
+/// The search's [`run()`](crate::SearchBuilder::run) does not return until
+/// every notifier has been called, so slow deliveries should time out.
+pub trait Notifier: Send + Sync {
+    /// Handle one event. Failures are the notifier's to report or drop.
+    fn notify(&self, event: &Event<'_>);
+}
+
+impl<F: Fn(&Event<'_>) + Send + Sync> Notifier for F {
+    fn notify(&self, event: &Event<'_>) {
+        self(event)
+    }
+}
+
+/// The notifiers and thresholds of one search.
+#[derive(Default)]
+pub(crate) struct Notifiers {
+    pub(crate) notifiers: Vec<Box<dyn Notifier>>,
+    pub(crate) thresholds: Vec<Threshold>,
+}
+
+impl Notifiers {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.notifiers.is_empty()
+    }
+
+    /// Tell every notifier about each breached threshold and each
+    /// violation of a notifying rule, then completion.
+    pub(crate) fn fire(&self, record: &AuditRecord, violations: &[Violation]) {
+        let breaches = self
+            .thresholds
+            .iter()
+            .filter(|t| t.breached_by(record))
+            .map(|&threshold| Event::Breached { threshold, record });
+        let violated = violations
+            .iter()
+            .filter(|v| v.rule.action == Action::Notify)
+            .map(|violation| Event::Violated { violation, record });
+        for event in breaches.chain(violated).chain([Event::Completed(record)]) {
+            for n in &self.notifiers {
+                n.notify(&event);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Webhook
+// ---------------------------------------------------------------------------
+
+/// Posts events as JSON ([`Event::to_json`]) to an HTTP or HTTPS endpoint.
+///
+/// Enabled with the `webhook` feature. Each event is one `POST` with
+/// `Content-Type: application/json`; a request that fails or times out is
+/// dropped, never retried, so a dead endpoint cannot stall scans for long.
+///
+/// ```rust,no_run
+/// use parex::notify::{Threshold, Webhook};
+/// # struct Files;
+/// # impl parex::Source for Files {
+/// #     fn walk(&self, _: &parex::engine::WalkConfig)
+/// #         -> Box<dyn Iterator<Item = Result<parex::Entry, parex::ParexError>>> {
+/// #         Box::new(std::iter::empty())
+/// #     }
+/// # }
+///
+/// let hook = Webhook::new("https://alerts.example.com/parex")
+///     .header("Authorization", "Bearer s3cr3t")
+///     .completions(false);
+/// parex::search()
+///     .source(Files)
+///     .alert_when(Threshold::Matches(0))
+///     .notify(hook)
+///     .run()?;
+/// # Ok::<(), parex::ParexError>(())
+/// ```
+#[cfg(feature = "webhook")]
+pub struct Webhook {
+    url: String,
+    headers: Vec<(String, String)>,
+    completions: bool,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "webhook")]
+impl Webhook {
+    /// Post to `url`, with a 10-second timeout.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            completions: true,
+            agent: Self::agent(Duration::from_secs(10)),
+        }
+    }
+
+    /// Give up on a request after `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.agent = Self::agent(timeout);
+        self
+    }
+
+    /// Send `name: value` with every request, e.g. for authentication.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Whether to post [`Event::Completed`] too, or only breaches and
+    /// violations.
+    /// On by default.
+    pub fn completions(mut self, yes: bool) -> Self {
+        self.completions = yes;
+        self
+    }
+
+    fn agent(timeout: Duration) -> ureq::Agent {
+        ureq::AgentBuilder::new().timeout(timeout).build()
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl Notifier for Webhook {
+    fn notify(&self, event: &Event<'_>) {
+        if matches!(event, Event::Completed(_)) && !self.completions {
+            return;
+        }
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        let _ = request.send_string(&event.to_json());
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl std::fmt::Debug for Webhook {
+    /// Header values are left out; they often hold credentials.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Webhook")
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &self.headers.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            )
+            .field("completions", &self.completions)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,107 @@
+use opentelemetry::trace::{Status, TraceContextExt, Tracer};
+use opentelemetry::{Context, ContextGuard, KeyValue, global};
+
+use crate::plan::Plan;
+use crate::results::{Completion, Results};
+
+// ---------------------------------------------------------------------------
+// OpenTelemetry spans
+// ---------------------------------------------------------------------------
+//
+// Every search is a `parex.scan` span under the caller's context, with
+// `parex.wait` (debounce or energy deferral) and `parex.walk` (the source
+// read and matched) phases below it. Matcher threads run with the walk
+// span's context attached, so a matcher calling a remote service can inject
+// `Context::current()` into its request and the remote work joins the trace.
+//
+// Spans go to the global tracer provider; with none installed, they are
+// no-ops.
+
+const TRACER: &str = "parex";
+
+/// The trace of one search, from the builder to the end of the walk.
+pub(crate) struct Trace {
+    /// The caller's context until the scan starts, then the scan span's.
+    cx: Context,
+    attributes: Vec<KeyValue>,
+}
+
+impl Trace {
+    /// A trace parented to `parent`, describing the search in `plan`.
+    pub(crate) fn new(parent: Context, plan: &Plan) -> Self {
+        let mut attributes = vec![
+            KeyValue::new("parex.matcher", plan.matcher),
+            KeyValue::new("parex.threads", plan.threads as i64),
+        ];
+        if let Some(source) = plan.source {
+            attributes.push(KeyValue::new("parex.source", source));
+        }
+        if let Some(limit) = plan.limit {
+            attributes.push(KeyValue::new("parex.limit", limit as i64));
+        }
+        Self {
+            cx: parent,
+            attributes,
+        }
+    }
+
+    /// Open the `parex.scan` span; later phases nest under it.
+    pub(crate) fn start(&mut self) -> Scan {
+        let tracer = global::tracer(TRACER);
+        let span = tracer
+            .span_builder("parex.scan")
+            .with_attributes(std::mem::take(&mut self.attributes))
+            .start_with_context(&tracer, &self.cx);
+        self.cx = self.cx.with_span(span);
+        Scan(self.cx.clone())
+    }
+
+    /// Open a phase span below the scan, ended when the phase drops.
+    pub(crate) fn phase(&self, name: &'static str) -> Phase {
+        let span = global::tracer(TRACER).start_with_context(name, &self.cx);
+        Phase(self.cx.with_span(span))
+    }
+}
+
+/// The open `parex.scan` span.
+pub(crate) struct Scan(Context);
+
+impl Scan {
+    /// Record how the search went and end the span.
+    pub(crate) fn finish(self, results: &Results) {
+        let span = self.0.span();
+        span.set_attributes([
+            KeyValue::new("parex.matches", results.matches as i64),
+            KeyValue::new("parex.files", results.stats.files as i64),
+            KeyValue::new("parex.dirs", results.stats.dirs as i64),
+        ]);
+        match &results.completion {
+            Completion::Finished => {}
+            Completion::Cancelled => span.set_attribute(KeyValue::new("parex.cancelled", true)),
+            Completion::Failed(e) => span.set_status(Status::error(e.to_string())),
+        }
+        span.end();
+    }
+}
+
+/// An open phase span.
+pub(crate) struct Phase(Context);
+
+impl Phase {
+    /// The phase's context, for threads that work on its behalf.
+    pub(crate) fn context(&self) -> &Context {
+        &self.0
+    }
+
+    /// Make this phase the current context of the calling thread until the
+    /// guard drops.
+    pub(crate) fn attach(&self) -> ContextGuard {
+        self.0.clone().attach()
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        self.0.span().end();
+    }
+}
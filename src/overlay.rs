@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::entry::{Entry, Tags};
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// Overlay
+// ---------------------------------------------------------------------------
+
+/// Enriches entries with application-level tags from a sidecar store.
+///
+/// Implement this over whatever holds your labels — a SQLite table, a JSON
+/// file of previous scan data, an in-memory map. The engine collects entries
+/// from the source into batches and calls [`lookup`](Self::lookup) once per
+/// batch, then stores each result in [`Entry::tags`] before matching, so
+/// matchers can filter on tags alongside filesystem data.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::{Overlay, Tags};
+/// use std::collections::HashMap;
+/// use std::path::{Path, PathBuf};
+/// use std::sync::Arc;
+///
+/// struct Labels(HashMap<PathBuf, Arc<Tags>>);
+///
+/// impl Overlay for Labels {
+///     fn lookup(&self, paths: &[&Path]) -> Vec<Option<Arc<Tags>>> {
+///         paths.iter().map(|p| self.0.get(*p).cloned()).collect()
+///     }
+/// }
+/// ```
+pub trait Overlay: Send + Sync {
+    /// Tags for each path, in the same order. Return `None` for untagged paths.
+    ///
+    /// Must return exactly one item per path.
+    fn lookup(&self, paths: &[&Path]) -> Vec<Option<Arc<Tags>>>;
+}
+
+// ---------------------------------------------------------------------------
+// Batching adapter
+// ---------------------------------------------------------------------------
+
+/// Wraps a source iterator, tagging entries one batch at a time.
+pub(crate) struct OverlayIter<'a> {
+    inner: Box<dyn Iterator<Item = Result<Entry, ParexError>>>,
+    overlay: &'a dyn Overlay,
+    batch_size: usize,
+    buffer: VecDeque<Result<Entry, ParexError>>,
+}
+
+impl<'a> OverlayIter<'a> {
+    pub(crate) fn new(
+        inner: Box<dyn Iterator<Item = Result<Entry, ParexError>>>,
+        overlay: &'a dyn Overlay,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            inner,
+            overlay,
+            batch_size: batch_size.max(1),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    fn fill(&mut self) {
+        self.buffer
+            .extend(self.inner.by_ref().take(self.batch_size));
+
+        let paths: Vec<&Path> = self
+            .buffer
+            .iter()
+            .filter_map(|item| item.as_ref().ok().map(|e| e.path.as_path()))
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        let tags = self.overlay.lookup(&paths);
+        let entries = self.buffer.iter_mut().filter_map(|item| item.as_mut().ok());
+        for (entry, tags) in entries.zip(tags) {
+            entry.tags = tags;
+        }
+    }
+}
+
+impl Iterator for OverlayIter<'_> {
+    type Item = Result<Entry, ParexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            self.fill();
+        }
+        self.buffer.pop_front()
+    }
+}
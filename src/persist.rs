@@ -0,0 +1,442 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// Checksummed encoding
+// ---------------------------------------------------------------------------
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a over everything written or read so far. Not cryptographic —
+/// it catches torn writes and bit rot, not tampering.
+#[derive(Clone, Copy)]
+struct Checksum(u64);
+
+impl Checksum {
+    fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+/// Writes little-endian primitives, checksumming as it goes.
+pub(crate) struct Encoder<W> {
+    inner: W,
+    sum: Checksum,
+}
+
+impl<W: Write> Encoder<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            sum: Checksum::new(),
+        }
+    }
+
+    pub(crate) fn raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.sum.update(bytes);
+        self.inner.write_all(bytes)
+    }
+
+    pub(crate) fn u8(&mut self, v: u8) -> io::Result<()> {
+        self.raw(&[v])
+    }
+
+    pub(crate) fn u32(&mut self, v: u32) -> io::Result<()> {
+        self.raw(&v.to_le_bytes())
+    }
+
+    pub(crate) fn u64(&mut self, v: u64) -> io::Result<()> {
+        self.raw(&v.to_le_bytes())
+    }
+
+    /// Length-prefixed bytes.
+    pub(crate) fn bytes(&mut self, v: &[u8]) -> io::Result<()> {
+        self.u64(v.len() as u64)?;
+        self.raw(v)
+    }
+
+    /// Write the rest through `f(writer)` — a compressor, say — keeping
+    /// the running checksum.
+    pub(crate) fn try_map<V>(self, f: impl FnOnce(W) -> io::Result<V>) -> io::Result<Encoder<V>> {
+        Ok(Encoder {
+            inner: f(self.inner)?,
+            sum: self.sum,
+        })
+    }
+
+    /// Append the checksum of everything written and return the writer.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        let sum = self.sum.0;
+        self.inner.write_all(&sum.to_le_bytes())?;
+        Ok(self.inner)
+    }
+}
+
+/// Reads what an [`Encoder`] wrote, reporting damage as
+/// [`ParexError::Corrupt`] rather than panicking or returning bad data.
+pub(crate) struct Decoder<R> {
+    inner: R,
+    sum: Checksum,
+    path: PathBuf,
+}
+
+impl<R: Read> Decoder<R> {
+    pub(crate) fn new(inner: R, path: &Path) -> Self {
+        Self {
+            inner,
+            sum: Checksum::new(),
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Read the rest through `f(reader)` — a decompressor, say — keeping
+    /// the running checksum.
+    pub(crate) fn try_map<S>(
+        self,
+        f: impl FnOnce(R) -> io::Result<S>,
+    ) -> Result<Decoder<S>, ParexError> {
+        match f(self.inner) {
+            Ok(inner) => Ok(Decoder {
+                inner,
+                sum: self.sum,
+                path: self.path,
+            }),
+            Err(source) => Err(ParexError::Io {
+                path: self.path,
+                source,
+            }),
+        }
+    }
+
+    /// A [`ParexError::Corrupt`] for this file.
+    pub(crate) fn corrupt(&self, reason: impl Into<String>) -> ParexError {
+        ParexError::Corrupt {
+            path: self.path.clone(),
+            reason: reason.into(),
+        }
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), ParexError> {
+        match self.inner.read_exact(buf) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(self.corrupt("truncated")),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => Err(self.corrupt(e.to_string())),
+            Err(source) => Err(ParexError::Io {
+                path: self.path.clone(),
+                source,
+            }),
+        }
+    }
+
+    pub(crate) fn raw<const N: usize>(&mut self) -> Result<[u8; N], ParexError> {
+        let mut buf = [0; N];
+        self.fill(&mut buf)?;
+        self.sum.update(&buf);
+        Ok(buf)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, ParexError> {
+        Ok(self.raw::<1>()?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, ParexError> {
+        Ok(u32::from_le_bytes(self.raw()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, ParexError> {
+        Ok(u64::from_le_bytes(self.raw()?))
+    }
+
+    /// Length-prefixed bytes of at most `max` bytes.
+    pub(crate) fn bytes(&mut self, max: usize) -> Result<Vec<u8>, ParexError> {
+        let len = self.u64()?;
+        // A damaged length must not become a huge allocation
+        let len = usize::try_from(len)
+            .ok()
+            .filter(|&l| l <= max)
+            .ok_or_else(|| self.corrupt(format!("field length {len} exceeds {max}")))?;
+        let mut buf = vec![0; len];
+        self.fill(&mut buf)?;
+        self.sum.update(&buf);
+        Ok(buf)
+    }
+
+    /// Check the trailing checksum and that nothing follows it.
+    pub(crate) fn finish(mut self) -> Result<(), ParexError> {
+        let expected = self.sum.0;
+        let mut stored = [0; 8];
+        self.fill(&mut stored)?;
+        if u64::from_le_bytes(stored) != expected {
+            return Err(self.corrupt("checksum mismatch"));
+        }
+        let mut rest = [0; 1];
+        match self.inner.read(&mut rest) {
+            Ok(0) => Ok(()),
+            Ok(_) => Err(self.corrupt("trailing data after checksum")),
+            Err(source) => Err(ParexError::Io {
+                path: self.path,
+                source,
+            }),
+        }
+    }
+}
+
+/// A decompressing reader whose failures mean damaged input.
+///
+/// zstd reports a bad frame as [`io::ErrorKind::Other`]; this turns it into
+/// [`io::ErrorKind::InvalidData`], which a [`Decoder`] reports as
+/// [`ParexError::Corrupt`]. Errors of the underlying file keep their kind.
+#[cfg(feature = "zstd")]
+pub(crate) struct Decompress<R>(pub(crate) R);
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Read for Decompress<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf).map_err(|e| match e.kind() {
+            io::ErrorKind::Other => io::Error::new(io::ErrorKind::InvalidData, e),
+            _ => e,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Header
+// ---------------------------------------------------------------------------
+
+/// Version of the header layout and of every format built on it.
+///
+/// - v1: magic, version.
+/// - v2: adds the header length, so optional header fields can be added
+///   without a version bump, and a flags word.
+/// - v3: snapshot records end with the entry's stable id.
+pub(crate) const FORMAT_VERSION: u32 = 3;
+
+/// Bytes of the v2 header after the version: header length and flags.
+const V2_HEADER_LEN: u32 = 8;
+
+/// Header flag: everything after the header is one zstd stream.
+pub(crate) const FLAG_ZSTD: u32 = 1;
+
+/// Header flag: everything after the header is encrypted.
+pub(crate) const FLAG_SEALED: u32 = 2;
+
+/// The flags this build can read.
+pub(crate) const KNOWN_FLAGS: u32 = (if cfg!(feature = "zstd") { FLAG_ZSTD } else { 0 })
+    | (if cfg!(feature = "encryption") {
+        FLAG_SEALED
+    } else {
+        0
+    });
+
+/// Longest header a reader accepts, so a damaged length cannot cause a huge skip.
+const MAX_HEADER_LEN: u32 = 4096;
+
+/// The start of every persistent file, normalised to the current version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header {
+    /// The version the file was written with.
+    pub(crate) version: u32,
+
+    /// Format-specific flags. Always `0` for v1 files.
+    pub(crate) flags: u32,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Write a current-version header.
+    pub(crate) fn header(&mut self, magic: &[u8; 8], flags: u32) -> io::Result<()> {
+        self.raw(magic)?;
+        self.u32(FORMAT_VERSION)?;
+        self.u32(V2_HEADER_LEN)?;
+        self.u32(flags)
+    }
+}
+
+impl<R: Read> Decoder<R> {
+    /// Read a header of any supported version, upgrading older ones.
+    ///
+    /// Flags outside `known_flags` make the file unreadable, since they
+    /// change how the rest of it is laid out.
+    pub(crate) fn header(
+        &mut self,
+        magic: &[u8; 8],
+        known_flags: u32,
+    ) -> Result<Header, ParexError> {
+        if &self.raw::<8>()? != magic {
+            return Err(self.corrupt("wrong file type"));
+        }
+        let version = self.u32()?;
+        let flags = match version {
+            0 => return Err(self.corrupt("format version 0")),
+            // v1 had no header fields beyond the version
+            1 => 0,
+            2.. if version <= FORMAT_VERSION => {
+                let len = self.u32()?;
+                if !(V2_HEADER_LEN..=MAX_HEADER_LEN).contains(&len) {
+                    return Err(self.corrupt(format!("bad header length {len}")));
+                }
+                let flags = self.u32()?;
+                // Optional fields this build does not know
+                for _ in V2_HEADER_LEN..len {
+                    self.u8()?;
+                }
+                flags
+            }
+            _ => return Err(self.unsupported(version)),
+        };
+        if flags & !known_flags != 0 {
+            return Err(self.unsupported(version));
+        }
+        Ok(Header { version, flags })
+    }
+
+    fn unsupported(&self, found: u32) -> ParexError {
+        ParexError::UnsupportedFormat {
+            path: self.path.clone(),
+            found,
+            supported: FORMAT_VERSION,
+        }
+    }
+}
+
+impl Header {
+    /// The header an [`Encoder`] writes with `flags`.
+    pub(crate) fn current(flags: u32) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            flags,
+        }
+    }
+
+    /// Bytes that encrypted bodies authenticate, binding them to the header.
+    pub(crate) fn aad(&self, magic: &[u8; 8]) -> Vec<u8> {
+        let mut aad = magic.to_vec();
+        aad.extend(self.version.to_le_bytes());
+        aad.extend(self.flags.to_le_bytes());
+        aad
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Body layers
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "encryption")]
+pub(crate) use crate::seal::EncryptionKey;
+#[cfg(feature = "encryption")]
+use crate::seal::{OpenReader, SealWriter};
+
+/// Stand-in so `Option<&EncryptionKey>` is always `None` without the
+/// `encryption` feature.
+#[cfg(not(feature = "encryption"))]
+pub(crate) enum EncryptionKey {}
+
+/// The writer for everything after the header: compressed with zstd at
+/// `level` if given, then encrypted with `key` if given.
+pub(crate) enum BodyWriter<W: Write> {
+    Plain(W),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+    #[cfg(feature = "encryption")]
+    Sealed(Box<SealWriter<W>>),
+    #[cfg(all(feature = "zstd", feature = "encryption"))]
+    SealedZstd(zstd::Encoder<'static, Box<SealWriter<W>>>),
+}
+
+impl<W: Write> BodyWriter<W> {
+    /// The header flags for a body written with these layers.
+    pub(crate) fn flags(level: Option<i32>, key: Option<&EncryptionKey>) -> u32 {
+        (if level.is_some() { FLAG_ZSTD } else { 0 })
+            | (if key.is_some() { FLAG_SEALED } else { 0 })
+    }
+
+    /// `aad` is the [`Header::aad`] of the file being written.
+    #[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
+    pub(crate) fn new(
+        w: W,
+        level: Option<i32>,
+        key: Option<&EncryptionKey>,
+        aad: &[u8],
+    ) -> io::Result<Self> {
+        Ok(match (level, key) {
+            (None, None) => Self::Plain(w),
+            #[cfg(feature = "zstd")]
+            (Some(level), None) => Self::Zstd(zstd::Encoder::new(w, level)?),
+            #[cfg(feature = "encryption")]
+            (None, Some(key)) => Self::Sealed(Box::new(SealWriter::new(w, key, aad)?)),
+            #[cfg(all(feature = "zstd", feature = "encryption"))]
+            (Some(level), Some(key)) => Self::SealedZstd(zstd::Encoder::new(
+                Box::new(SealWriter::new(w, key, aad)?),
+                level,
+            )?),
+            #[cfg(not(all(feature = "zstd", feature = "encryption")))]
+            _ => return Err(io::Error::other("layer not compiled in")),
+        })
+    }
+
+    /// Flush every layer and return the file writer.
+    pub(crate) fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(w) => Ok(w),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(z) => z.finish(),
+            #[cfg(feature = "encryption")]
+            Self::Sealed(s) => (*s).finish(),
+            #[cfg(all(feature = "zstd", feature = "encryption"))]
+            Self::SealedZstd(z) => (*z.finish()?).finish(),
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(w) => w,
+            #[cfg(feature = "zstd")]
+            Self::Zstd(z) => z,
+            #[cfg(feature = "encryption")]
+            Self::Sealed(s) => &mut **s,
+            #[cfg(all(feature = "zstd", feature = "encryption"))]
+            Self::SealedZstd(z) => z,
+        }
+    }
+}
+
+impl<W: Write> Write for BodyWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer().flush()
+    }
+}
+
+/// The reader for everything after `header`, undoing the layers its flags
+/// name. The caller checks that a key is given when the body is sealed.
+#[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
+pub(crate) fn body_reader<'a, R: io::BufRead + 'a>(
+    r: R,
+    header: &Header,
+    key: Option<&EncryptionKey>,
+    aad: &[u8],
+) -> io::Result<Box<dyn Read + 'a>> {
+    #[cfg(feature = "encryption")]
+    let r: Box<dyn io::BufRead + 'a> = match key {
+        Some(key) if header.flags & FLAG_SEALED != 0 => {
+            Box::new(io::BufReader::new(OpenReader::new(r, key, aad)?))
+        }
+        _ => Box::new(r),
+    };
+    #[cfg(feature = "zstd")]
+    if header.flags & FLAG_ZSTD != 0 {
+        return Ok(Box::new(Decompress(zstd::Decoder::with_buffer(r)?)));
+    }
+    Ok(Box::new(r))
+}
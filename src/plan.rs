@@ -0,0 +1,161 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::engine::MetadataMode;
+
+// ---------------------------------------------------------------------------
+// Cost
+// ---------------------------------------------------------------------------
+
+/// How much work a [`Matcher`](crate::Matcher) does per entry.
+///
+/// Ordered from cheapest to most expensive, so classes can be compared
+/// directly: `Cost::Name < Cost::Content`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Cost {
+    /// Looks only at the path, kind, depth, or tags — no syscalls.
+    Name,
+
+    /// Reads file metadata — one `stat()` per entry unless the source cached it.
+    Metadata,
+
+    /// Opens and reads file contents.
+    Content,
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Name => "name",
+            Self::Metadata => "metadata",
+            Self::Content => "content",
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Plan
+// ---------------------------------------------------------------------------
+
+/// What a configured search will do, as reported by
+/// [`SearchBuilder::explain()`](crate::SearchBuilder::explain).
+///
+/// Nothing is walked to produce a plan. Source and matcher names are Rust
+/// type names, which is usually enough to tell which implementation a
+/// builder chain ended up with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// Type name of the source, or `None` if no source is set yet.
+    pub source: Option<&'static str>,
+
+    /// Where the source starts walking, if it reports a
+    /// [`root`](crate::Source::root).
+    pub root: Option<PathBuf>,
+
+    /// Type name of the matcher. Without `.matching()` or `.with_matcher()`
+    /// every entry matches.
+    pub matcher: &'static str,
+
+    /// The matcher followed by any `.filter()` predicates, in the order
+    /// they will be evaluated — cheapest first.
+    pub predicates: Vec<&'static str>,
+
+    /// The most expensive declared per-entry cost among the predicates.
+    pub cost: Cost,
+
+    /// Worker threads.
+    pub threads: usize,
+
+    /// Depth limit passed to the source.
+    pub max_depth: Option<usize>,
+
+    /// Whether metadata reads follow symlinks.
+    pub metadata_mode: MetadataMode,
+
+    /// Number of excluded paths. Entries under any of them are skipped
+    /// before matching.
+    pub excluded_paths: usize,
+
+    /// The walk stops after this many matches.
+    pub limit: Option<usize>,
+
+    /// Whether the walk continues past `limit` to count every entry.
+    pub accurate_stats: bool,
+
+    /// Whether an overlay tags entries before matching.
+    pub overlay: bool,
+
+    /// Whether a sink receives every match.
+    pub sink: bool,
+}
+
+impl Plan {
+    /// Whether matching may `stat()` entries.
+    pub fn reads_metadata(&self) -> bool {
+        self.cost >= Cost::Metadata
+    }
+
+    /// Whether matching may open files.
+    pub fn reads_content(&self) -> bool {
+        self.cost >= Cost::Content
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "source:   {}", self.source.unwrap_or("<none>"))?;
+        if let Some(root) = &self.root {
+            writeln!(f, "root:     {}", root.display())?;
+        }
+        writeln!(f, "matcher:  {} (cost: {})", self.matcher, self.cost)?;
+        if self.predicates.len() > 1 {
+            writeln!(f, "order:    {}", self.predicates.join(" → "))?;
+        }
+        writeln!(f, "threads:  {}", self.threads)?;
+
+        let mut pruning = Vec::new();
+        if let Some(d) = self.max_depth {
+            pruning.push(format!("max depth {d}"));
+        }
+        if self.excluded_paths > 0 {
+            pruning.push(format!("{} excluded paths", self.excluded_paths));
+        }
+        match self.limit {
+            Some(n) if self.accurate_stats => {
+                pruning.push(format!(
+                    "stop matching after {n} matches, count every entry"
+                ));
+            }
+            Some(n) => pruning.push(format!("stop after {n} matches")),
+            None => {}
+        }
+        if pruning.is_empty() {
+            writeln!(f, "pruning:  none — every entry is visited")?;
+        } else {
+            writeln!(f, "pruning:  {}", pruning.join(", "))?;
+        }
+
+        write!(
+            f,
+            "reads:    names{}{}",
+            match (self.reads_metadata(), self.metadata_mode) {
+                (false, _) => "",
+                (true, MetadataMode::Follow) => ", metadata (stat)",
+                (true, MetadataMode::NoFollow) => ", metadata (lstat)",
+            },
+            if self.reads_content() {
+                ", content"
+            } else {
+                ""
+            },
+        )?;
+        if self.overlay {
+            write!(f, "\noverlay:  yes")?;
+        }
+        if self.sink {
+            write!(f, "\nsink:     yes")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Power and thermal detection, so background scans can yield to the user.
+//!
+//! Enabled with the `power` feature. A scan that pegs every core and the
+//! disk is fine on a desktop; on a laptop running from its battery, or one
+//! already throttling to stay cool, it drains the battery and makes the
+//! fans spin up. [`detect`] reports both conditions and
+//! [`SearchBuilder::energy_policy`](crate::SearchBuilder::energy_policy)
+//! decides what a search does about them.
+
+use std::path::Path;
+use std::time::Duration;
+
+// ---------------------------------------------------------------------------
+// PowerState
+// ---------------------------------------------------------------------------
+
+/// The host's power and thermal condition, as far as it could be detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerState {
+    /// Running from a battery, with no external power connected.
+    pub on_battery: bool,
+
+    /// The CPU is being slowed down to keep it within thermal limits.
+    pub throttled: bool,
+}
+
+impl PowerState {
+    /// Whether a heavy scan now would cost the user battery or heat.
+    pub fn constrained(&self) -> bool {
+        self.on_battery || self.throttled
+    }
+}
+
+/// What a search does when the host is [constrained](PowerState::constrained).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnergyPolicy {
+    /// Wait before walking until the host is unconstrained, checking again
+    /// at this interval. Cancelling the search ends the wait.
+    Defer(Duration),
+
+    /// Walk right away with one thread and one directory read at a time.
+    Slow,
+}
+
+// ---------------------------------------------------------------------------
+// Detection
+// ---------------------------------------------------------------------------
+
+/// Detect the host's power and thermal condition.
+///
+/// On Linux this reads sysfs: a battery that is discharging while no mains
+/// or USB supply is online means [`on_battery`](PowerState::on_battery),
+/// and a processor cooling device in any state but 0 means
+/// [`throttled`](PowerState::throttled). Elsewhere, and for anything that
+/// cannot be read, it reports an unconstrained host.
+pub fn detect() -> PowerState {
+    imp::detect(Path::new("/sys"))
+}
+
+/// [`detect`] against a sysfs mounted at `sysfs`, for containers that
+/// expose the host's sysfs somewhere other than `/sys`.
+#[cfg(target_os = "linux")]
+pub fn detect_in(sysfs: impl AsRef<Path>) -> PowerState {
+    imp::detect(sysfs.as_ref())
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::path::{Path, PathBuf};
+
+    use super::PowerState;
+
+    pub(super) fn detect(sysfs: &Path) -> PowerState {
+        PowerState {
+            on_battery: on_battery(sysfs),
+            throttled: throttled(sysfs),
+        }
+    }
+
+    fn on_battery(sysfs: &Path) -> bool {
+        let supplies = entries(&sysfs.join("class/power_supply"));
+        let external = supplies.iter().any(|s| {
+            matches!(read(s, "type").as_deref(), Some("Mains" | "USB"))
+                && read(s, "online").as_deref() == Some("1")
+        });
+        let discharging = supplies.iter().any(|s| {
+            read(s, "type").as_deref() == Some("Battery")
+                && read(s, "status").as_deref() == Some("Discharging")
+        });
+        discharging && !external
+    }
+
+    fn throttled(sysfs: &Path) -> bool {
+        entries(&sysfs.join("class/thermal"))
+            .iter()
+            .filter(|d| {
+                d.file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with("cooling_device"))
+            })
+            .filter(|d| {
+                read(d, "type").is_some_and(|t| t == "Processor" || t == "intel_powerclamp")
+            })
+            .any(|d| read(d, "cur_state").is_some_and(|s| s != "0"))
+    }
+
+    fn entries(dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|rd| rd.filter_map(|e| Some(e.ok()?.path())).collect())
+            .unwrap_or_default()
+    }
+
+    fn read(dir: &Path, name: &str) -> Option<String> {
+        std::fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|s| s.trim().to_owned())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use std::path::Path;
+
+    use super::PowerState;
+
+    pub(super) fn detect(_sysfs: &Path) -> PowerState {
+        PowerState::default()
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crate::entry::{Entry, EntryKind};
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// Prefetch adapter
+// ---------------------------------------------------------------------------
+
+/// Wraps a source iterator, hinting the kernel to read files ahead of the matcher.
+///
+/// Keeps `distance` entries buffered. Each regular file gets a `WILLNEED`
+/// hint as it enters the buffer, so by the time the matcher opens it the
+/// kernel has had `distance` entries' worth of matching to pull it into the
+/// page cache.
+pub(crate) struct PrefetchIter<'a> {
+    inner: Box<dyn Iterator<Item = Result<Entry, ParexError>> + 'a>,
+    distance: usize,
+    buffer: VecDeque<Result<Entry, ParexError>>,
+}
+
+impl<'a> PrefetchIter<'a> {
+    pub(crate) fn new(
+        inner: Box<dyn Iterator<Item = Result<Entry, ParexError>> + 'a>,
+        distance: usize,
+    ) -> Self {
+        Self {
+            inner,
+            distance,
+            buffer: VecDeque::with_capacity(distance + 1),
+        }
+    }
+}
+
+impl Iterator for PrefetchIter<'_> {
+    type Item = Result<Entry, ParexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() <= self.distance {
+            let Some(item) = self.inner.next() else {
+                break;
+            };
+            if let Ok(entry) = &item
+                && entry.kind == EntryKind::File
+            {
+                will_need(&entry.path);
+            }
+            self.buffer.push_back(item);
+        }
+        self.buffer.pop_front()
+    }
+}
+
+/// Ask the kernel to start reading `path` into the page cache.
+///
+/// Best effort — failures are ignored, the matcher will read the file anyway.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+fn will_need(path: &Path) {
+    use rustix::fs::{Advice, fadvise};
+
+    if let Ok(file) = std::fs::File::open(path) {
+        let _ = fadvise(&file, 0, None, Advice::WillNeed);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+fn will_need(_path: &Path) {}
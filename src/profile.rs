@@ -0,0 +1,436 @@
+//! Where a search spends its time.
+//!
+//! Built with [`SearchBuilder::profile`](crate::SearchBuilder::profile), a
+//! search times each pull from the source, each entry's trip through the
+//! matcher, and each `stat()` made through
+//! [`MatchContext::metadata`](crate::MatchContext::metadata), and reports
+//! the distributions in [`Results::profile`](crate::Results::profile).
+//! Latencies go into fixed-size histograms, so profiling a walk of millions
+//! of entries costs a few kilobytes and a handful of clock reads per entry.
+//!
+//! [`SearchBuilder::slowest_dirs`](crate::SearchBuilder::slowest_dirs)
+//! reuses the per-directory timing for a [`DirReport`] of the directories
+//! worth excluding, without the rest of the profile.
+//!
+//! The built-in histograms are accurate to about 6%. The `hdr` feature
+//! swaps in [HdrHistogram](https://docs.rs/hdrhistogram) with three
+//! significant digits, for tail latencies that need to be exact.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+#[cfg(not(feature = "hdr"))]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Entries per throughput sample.
+const WINDOW: usize = 1024;
+
+/// Fewest samples a percentile needs before it says anything about caches.
+const MIN_SAMPLES: u64 = 32;
+
+// ---------------------------------------------------------------------------
+// ScanProfile
+// ---------------------------------------------------------------------------
+
+/// Timing detail for one search. See [`Results::profile`](crate::Results::profile).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanProfile {
+    /// Entries per second over consecutive windows of 1024 entries, in walk
+    /// order. A rising curve means caches warmed up as the walk went on.
+    pub throughput: Vec<f64>,
+
+    /// Time to pull each entry from the source — directory reads, for a
+    /// filesystem walker.
+    pub source_latency: Percentiles,
+
+    /// Time each directory took to enumerate: the pulls of all its entries
+    /// added up, one sample per directory. A long tail here points at a few
+    /// slow directories rather than a slow disk.
+    pub dir_latency: Percentiles,
+
+    /// Time each entry spent in the matcher, `stat()` calls included.
+    pub entry_latency: Percentiles,
+
+    /// Latency of the `stat()` calls matchers made through
+    /// [`MatchContext::metadata`](crate::MatchContext::metadata). `None` if
+    /// no matcher asked for metadata the source had not cached.
+    pub stat_latency: Option<Percentiles>,
+
+    /// A best guess at whether the data came from memory or from storage.
+    pub cache_temperature: CacheTemperature,
+}
+
+/// A latency distribution.
+///
+/// Percentiles are accurate to within about 6% of the reported value, or
+/// 0.1% with the `hdr` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Percentiles {
+    /// Number of measurements.
+    pub samples: u64,
+
+    /// Median.
+    pub p50: Duration,
+
+    /// 95th percentile.
+    pub p95: Duration,
+
+    /// 99th percentile.
+    pub p99: Duration,
+
+    /// Slowest measurement, exact.
+    pub max: Duration,
+}
+
+/// Whether a search was served from the OS caches.
+///
+/// The same query is often 20× slower the first time it runs: directory
+/// listings and inodes come from storage instead of memory. Guessed from
+/// `stat()` latency when matchers read metadata, otherwise from how long
+/// the source takes per entry, and from whether throughput climbs during
+/// the walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CacheTemperature {
+    /// Lookups answered in microseconds — metadata was cached.
+    Warm,
+
+    /// Part cached, part not, or caches warmed up during the walk.
+    Mixed,
+
+    /// Lookups consistently went to storage.
+    Cold,
+
+    /// Too few measurements to tell.
+    Unknown,
+}
+
+impl fmt::Display for CacheTemperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warm => "warm",
+            Self::Mixed => "mixed",
+            Self::Cold => "cold",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+impl CacheTemperature {
+    /// The heuristic behind [`ScanProfile::cache_temperature`].
+    fn estimate(stat: Option<&Percentiles>, source: &Percentiles, throughput: &[f64]) -> Self {
+        const MICRO: Duration = Duration::from_micros(1);
+
+        // Per-call thresholds: (warm below, cold above)
+        let (sample, warm, cold) = match stat {
+            Some(s) if s.samples >= MIN_SAMPLES => (s, 20 * MICRO, 200 * MICRO),
+            _ if source.samples >= MIN_SAMPLES => (source, 5 * MICRO, 50 * MICRO),
+            _ => return Self::Unknown,
+        };
+
+        // Throughput that climbs severalfold means the cache filled mid-walk
+        if let (Some(&first), Some(&last)) = (throughput.first(), throughput.last())
+            && throughput.len() >= 4
+            && first > 0.0
+            && last / first > 3.0
+        {
+            return Self::Mixed;
+        }
+
+        if sample.p50 >= cold {
+            Self::Cold
+        } else if sample.p50 < warm && sample.p95 < cold {
+            Self::Warm
+        } else {
+            Self::Mixed
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DirReport
+// ---------------------------------------------------------------------------
+
+/// The directories that cost a search the most. See
+/// [`Results::dir_report`](crate::Results::dir_report).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DirReport {
+    /// The directories that took longest to enumerate, slowest first.
+    pub slowest: Vec<DirCost>,
+
+    /// The directories that yielded the most entries, largest first.
+    pub largest: Vec<DirCost>,
+}
+
+/// What one directory cost a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirCost {
+    /// The directory.
+    pub path: PathBuf,
+
+    /// Time spent pulling its entries from the source.
+    pub elapsed: Duration,
+
+    /// Entries the source yielded from it, not counting those of its
+    /// subdirectories.
+    pub entries: usize,
+}
+
+/// Source pull time and entry counts added up per parent directory.
+/// Owned by the thread that pulls from the source.
+#[derive(Default)]
+pub(crate) struct DirTally(HashMap<PathBuf, (Duration, usize)>);
+
+impl DirTally {
+    /// One pull took `d` and yielded an entry of `parent`.
+    pub(crate) fn add(&mut self, parent: &Path, d: Duration) {
+        match self.0.get_mut(parent) {
+            Some((elapsed, entries)) => {
+                *elapsed += d;
+                *entries += 1;
+            }
+            None => {
+                self.0.insert(parent.to_path_buf(), (d, 1));
+            }
+        }
+    }
+
+    /// The top `n` directories by time and by entry count.
+    pub(crate) fn report(&self, n: usize) -> DirReport {
+        let mut costs: Vec<DirCost> = self
+            .0
+            .iter()
+            .map(|(path, &(elapsed, entries))| DirCost {
+                path: path.clone(),
+                elapsed,
+                entries,
+            })
+            .collect();
+        // Ties broken by path so the report does not depend on hash order
+        costs.sort_by(|a, b| b.entries.cmp(&a.entries).then_with(|| a.path.cmp(&b.path)));
+        let largest = costs.iter().take(n).cloned().collect();
+        costs.sort_by(|a, b| b.elapsed.cmp(&a.elapsed).then_with(|| a.path.cmp(&b.path)));
+        costs.truncate(n);
+        DirReport {
+            slowest: costs,
+            largest,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Histogram
+// ---------------------------------------------------------------------------
+
+/// Sub-buckets per power of two. 16 bounds the error at 1/16.
+#[cfg(not(feature = "hdr"))]
+const SUB: usize = 16;
+#[cfg(not(feature = "hdr"))]
+const SUB_BITS: u32 = SUB.trailing_zeros();
+#[cfg(not(feature = "hdr"))]
+const BUCKETS: usize = SUB + (64 - SUB_BITS as usize) * SUB;
+
+/// A lock-free log-linear histogram of nanosecond values.
+#[cfg(not(feature = "hdr"))]
+pub(crate) struct Histogram {
+    counts: Box<[AtomicU64]>,
+    max: AtomicU64,
+}
+
+#[cfg(not(feature = "hdr"))]
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            counts: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, d: Duration) {
+        let v = u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[Self::index(v)].fetch_add(1, Ordering::Relaxed);
+        self.max.fetch_max(v, Ordering::Relaxed);
+    }
+
+    fn index(v: u64) -> usize {
+        if v < SUB as u64 {
+            return v as usize;
+        }
+        let exp = 63 - v.leading_zeros();
+        let sub = (v >> (exp - SUB_BITS)) as usize & (SUB - 1);
+        SUB + (exp - SUB_BITS) as usize * SUB + sub
+    }
+
+    /// The midpoint of bucket `i`.
+    fn value(i: usize) -> u64 {
+        if i < SUB {
+            return i as u64;
+        }
+        let shift = ((i - SUB) / SUB) as u32;
+        let low = ((SUB + (i - SUB) % SUB) as u64) << shift;
+        low + ((1u64 << shift) >> 1)
+    }
+
+    fn percentiles(&self) -> Percentiles {
+        let counts: Vec<u64> = self
+            .counts
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed))
+            .collect();
+        let samples: u64 = counts.iter().sum();
+        let max = self.max.load(Ordering::Relaxed);
+        let at = |q: f64| {
+            let rank = ((samples as f64 * q).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (i, &c) in counts.iter().enumerate() {
+                seen += c;
+                if seen >= rank {
+                    return Duration::from_nanos(Self::value(i).min(max));
+                }
+            }
+            Duration::from_nanos(max)
+        };
+
+        if samples == 0 {
+            return Percentiles::default();
+        }
+        Percentiles {
+            samples,
+            p50: at(0.50),
+            p95: at(0.95),
+            p99: at(0.99),
+            max: Duration::from_nanos(max),
+        }
+    }
+}
+
+/// An HdrHistogram of nanosecond values, three significant digits.
+#[cfg(feature = "hdr")]
+pub(crate) struct Histogram(Mutex<hdrhistogram::Histogram<u64>>);
+
+#[cfg(feature = "hdr")]
+impl Histogram {
+    fn new() -> Self {
+        // Cannot fail: 3 significant digits is within the supported range
+        let hist = hdrhistogram::Histogram::new(3).expect("valid precision");
+        Self(Mutex::new(hist))
+    }
+
+    fn record(&self, d: Duration) {
+        let v = u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .saturating_record(v);
+    }
+
+    fn percentiles(&self) -> Percentiles {
+        let hist = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if hist.is_empty() {
+            return Percentiles::default();
+        }
+        let at = |q: f64| Duration::from_nanos(hist.value_at_quantile(q));
+        Percentiles {
+            samples: hist.len(),
+            p50: at(0.50),
+            p95: at(0.95),
+            p99: at(0.99),
+            max: Duration::from_nanos(hist.max()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Profiler
+// ---------------------------------------------------------------------------
+
+/// Collects measurements during a profiled search. Shared by every thread
+/// that matches entries.
+pub(crate) struct Profiler {
+    source: Histogram,
+    stat: Histogram,
+    matching: Histogram,
+    windows: Mutex<Windows>,
+}
+
+struct Windows {
+    entries: usize,
+    started: Instant,
+    rates: Vec<f64>,
+}
+
+impl Profiler {
+    pub(crate) fn new() -> Self {
+        Self {
+            source: Histogram::new(),
+            stat: Histogram::new(),
+            matching: Histogram::new(),
+            windows: Mutex::new(Windows {
+                entries: 0,
+                started: Instant::now(),
+                rates: Vec::new(),
+            }),
+        }
+    }
+
+    /// One pull from the source took `d`.
+    pub(crate) fn source_pull(&self, d: Duration) {
+        self.source.record(d);
+    }
+
+    /// Matching one entry took `d`.
+    pub(crate) fn matched(&self, d: Duration) {
+        self.matching.record(d);
+    }
+
+    /// One `stat()` took `d`.
+    pub(crate) fn stat(&self, d: Duration) {
+        self.stat.record(d);
+    }
+
+    /// One entry was admitted to the walk.
+    pub(crate) fn entry(&self) {
+        let mut w = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        w.entries += 1;
+        if w.entries == WINDOW {
+            let secs = w.started.elapsed().as_secs_f64();
+            w.rates.push(if secs > 0.0 {
+                WINDOW as f64 / secs
+            } else {
+                0.0
+            });
+            w.entries = 0;
+            w.started = Instant::now();
+        }
+    }
+
+    /// The profile, with per-directory times taken from `dirs`.
+    pub(crate) fn finish(self, dirs: &DirTally) -> ScanProfile {
+        let throughput = self
+            .windows
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .rates;
+        let source_latency = self.source.percentiles();
+        let dir_latency = Histogram::new();
+        for &(elapsed, _) in dirs.0.values() {
+            dir_latency.record(elapsed);
+        }
+        let stat = self.stat.percentiles();
+        let stat_latency = (stat.samples > 0).then_some(stat);
+        ScanProfile {
+            cache_temperature: CacheTemperature::estimate(
+                stat_latency.as_ref(),
+                &source_latency,
+                &throughput,
+            ),
+            throughput,
+            source_latency,
+            dir_latency: dir_latency.percentiles(),
+            entry_latency: self.matching.percentiles(),
+            stat_latency,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Publishing matches to a message queue as the walk runs.
+//!
+//! Enabled with the `queue` feature. parex does not ship a Kafka, NATS, or
+//! AMQP client — implement [`Publisher`] over the client you already use and
+//! wrap it in a [`QueueSink`].
+
+use std::sync::mpsc::{Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::traits::Sink;
+
+// ---------------------------------------------------------------------------
+// Publisher
+// ---------------------------------------------------------------------------
+
+/// Sends one matched entry to an external queue.
+///
+/// A publisher runs on a dedicated thread owned by [`QueueSink`], so it may
+/// block on network IO without stalling the walk until the buffer fills.
+/// Return a fatal error to halt the walk; a recoverable error is reported and
+/// publishing continues.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::queue::Publisher;
+/// use parex::{Entry, ParexError};
+///
+/// struct StdoutTopic;
+///
+/// impl Publisher for StdoutTopic {
+///     fn publish(&mut self, entry: &Entry) -> Result<(), ParexError> {
+///         println!("{{\"path\":{:?}}}", entry.path);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Publisher: Send + 'static {
+    /// Publish one entry.
+    fn publish(&mut self, entry: &Entry) -> Result<(), ParexError>;
+
+    /// Called once after the last entry, before the publishing thread exits.
+    fn flush(&mut self) -> Result<(), ParexError> {
+        Ok(())
+    }
+}
+
+/// Forwards entries into a bounded in-process channel, blocking while it is full.
+impl Publisher for SyncSender<Entry> {
+    fn publish(&mut self, entry: &Entry) -> Result<(), ParexError> {
+        self.send(entry.clone())
+            .map_err(|_| ParexError::source_err(std::io::Error::other("queue receiver hung up")))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// QueueSink
+// ---------------------------------------------------------------------------
+
+/// A [`Sink`] that hands matches to a [`Publisher`] on a background thread.
+///
+/// Matches are buffered in a bounded channel of `capacity` entries. When the
+/// publisher falls behind and the buffer is full, [`accept`](Sink::accept)
+/// blocks — the walk slows to the queue's pace instead of buffering without
+/// limit.
+///
+/// Publisher errors surface on the next `accept` call, and from
+/// [`close`](Self::close). Call `close` after the search to wait until every
+/// buffered match has been published; dropping the sink does the same but
+/// discards any error.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::queue::QueueSink;
+/// use std::sync::Arc;
+///
+/// let (tx, rx) = std::sync::mpsc::sync_channel(64);
+/// let sink = Arc::new(QueueSink::new(tx, 1024).unwrap());
+///
+/// // .sink(Arc::clone(&sink)) on the builder, run the search, then:
+/// sink.close().unwrap();
+/// assert!(rx.try_recv().is_err());
+/// ```
+pub struct QueueSink {
+    tx: Mutex<Option<SyncSender<Entry>>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+    errors: Arc<Mutex<Vec<ParexError>>>,
+}
+
+impl QueueSink {
+    /// Start a publishing thread for `publisher`, buffering up to `capacity` matches.
+    pub fn new(publisher: impl Publisher, capacity: usize) -> Result<Self, ParexError> {
+        let (tx, rx) = std::sync::mpsc::sync_channel::<Entry>(capacity.max(1));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+
+        let worker = {
+            let errors = Arc::clone(&errors);
+            std::thread::Builder::new()
+                .name("parex-publish".into())
+                .spawn(move || publish_worker(publisher, &rx, &errors))
+                .map_err(|e| ParexError::ThreadPool(e.to_string()))?
+        };
+
+        Ok(Self {
+            tx: Mutex::new(Some(tx)),
+            worker: Mutex::new(Some(worker)),
+            errors,
+        })
+    }
+
+    /// Stop accepting matches and wait for the buffer to drain.
+    ///
+    /// Returns the first publisher error that has not already been reported
+    /// through `accept`. Closing twice is a no-op.
+    pub fn close(&self) -> Result<(), ParexError> {
+        self.tx.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(worker) = self.worker.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            let _ = worker.join();
+        }
+        self.take_error()
+    }
+
+    fn take_error(&self) -> Result<(), ParexError> {
+        let mut errors = self.errors.lock().unwrap_or_else(|e| e.into_inner());
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.remove(0))
+        }
+    }
+}
+
+impl Sink for QueueSink {
+    fn accept(&self, entry: &Entry) -> Result<(), ParexError> {
+        self.take_error()?;
+
+        // Clone the sender out so a blocked send does not hold the lock
+        let tx = self.tx.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let Some(tx) = tx else {
+            return Err(ParexError::source_err(std::io::Error::other(
+                "queue sink is closed",
+            )));
+        };
+        tx.send(entry.clone())
+            .map_err(|_| ParexError::ThreadPool("publishing thread exited unexpectedly".into()))
+    }
+}
+
+impl Drop for QueueSink {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+fn publish_worker(
+    mut publisher: impl Publisher,
+    rx: &Receiver<Entry>,
+    errors: &Mutex<Vec<ParexError>>,
+) {
+    let report = |err: ParexError| errors.lock().unwrap_or_else(|e| e.into_inner()).push(err);
+
+    for entry in rx {
+        if let Err(err) = publisher.publish(&entry) {
+            let fatal = err.is_fatal();
+            report(err);
+            if fatal {
+                // Hanging up makes the next accept fail instead of blocking
+                return;
+            }
+        }
+    }
+    if let Err(err) = publisher.flush() {
+        report(err);
+    }
+}
@@ -0,0 +1,323 @@
+//! Ranking matched entries after the walk.
+//!
+//! By default parex treats matching as a yes/no question and reports
+//! matches unordered. When a search is built with
+//! [`SearchBuilder::rerank`](crate::SearchBuilder::rerank), every match is
+//! also kept as a [`ScoredEntry`] and handed to a [`Rerank`] stage once the
+//! walk ends — the place for recency boosts, usage history, or a model that
+//! is too expensive to run on every entry. The outcome lands in
+//! [`Results::ranked`](crate::Results::ranked), best first.
+//!
+//! Matching can be graded from the start, too: a [`ScoredMatcher`] passed
+//! to [`SearchBuilder::rank_by_score`](crate::SearchBuilder::rank_by_score)
+//! both decides which entries match and gives each its initial score —
+//! the shape of a fuzzy file picker.
+//!
+//! [`Frecency`] is a ready-made stage that boosts paths the user opens
+//! often and recently, from a caller-provided [`UsageStore`].
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::context::MatchContext;
+use crate::entry::Entry;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// ScoredEntry
+// ---------------------------------------------------------------------------
+
+/// A matched entry with its relevance score. Higher is better.
+#[derive(Debug, Clone)]
+pub struct ScoredEntry {
+    /// The matched entry.
+    pub entry: Entry,
+
+    /// Relevance score. Starts at `0.0` for every match.
+    pub score: f32,
+}
+
+impl ScoredEntry {
+    /// `entry` with a score of `0.0`.
+    pub fn new(entry: Entry) -> Self {
+        Self { entry, score: 0.0 }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ScoredMatcher
+// ---------------------------------------------------------------------------
+
+/// A matcher that grades entries instead of accepting or rejecting them.
+///
+/// `Some(score)` is a match, higher scores better; `None` is no match.
+/// Used through [`SearchBuilder::rank_by_score`](crate::SearchBuilder::rank_by_score),
+/// which scores each entry once, on the engine's matcher threads, and
+/// carries the score of a match through to [`Results::ranked`](crate::Results::ranked).
+///
+/// Closures of the form `Fn(&Entry) -> Option<f32>` implement
+/// `ScoredMatcher`.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::Entry;
+/// use parex::rank::ScoredMatcher;
+///
+/// /// Names containing every character of the query, in order. Tighter
+/// /// runs score higher.
+/// struct Fuzzy(String);
+///
+/// impl ScoredMatcher for Fuzzy {
+///     fn score(&self, entry: &Entry) -> Option<f32> {
+///         let name = entry.path.file_name()?.to_str()?.to_lowercase();
+///         let (mut from, mut gaps) = (0, 0);
+///         for c in self.0.chars() {
+///             let at = name[from..].find(c)?;
+///             gaps += at;
+///             from += at + c.len_utf8();
+///         }
+///         Some(-(gaps as f32))
+///     }
+/// }
+/// # let _ =
+/// parex::search().rank_by_score(Fuzzy("invc".into())).top_k(20);
+/// ```
+pub trait ScoredMatcher: Send + Sync {
+    /// The relevance of `entry`, or `None` if it does not match.
+    fn score(&self, entry: &Entry) -> Option<f32>;
+
+    /// How much work [`score`](Self::score) does per entry. Defaults to
+    /// [`Cost::Name`]; see [`Matcher::cost`].
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
+}
+
+impl<F> ScoredMatcher for F
+where
+    F: Fn(&Entry) -> Option<f32> + Send + Sync,
+{
+    fn score(&self, entry: &Entry) -> Option<f32> {
+        self(entry)
+    }
+}
+
+/// Runs a [`ScoredMatcher`] as a filter: entries with a score match, and
+/// the score is left in the [`MatchContext`] for the engine to collect.
+pub(crate) struct Scored(pub(crate) Box<dyn ScoredMatcher>);
+
+impl Matcher for Scored {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.0.score(entry).is_some()
+    }
+
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        match self.0.score(entry) {
+            Some(score) => {
+                ctx.set_score(score);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn cost(&self) -> Cost {
+        self.0.cost()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rerank
+// ---------------------------------------------------------------------------
+
+/// A post-collection stage that adjusts the scores of matched entries.
+///
+/// Runs once, on the thread that called [`run()`](crate::SearchBuilder::run),
+/// after the walk has finished. Raise or lower `score`s, or remove
+/// candidates outright; parex then sorts what remains by score, best first.
+/// Candidates with equal scores keep the order the reranker left them in.
+///
+/// Closures of the form `Fn(&mut Vec<ScoredEntry>)` implement `Rerank`.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::rank::ScoredEntry;
+///
+/// // Prefer shallow entries
+/// let shallow_first = |candidates: &mut Vec<ScoredEntry>| {
+///     for c in candidates.iter_mut() {
+///         c.score -= c.entry.depth as f32;
+///     }
+/// };
+/// # let _ = parex::search().rerank(shallow_first);
+/// ```
+pub trait Rerank: Send + Sync {
+    /// Adjust `candidates` in place.
+    fn rerank(&self, candidates: &mut Vec<ScoredEntry>);
+}
+
+impl<F> Rerank for F
+where
+    F: Fn(&mut Vec<ScoredEntry>) + Send + Sync,
+{
+    fn rerank(&self, candidates: &mut Vec<ScoredEntry>) {
+        self(candidates)
+    }
+}
+
+/// Sort `candidates` best first. Stable, and NaN scores sort last.
+pub fn sort_by_score(candidates: &mut [ScoredEntry]) {
+    candidates.sort_by(best_first);
+}
+
+/// Drop all but the `k` best of `candidates`, leaving them unordered.
+pub(crate) fn keep_best(candidates: &mut Vec<ScoredEntry>, k: usize) {
+    if candidates.len() <= k {
+        return;
+    }
+    if k > 0 {
+        candidates.select_nth_unstable_by(k - 1, best_first);
+    }
+    candidates.truncate(k);
+}
+
+/// Higher scores first, NaN last.
+fn best_first(a: &ScoredEntry, b: &ScoredEntry) -> Ordering {
+    match (a.score.is_nan(), b.score.is_nan()) {
+        (false, false) => b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal),
+        (x, y) => x.cmp(&y),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Frecency
+// ---------------------------------------------------------------------------
+
+/// How often and how recently a path was used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    /// Number of recorded uses.
+    pub count: u32,
+
+    /// When the path was last used.
+    pub last_used: SystemTime,
+}
+
+/// A caller-provided history of which paths the user opens.
+///
+/// Looked up in batches so a store backed by a database or a remote
+/// service pays one round trip per batch rather than one per match.
+pub trait UsageStore: Send + Sync {
+    /// The usage of each of `paths`, in the same order. `None` for paths
+    /// with no history. Must return exactly `paths.len()` items.
+    fn lookup(&self, paths: &[&Path]) -> Vec<Option<Usage>>;
+}
+
+impl UsageStore for HashMap<PathBuf, Usage> {
+    fn lookup(&self, paths: &[&Path]) -> Vec<Option<Usage>> {
+        paths.iter().map(|p| self.get(*p).copied()).collect()
+    }
+}
+
+/// Lets the caller keep recording into a store it hands to [`Frecency`].
+impl<S: UsageStore + ?Sized> UsageStore for std::sync::Arc<S> {
+    fn lookup(&self, paths: &[&Path]) -> Vec<Option<Usage>> {
+        (**self).lookup(paths)
+    }
+}
+
+/// A [`Rerank`] stage that adds a frecency boost from a [`UsageStore`].
+///
+/// Each candidate's score rises by `weight × count × 0.5^(age / half_life)`,
+/// where `age` is the time since the path was last used: a file opened ten
+/// times today outranks one opened ten times last year, which outranks one
+/// never opened. Defaults: weight 1, half-life one week, batches of 256.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::rank::{Frecency, Usage};
+/// use std::collections::HashMap;
+/// use std::path::PathBuf;
+/// use std::time::SystemTime;
+///
+/// let mut history = HashMap::new();
+/// history.insert(
+///     PathBuf::from("/home/me/notes.md"),
+///     Usage { count: 12, last_used: SystemTime::now() },
+/// );
+/// # let _ =
+/// parex::search().rerank(Frecency::new(history).weight(0.5));
+/// ```
+pub struct Frecency<S> {
+    store: S,
+    now: SystemTime,
+    weight: f32,
+    half_life: Duration,
+    batch: usize,
+}
+
+impl<S: UsageStore> Frecency<S> {
+    /// Boost from `store`, measuring ages from the current time.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            now: SystemTime::now(),
+            weight: 1.0,
+            half_life: Duration::from_secs(7 * 24 * 60 * 60),
+            batch: 256,
+        }
+    }
+
+    /// Scale the boost. Default: 1.
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Age at which a use counts half as much. Default: one week.
+    pub fn half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// Paths per [`UsageStore::lookup`] call. `0` is treated as `1`.
+    /// Default: 256.
+    pub fn batch(mut self, batch: usize) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    /// Measure ages from `now` instead of the current time, for
+    /// reproducible rankings.
+    pub fn as_of(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// The boost for one use record.
+    fn boost(&self, usage: Usage) -> f32 {
+        let age = self.now.duration_since(usage.last_used).unwrap_or_default();
+        let half_lives = age.as_secs_f64() / self.half_life.as_secs_f64().max(f64::MIN_POSITIVE);
+        (f64::from(self.weight) * f64::from(usage.count) * 0.5f64.powf(half_lives)) as f32
+    }
+}
+
+impl<S: UsageStore> Rerank for Frecency<S> {
+    fn rerank(&self, candidates: &mut Vec<ScoredEntry>) {
+        for chunk in candidates.chunks_mut(self.batch) {
+            let paths: Vec<&Path> = chunk.iter().map(|c| c.entry.path.as_path()).collect();
+            let usages = self.store.lookup(&paths);
+            for (c, usage) in chunk.iter_mut().zip(usages) {
+                if let Some(usage) = usage {
+                    c.score += self.boost(usage);
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::entry::{Entry, EntryKind};
 use crate::error::ParexError;
+use crate::profile::{DirReport, ScanProfile};
+use crate::rank::ScoredEntry;
+use crate::rules::Violation;
+use crate::sort::SortOrder;
+use crate::warning::Warning;
 
 /// The output of a completed search.
 ///
@@ -12,17 +18,153 @@ pub struct Results {
     /// Total number of entries that matched the search criteria.
     pub matches: usize,
 
+    /// How many of the matches are files.
+    pub matched_files: usize,
+
+    /// How many of the matches are directories.
+    pub matched_dirs: usize,
+
+    /// How many of the matches are symlinks.
+    pub matched_symlinks: usize,
+
+    /// How many of the matches are of [`EntryKind::Other`] — whatever
+    /// custom kind the source maps there.
+    pub matched_other: usize,
+
+    /// Number of matches at each depth: `match_depths[d]` matches were
+    /// found `d` levels below the root. Only populated if
+    /// `.depth_histogram(true)` was set on the builder.
+    pub match_depths: Vec<usize>,
+
     /// Paths of matched entries, in the order they were found.
     /// Only populated if `.collect_paths(true)` was set on the builder.
     pub paths: Vec<PathBuf>,
 
+    /// Matched entries — kind, depth, metadata, and tags alongside the
+    /// path — in the order they were found.
+    /// Only populated if `.collect_entries(true)` was set on the builder.
+    pub entries: Vec<Entry>,
+
     /// Scan performance statistics.
     pub stats: ScanStats,
 
     /// Non-fatal errors encountered during the search (permission denied, etc.).
-    /// Only populated if `.collect_errors(true)` was set on the builder.
+    /// Only populated if `.collect_errors(true)` was set on the builder, apart
+    /// from a failure to write the `.checkpoint()` file, which is always kept.
     /// Use [`ParexError::is_recoverable`] to distinguish warnings from failures.
     pub errors: Vec<ParexError>,
+
+    /// Diagnostics that did not stop an entry from being handled — files
+    /// skipped as binary, throttled requests, and the like.
+    /// Only populated if `.collect_warnings(true)` was set on the builder.
+    pub warnings: Vec<Warning>,
+
+    /// Every match with its score, best first — or the best `k` with
+    /// `.top_k(k)`. Only populated if a [`Rerank`](crate::rank::Rerank)
+    /// stage was set with `.rerank()` on the builder, or a
+    /// [`ScoredMatcher`](crate::rank::ScoredMatcher) with `.rank_by_score()`.
+    pub ranked: Vec<ScoredEntry>,
+
+    /// Every [`rule`](crate::SearchBuilder::rule) the matches broke, in the
+    /// order the rules were added. Evaluated over whatever the search saw,
+    /// so a cancelled or failed search is checked on its partial matches.
+    pub violations: Vec<Violation>,
+
+    /// The directories that took longest or held the most entries.
+    /// Only populated if `.slowest_dirs(n)` was set on the builder.
+    pub dir_report: Option<DirReport>,
+
+    /// Latency and throughput detail.
+    /// Only populated if `.profile(true)` was set on the builder.
+    pub profile: Option<ScanProfile>,
+
+    /// How the search ended.
+    ///
+    /// A cancelled or failed search still reports everything gathered up to
+    /// that point — `matches`, `paths`, `stats`, `errors`, and `warnings` describe the
+    /// partial walk.
+    pub completion: Completion,
+}
+
+impl Results {
+    /// Whether the search ran to completion (or to its match limit)
+    /// rather than being cancelled or halted by a fatal error.
+    pub fn is_finished(&self) -> bool {
+        matches!(self.completion, Completion::Finished)
+    }
+
+    /// How many matches are of `kind`.
+    pub fn matched(&self, kind: EntryKind) -> usize {
+        match kind {
+            EntryKind::File => self.matched_files,
+            EntryKind::Dir => self.matched_dirs,
+            EntryKind::Symlink => self.matched_symlinks,
+            EntryKind::Other => self.matched_other,
+        }
+    }
+
+    /// Up to `len` collected paths starting at `offset`, with enough
+    /// position information to request the next page.
+    ///
+    /// Lets a UI show matches a screenful at a time without copying the
+    /// whole result set. An `offset` past the end yields an empty page.
+    /// Pages are stable only while `paths` is not re-sorted or modified.
+    pub fn page(&self, offset: usize, len: usize) -> Page<'_> {
+        let total = self.paths.len();
+        let start = offset.min(total);
+        let end = start.saturating_add(len).min(total);
+        Page {
+            paths: &self.paths[start..end],
+            offset: start,
+            total,
+        }
+    }
+
+    /// Sort [`paths`](Self::paths) and [`entries`](Self::entries) in
+    /// `order`.
+    ///
+    /// The engine yields matches in no particular order; this is what
+    /// [`SearchBuilder::sort`](crate::SearchBuilder::sort) runs after the walk.
+    pub fn sort_paths(&mut self, order: &SortOrder) {
+        self.paths.sort_by(|a, b| order.compare(a, b));
+        self.entries.sort_by(|a, b| order.compare(&a.path, &b.path));
+    }
+}
+
+/// A slice of [`Results::paths`] returned by [`Results::page`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page<'a> {
+    /// The paths on this page.
+    pub paths: &'a [PathBuf],
+
+    /// Index of the first path on this page within `Results::paths`.
+    pub offset: usize,
+
+    /// Total number of collected paths.
+    pub total: usize,
+}
+
+impl Page<'_> {
+    /// Offset of the following page, or `None` if this is the last one.
+    pub fn next_offset(&self) -> Option<usize> {
+        let end = self.offset + self.paths.len();
+        (end < self.total && !self.paths.is_empty()).then_some(end)
+    }
+}
+
+/// How a search ended. See [`Results::completion`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Completion {
+    /// The walk ran to the end, or stopped early because the match limit was reached.
+    Finished,
+
+    /// The search was cancelled via its [`CancelToken`](crate::engine::CancelToken),
+    /// a dropped stream, or a process signal.
+    Cancelled,
+
+    /// The source yielded a fatal error and the walk was halted.
+    Failed(ParexError),
 }
 
 /// Performance statistics for a completed scan.
@@ -40,11 +182,55 @@ pub struct ScanStats {
     /// `(files + dirs) / duration.as_secs_f64()`, clamped to 0 on
     /// zero-duration runs.
     pub entries_per_sec: usize,
+
+    /// Whether `files` and `dirs` describe the whole source.
+    ///
+    /// `false` when the walk stopped early — the counts then cover only the
+    /// prefix of the source that was visited. See `stopped_reason`.
+    pub complete: bool,
+
+    /// Why the walk stopped before exhausting the source, if it did.
+    pub stopped_reason: Option<StopReason>,
+
+    /// Share of matcher-thread time, in percent, spent parked waiting for
+    /// entries.
+    ///
+    /// Near 100 means the source is the bottleneck and extra threads only
+    /// sleep; near 0 means matching is. `None` when matching ran on the
+    /// consuming thread rather than the worker pool.
+    pub idle_percent: Option<f64>,
+
+    /// Time from search start until the first match was delivered, or
+    /// `None` if nothing matched.
+    ///
+    /// What an interactive user actually waits for — often far shorter
+    /// than `duration`. With parallel matching it includes the hand-off
+    /// back from the worker pool.
+    pub time_to_first_match: Option<Duration>,
+}
+
+/// Why a walk stopped before exhausting its source. See [`ScanStats::stopped_reason`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StopReason {
+    /// The match limit set with `.limit()` was reached.
+    Limit,
+
+    /// The search was cancelled.
+    Cancelled,
+
+    /// A fatal error halted the walk.
+    Failed,
 }
 
 impl ScanStats {
     /// Compute `entries_per_sec` from raw counts and duration.
-    pub(crate) fn compute(files: usize, dirs: usize, duration: Duration) -> Self {
+    pub(crate) fn compute(
+        files: usize,
+        dirs: usize,
+        duration: Duration,
+        stopped_reason: Option<StopReason>,
+    ) -> Self {
         let total = files + dirs;
         let eps = if duration.as_secs_f64() > 0.0 {
             (total as f64 / duration.as_secs_f64()) as usize
@@ -56,6 +242,10 @@ impl ScanStats {
             dirs,
             duration,
             entries_per_sec: eps,
+            complete: stopped_reason.is_none(),
+            stopped_reason,
+            idle_percent: None,
+            time_to_first_match: None,
         }
     }
 }
@@ -0,0 +1,283 @@
+//! Limits checked against what a search matched.
+//!
+//! A [`Rule`] names an [`Aggregate`] — a count or byte total over the
+//! matches, overall or per directory — and the threshold it must not
+//! exceed. Add rules with [`SearchBuilder::rule`](crate::SearchBuilder::rule);
+//! when the search ends every rule is evaluated and each breach is listed in
+//! [`Results::violations`](crate::Results::violations), so a monitoring agent
+//! reads "which directories are over quota" instead of summing matches
+//! itself. A rule with [`Action::Notify`] also raises an
+//! [`Event::Violated`](crate::notify::Event::Violated) for the search's
+//! notifiers.
+//!
+//! ```rust
+//! use parex::rules::{Action, Aggregate, Rule};
+//! # use parex::{Source, Entry, ParexError};
+//! # use parex::engine::WalkConfig;
+//! # struct Files;
+//! # impl Source for Files {
+//! #     fn walk(&self, _: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+//! #         Box::new(std::iter::empty())
+//! #     }
+//! # }
+//!
+//! const GB: u64 = 1 << 30;
+//! let results = parex::search()
+//!     .source(Files)
+//!     .rule(Rule::new(Aggregate::DirBytes, 100 * GB).with_action(Action::Notify))
+//!     .run()?;
+//!
+//! for v in &results.violations {
+//!     println!("{} holds {} bytes", v.dir.as_ref().unwrap().display(), v.value);
+//! }
+//! # Ok::<(), ParexError>(())
+//! ```
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::aggregate::{AgeBucket, AgeBuckets, BucketTotals};
+use crate::entry::{Entry, EntryKind};
+use crate::results::Results;
+
+// ---------------------------------------------------------------------------
+// Rule
+// ---------------------------------------------------------------------------
+
+/// A quantity computed over a search's matches.
+///
+/// Byte totals count matched regular files, taking size and mtime from
+/// [`Entry::metadata`] when the source cached it, otherwise from a `stat()`;
+/// files that cannot be stat'ed are left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Aggregate {
+    /// [`Results::matches`].
+    Matches,
+
+    /// Collected errors. Needs
+    /// [`collect_errors`](crate::SearchBuilder::collect_errors).
+    Errors,
+
+    /// Bytes in all matched files.
+    Bytes,
+
+    /// Bytes in matched files last modified in this age range.
+    AgeBytes(AgeBucket),
+
+    /// Bytes in matched files below each directory. Checked per directory.
+    DirBytes,
+
+    /// Matched files below each directory. Checked per directory.
+    DirFiles,
+}
+
+impl Aggregate {
+    /// Whether the aggregate has a value per directory rather than one for
+    /// the whole search.
+    pub fn per_dir(&self) -> bool {
+        matches!(self, Self::DirBytes | Self::DirFiles)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Matches => "matches",
+            Self::Errors => "errors",
+            Self::Bytes => "bytes",
+            Self::AgeBytes(_) => "age_bytes",
+            Self::DirBytes => "dir_bytes",
+            Self::DirFiles => "dir_files",
+        }
+    }
+}
+
+/// What happens when a [`Rule`] is violated, besides the entry in
+/// [`Results::violations`](crate::Results::violations).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Action {
+    /// Nothing else.
+    #[default]
+    Report,
+
+    /// Raise an [`Event::Violated`](crate::notify::Event::Violated) for
+    /// every [`notify`](crate::SearchBuilder::notify) notifier.
+    Notify,
+}
+
+/// A limit on an [`Aggregate`]: the rule is violated when the aggregate
+/// exceeds `threshold` — for a per-directory aggregate, once for every
+/// directory that does.
+///
+/// A directory's totals include everything below it, so the parents of a
+/// violating directory often violate the rule too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Rule {
+    /// What is measured.
+    pub aggregate: Aggregate,
+
+    /// The largest value that does not violate the rule.
+    pub threshold: u64,
+
+    /// What a violation does.
+    pub action: Action,
+}
+
+impl Rule {
+    /// A rule that [reports](Action::Report) `aggregate` exceeding
+    /// `threshold`.
+    pub fn new(aggregate: Aggregate, threshold: u64) -> Self {
+        Self {
+            aggregate,
+            threshold,
+            action: Action::Report,
+        }
+    }
+
+    /// Do `action` on a violation.
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub(crate) fn to_json(self) -> String {
+        let aggregate = match self.aggregate {
+            Aggregate::AgeBytes(bucket) => {
+                let age = format!("{bucket:?}").to_lowercase();
+                format!("\"age_bytes\",\"age\":\"{age}\"")
+            }
+            other => format!("\"{}\"", other.name()),
+        };
+        format!(
+            "{{\"aggregate\":{aggregate},\"threshold\":{}}}",
+            self.threshold
+        )
+    }
+}
+
+/// A [`Rule`] the search's matches broke.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Violation {
+    /// The rule.
+    pub rule: Rule,
+
+    /// The aggregate's value, which exceeds `rule.threshold`.
+    pub value: u64,
+
+    /// The directory, for a per-directory aggregate.
+    pub dir: Option<PathBuf>,
+}
+
+// ---------------------------------------------------------------------------
+// Tally
+// ---------------------------------------------------------------------------
+
+/// The aggregates a search's rules need, fed every counted match.
+pub(crate) struct Tally {
+    rules: Vec<Rule>,
+    /// Whether any rule needs file sizes.
+    sizes: bool,
+    bytes: u64,
+    ages: Option<AgeBuckets>,
+    dirs: Option<HashMap<PathBuf, BucketTotals>>,
+}
+
+impl Tally {
+    /// A tally for `rules`, or `None` if there are none.
+    pub(crate) fn new(rules: Vec<Rule>) -> Option<Self> {
+        if rules.is_empty() {
+            return None;
+        }
+        let any = |f: fn(&Aggregate) -> bool| rules.iter().any(|r| f(&r.aggregate));
+        Some(Self {
+            sizes: any(|a| !matches!(a, Aggregate::Matches | Aggregate::Errors)),
+            bytes: 0,
+            ages: any(|a| matches!(a, Aggregate::AgeBytes(_))).then(AgeBuckets::new),
+            dirs: any(Aggregate::per_dir).then(HashMap::new),
+            rules,
+        })
+    }
+
+    /// Count a match.
+    pub(crate) fn add(&mut self, entry: &Entry) {
+        if !self.sizes || entry.kind != EntryKind::File {
+            return;
+        }
+        let stat;
+        let meta = match &entry.metadata {
+            Some(m) => m,
+            None => match std::fs::metadata(&entry.path) {
+                Ok(m) => {
+                    stat = m;
+                    &stat
+                }
+                Err(_) => return,
+            },
+        };
+
+        let len = meta.len();
+        self.bytes += len;
+        if let Some(ages) = &self.ages
+            && let Ok(modified) = meta.modified()
+        {
+            ages.record(modified, len);
+        }
+        if let Some(dirs) = &mut self.dirs {
+            for dir in entry.path.ancestors().skip(1).take(entry.depth) {
+                let totals = match dirs.get_mut(dir) {
+                    Some(totals) => totals,
+                    None => dirs.entry(dir.to_path_buf()).or_default(),
+                };
+                totals.files += 1;
+                totals.bytes += len;
+            }
+        }
+    }
+
+    /// Every violation, in the order the rules were added; per-directory
+    /// ones sorted by path.
+    pub(crate) fn evaluate(self, results: &Results) -> Vec<Violation> {
+        let ages = self.ages.as_ref().map(AgeBuckets::report);
+        let mut dirs: Vec<_> = self.dirs.into_iter().flatten().collect();
+        dirs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut violations = Vec::new();
+        for &rule in &self.rules {
+            let total = match rule.aggregate {
+                Aggregate::Matches => results.matches as u64,
+                Aggregate::Errors => results.errors.len() as u64,
+                Aggregate::Bytes => self.bytes,
+                Aggregate::AgeBytes(bucket) => ages.map_or(0, |a| a.get(bucket).bytes),
+                Aggregate::DirBytes | Aggregate::DirFiles => {
+                    for (dir, totals) in &dirs {
+                        let value = match rule.aggregate {
+                            Aggregate::DirBytes => totals.bytes,
+                            _ => totals.files,
+                        };
+                        if value > rule.threshold {
+                            violations.push(Violation {
+                                rule,
+                                value,
+                                dir: Some(dir.clone()),
+                            });
+                        }
+                    }
+                    continue;
+                }
+            };
+            if total > rule.threshold {
+                violations.push(Violation {
+                    rule,
+                    value: total,
+                    dir: None,
+                });
+            }
+        }
+        violations
+    }
+}
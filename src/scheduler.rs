@@ -0,0 +1,241 @@
+//! Work-stealing distribution of entry batches to matcher threads.
+//!
+//! The source iterator is not `Send`, so one thread pulls entries and packs
+//! them into batches; [`Scheduler`] spreads those batches over per-worker
+//! deques. A worker pops from the back of its own deque and, once that is
+//! empty, steals the older half of another worker's. Batches rather than
+//! single entries keep lock traffic to one acquisition per few dozen
+//! entries, and stealing keeps every core busy when some entries (a large
+//! file for a content matcher) take far longer than others.
+//!
+//! There is no shared lock on the hot path: each deque has its own, and
+//! the count of queued batches is an atomic that workers claim from. A
+//! worker only takes a deque lock other than its own to steal.
+//!
+//! Idle workers never poll: with nothing queued they park on a condvar
+//! until the producer pushes a batch or closes the scheduler, so an
+//! IO-bound source does not leave matcher threads burning CPU. The condvar's
+//! mutex is only taken when someone is, or is about to be, parked.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+use crate::entry::Entry;
+
+pub(crate) type Batch = Vec<Entry>;
+
+/// Retries spent spinning for an in-transit batch before yielding the CPU.
+const SPIN_LIMIT: u32 = 64;
+
+// ---------------------------------------------------------------------------
+// Scheduler
+// ---------------------------------------------------------------------------
+
+pub(crate) struct Scheduler {
+    deques: Vec<Mutex<VecDeque<Batch>>>,
+    /// Queued batches not yet claimed by a worker.
+    pending: AtomicUsize,
+    /// Round-robin position for the next push.
+    next: AtomicUsize,
+    /// Workers that have not exited.
+    live: AtomicUsize,
+    closed: AtomicBool,
+    /// Where workers wait for a batch to be pushed or the scheduler to close.
+    ready: Parking,
+    /// Where the producer waits for a worker to take a batch or exit.
+    space: Parking,
+    /// Batches queued across all deques before [`push`](Self::push) blocks.
+    capacity: usize,
+    /// Emptied buffers waiting for reuse, when recycling is on.
+    free: Option<Mutex<Vec<Batch>>>,
+}
+
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Scheduler {
+    /// A scheduler for `workers` threads holding at most `capacity` batches,
+    /// optionally recycling batch buffers.
+    pub(crate) fn new(workers: usize, capacity: usize, recycle: bool) -> Self {
+        Self {
+            deques: (0..workers).map(|_| Mutex::new(VecDeque::new())).collect(),
+            pending: AtomicUsize::new(0),
+            next: AtomicUsize::new(0),
+            live: AtomicUsize::new(workers),
+            closed: AtomicBool::new(false),
+            ready: Parking::default(),
+            space: Parking::default(),
+            capacity: capacity.max(1),
+            free: recycle.then(|| Mutex::new(Vec::new())),
+        }
+    }
+
+    /// An empty buffer for the next batch — a recycled one if available.
+    pub(crate) fn buffer(&self, batch_size: usize) -> Batch {
+        self.free
+            .as_ref()
+            .and_then(|free| lock(free).pop())
+            .unwrap_or_else(|| Vec::with_capacity(batch_size))
+    }
+
+    /// Return a drained buffer for reuse. Dropped if recycling is off, or
+    /// enough buffers are already waiting to refill every queue slot.
+    pub(crate) fn recycle(&self, mut batch: Batch) {
+        if let Some(free) = &self.free {
+            batch.clear();
+            let mut free = lock(free);
+            if free.len() < self.capacity {
+                free.push(batch);
+            }
+        }
+    }
+
+    /// Queue a batch, blocking while the scheduler is full.
+    ///
+    /// Dropped without blocking if every worker has exited — only possible
+    /// after a matcher panic, which resurfaces when the threads are joined.
+    pub(crate) fn push(&self, batch: Batch) {
+        self.space.wait_while(|| {
+            self.pending.load(Ordering::SeqCst) >= self.capacity
+                && self.live.load(Ordering::SeqCst) > 0
+        });
+        if self.live.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        // Only the producer pushes, so the position needs no ordering
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.deques.len();
+        // Pushed before `pending` is raised, so a claimed batch always exists
+        lock(&self.deques[i]).push_back(batch);
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.ready.notify_one();
+    }
+
+    /// No more batches will be pushed. Workers drain what is queued, then stop.
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.ready.notify_all();
+    }
+
+    /// The next batch for worker `me`, parking while there is none.
+    ///
+    /// `None` once the scheduler is closed and drained.
+    pub(crate) fn next(&self, me: usize) -> Option<Batch> {
+        loop {
+            let claimed = self
+                .pending
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if claimed {
+                break;
+            }
+            // Every push happens before the close, so a closed scheduler
+            // with nothing pending is drained for good
+            if self.closed.load(Ordering::SeqCst) {
+                if self.pending.load(Ordering::SeqCst) == 0 {
+                    return None;
+                }
+                continue;
+            }
+            self.ready.wait_while(|| {
+                self.pending.load(Ordering::SeqCst) == 0 && !self.closed.load(Ordering::SeqCst)
+            });
+        }
+        self.space.notify_one();
+
+        // One batch is reserved for us; it may be briefly in transit
+        // between deques while another worker steals, so retry until found —
+        // spinning first, since the move takes only a few instructions.
+        let mut spins = 0;
+        loop {
+            if let Some(batch) = lock(&self.deques[me]).pop_back() {
+                return Some(batch);
+            }
+            if let Some(batch) = self.steal(me) {
+                return Some(batch);
+            }
+            if spins < SPIN_LIMIT {
+                spins += 1;
+                std::hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    }
+
+    /// Take the older half of the first non-empty deque after `me`'s,
+    /// keeping one batch and moving the rest into `me`'s deque.
+    fn steal(&self, me: usize) -> Option<Batch> {
+        let n = self.deques.len();
+        for victim in (1..n).map(|k| (me + k) % n) {
+            let mut stolen = {
+                let mut deque = lock(&self.deques[victim]);
+                if deque.is_empty() {
+                    continue;
+                }
+                let half = deque.len().div_ceil(2);
+                deque.drain(..half).collect::<VecDeque<_>>()
+            };
+            let batch = stolen.pop_back();
+            if !stolen.is_empty() {
+                lock(&self.deques[me]).extend(stolen);
+            }
+            return batch;
+        }
+        None
+    }
+
+    /// Record that a worker has exited.
+    pub(crate) fn worker_exited(&self) {
+        self.live.fetch_sub(1, Ordering::SeqCst);
+        self.space.notify_all();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parking
+// ---------------------------------------------------------------------------
+
+/// A condvar that is only locked when a thread is parked on it.
+///
+/// Waiters count themselves in `sleepers` under the lock before rechecking
+/// their condition, and notifiers change the condition before reading
+/// `sleepers` — both `SeqCst` — so a notifier that sees no sleepers is
+/// guaranteed the waiter's recheck will see its change.
+#[derive(Default)]
+struct Parking {
+    lock: Mutex<()>,
+    cvar: Condvar,
+    sleepers: AtomicUsize,
+}
+
+impl Parking {
+    /// Park while `blocked()` holds.
+    fn wait_while(&self, blocked: impl Fn() -> bool) {
+        if !blocked() {
+            return;
+        }
+        let mut guard = lock(&self.lock);
+        self.sleepers.fetch_add(1, Ordering::SeqCst);
+        while blocked() {
+            guard = self.cvar.wait(guard).unwrap_or_else(|e| e.into_inner());
+        }
+        self.sleepers.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn notify_one(&self) {
+        if self.sleepers.load(Ordering::SeqCst) > 0 {
+            let _guard = lock(&self.lock);
+            self.cvar.notify_one();
+        }
+    }
+
+    fn notify_all(&self) {
+        if self.sleepers.load(Ordering::SeqCst) > 0 {
+            let _guard = lock(&self.lock);
+            self.cvar.notify_all();
+        }
+    }
+}
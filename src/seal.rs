@@ -0,0 +1,210 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+
+// ---------------------------------------------------------------------------
+// EncryptionKey
+// ---------------------------------------------------------------------------
+
+/// A 256-bit key for encrypting persisted files.
+///
+/// parex never stores or derives keys: generate one with a CSPRNG and
+/// keep it in the platform's secret store, or derive it from a passphrase
+/// with a KDF such as Argon2 before handing it over.
+#[derive(Clone)]
+pub struct EncryptionKey(LessSafeKey);
+
+impl EncryptionKey {
+    /// Key length in bytes.
+    pub const LEN: usize = 32;
+
+    /// A key from 32 raw bytes.
+    pub fn new(bytes: &[u8; Self::LEN]) -> Self {
+        // Cannot fail: the length is checked by the type
+        let key = UnboundKey::new(&CHACHA20_POLY1305, bytes).expect("32-byte key");
+        Self(LessSafeKey::new(key))
+    }
+}
+
+/// Never prints key material.
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Chunked authenticated encryption
+// ---------------------------------------------------------------------------
+//
+// The STREAM construction: the body is cut into chunks, each sealed with
+// ChaCha20-Poly1305 under the nonce `prefix ‖ counter ‖ last`. A random
+// 7-byte prefix per file keeps nonces unique across saves with one key;
+// the counter stops chunks being reordered, and the last-chunk flag stops
+// a file being truncated at a chunk boundary. Every chunk also
+// authenticates the file header as associated data.
+//
+// On disk: prefix, then per chunk a `last` byte, a u32 ciphertext length,
+// and the ciphertext with its tag.
+
+/// Plaintext bytes per chunk.
+const CHUNK: usize = 64 * 1024;
+
+const PREFIX_LEN: usize = NONCE_LEN - 5;
+
+fn nonce(prefix: &[u8; PREFIX_LEN], counter: u32, last: bool) -> Nonce {
+    let mut n = [0; NONCE_LEN];
+    n[..PREFIX_LEN].copy_from_slice(prefix);
+    n[PREFIX_LEN..NONCE_LEN - 1].copy_from_slice(&counter.to_be_bytes());
+    n[NONCE_LEN - 1] = u8::from(last);
+    Nonce::assume_unique_for_key(n)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Encrypts everything written to it. Call [`finish`](Self::finish).
+pub(crate) struct SealWriter<W: Write> {
+    inner: W,
+    key: LessSafeKey,
+    aad: Vec<u8>,
+    prefix: [u8; PREFIX_LEN],
+    counter: u32,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> SealWriter<W> {
+    /// Encrypt into `inner`, authenticating `aad` with every chunk.
+    pub(crate) fn new(mut inner: W, key: &EncryptionKey, aad: &[u8]) -> io::Result<Self> {
+        let mut prefix = [0; PREFIX_LEN];
+        SystemRandom::new()
+            .fill(&mut prefix)
+            .map_err(|_| io::Error::other("no system randomness for a nonce"))?;
+        inner.write_all(&prefix)?;
+        Ok(Self {
+            inner,
+            key: key.0.clone(),
+            aad: aad.to_vec(),
+            prefix,
+            counter: 0,
+            buf: Vec::with_capacity(CHUNK),
+        })
+    }
+
+    fn seal(&mut self, last: bool) -> io::Result<()> {
+        let nonce = nonce(&self.prefix, self.counter, last);
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("file too large to encrypt"))?;
+        self.key
+            .seal_in_place_append_tag(nonce, Aad::from(&self.aad), &mut self.buf)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        self.inner.write_all(&[u8::from(last)])?;
+        self.inner
+            .write_all(&(self.buf.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&self.buf)?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// Seal the final chunk and return the writer.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        self.seal(true)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for SealWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let n = data.len().min(CHUNK - self.buf.len());
+        self.buf.extend_from_slice(&data[..n]);
+        if self.buf.len() == CHUNK {
+            self.seal(false)?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decrypts and authenticates what a [`SealWriter`] wrote.
+///
+/// A wrong key, a tampered chunk, or a missing final chunk is an
+/// [`io::ErrorKind::InvalidData`] error.
+pub(crate) struct OpenReader<R: Read> {
+    inner: R,
+    key: LessSafeKey,
+    aad: Vec<u8>,
+    prefix: [u8; PREFIX_LEN],
+    counter: u32,
+    /// Decrypted bytes of the current chunk, and how many were read.
+    plain: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: Read> OpenReader<R> {
+    pub(crate) fn new(mut inner: R, key: &EncryptionKey, aad: &[u8]) -> io::Result<Self> {
+        let mut prefix = [0; PREFIX_LEN];
+        inner.read_exact(&mut prefix)?;
+        Ok(Self {
+            inner,
+            key: key.0.clone(),
+            aad: aad.to_vec(),
+            prefix,
+            counter: 0,
+            plain: Vec::new(),
+            pos: 0,
+            done: false,
+        })
+    }
+
+    fn open_next(&mut self) -> io::Result<()> {
+        let mut head = [0; 5];
+        self.inner.read_exact(&mut head)?;
+        let last = match head[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid("bad chunk marker")),
+        };
+        let len = u32::from_le_bytes([head[1], head[2], head[3], head[4]]) as usize;
+        if len > CHUNK + CHACHA20_POLY1305.tag_len() {
+            return Err(invalid("oversized chunk"));
+        }
+
+        self.plain.resize(len, 0);
+        self.inner.read_exact(&mut self.plain)?;
+        let nonce = nonce(&self.prefix, self.counter, last);
+        let plain_len = self
+            .key
+            .open_in_place(nonce, Aad::from(&self.aad), &mut self.plain)
+            .map_err(|_| invalid("authentication failed: wrong key or damaged file"))?
+            .len();
+        self.plain.truncate(plain_len);
+        self.pos = 0;
+        self.counter = self.counter.wrapping_add(1);
+        self.done = last;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for OpenReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.plain.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.open_next()?;
+        }
+        let n = out.len().min(self.plain.len() - self.pos);
+        out[..n].copy_from_slice(&self.plain[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
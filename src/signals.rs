@@ -0,0 +1,61 @@
+use std::sync::{Mutex, OnceLock};
+
+use crate::engine::CancelToken;
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// Process signal handling
+// ---------------------------------------------------------------------------
+
+/// Searches currently listening for SIGINT / SIGTERM.
+///
+/// The process-wide handler is installed once, on first use, and cancels
+/// every registered token. Searches register on start and deregister when
+/// their [`SignalGuard`] drops, so a signal only affects searches in flight.
+static ACTIVE: OnceLock<Result<Mutex<Vec<CancelToken>>, String>> = OnceLock::new();
+
+fn active() -> Result<&'static Mutex<Vec<CancelToken>>, ParexError> {
+    ACTIVE
+        .get_or_init(|| {
+            ctrlc::set_handler(|| {
+                if let Some(Ok(active)) = ACTIVE.get() {
+                    let active = active.lock().unwrap_or_else(|e| e.into_inner());
+                    for token in active.iter() {
+                        token.cancel();
+                    }
+                }
+            })
+            .map(|()| Mutex::new(Vec::new()))
+            .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| ParexError::Signal(e.clone()))
+}
+
+/// Keeps a search registered with the signal handler for as long as it lives.
+pub(crate) struct SignalGuard(CancelToken);
+
+impl SignalGuard {
+    /// Register `token` to be cancelled on SIGINT / SIGTERM.
+    ///
+    /// Installs the process-wide handler on first call. Fails if another
+    /// handler was already installed by the application.
+    pub(crate) fn register(token: &CancelToken) -> Result<Self, ParexError> {
+        active()?
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(token.clone());
+        Ok(Self(token.clone()))
+    }
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        if let Ok(active) = active() {
+            active
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|t| !t.same_as(&self.0));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path};
+
+use crate::context::MatchContext;
+use crate::entry::Entry;
+use crate::plan::Cost;
+use crate::traits::Matcher;
+
+// ---------------------------------------------------------------------------
+// Common bloat
+// ---------------------------------------------------------------------------
+
+/// Directory names that are almost never worth searching: version control
+/// metadata, dependency trees, build output, and tool caches. Used by
+/// [`SearchBuilder::skip_common_bloat`](crate::SearchBuilder::skip_common_bloat)
+/// and [`SkipDirs::common`].
+pub const COMMON_BLOAT: &[&str] = &[
+    // Version control
+    ".git",
+    ".hg",
+    ".svn",
+    // Dependencies
+    "node_modules",
+    "bower_components",
+    ".venv",
+    "venv",
+    ".tox",
+    ".eggs",
+    ".stack-work",
+    // Build output
+    "target",
+    ".gradle",
+    ".next",
+    ".nuxt",
+    ".dart_tool",
+    ".terraform",
+    // Caches
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".parcel-cache",
+    ".sass-cache",
+    ".cache",
+    ".direnv",
+];
+
+// ---------------------------------------------------------------------------
+// SkipDirs
+// ---------------------------------------------------------------------------
+
+/// A filter that drops every entry inside a directory with one of the
+/// given names, and prunes those directories so the source never reads them.
+///
+/// Names are matched exactly against each component of the path below the
+/// search root, so searching from inside `~/src/target/` still works; only
+/// directories the walk descends into are skipped. The named directory
+/// itself is dropped too.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::SkipDirs;
+///
+/// // The usual list, but keep `target/` and also skip `vendor/`
+/// let skip = SkipDirs::common().without("target").with("vendor");
+/// # let _ =
+/// parex::search().filter(skip);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SkipDirs {
+    names: HashSet<OsString>,
+}
+
+impl SkipDirs {
+    /// Skip directories named any of `names`.
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Skip the directories in [`COMMON_BLOAT`].
+    pub fn common() -> Self {
+        Self::new(COMMON_BLOAT.iter().copied())
+    }
+
+    /// Also skip directories named `name`.
+    pub fn with(mut self, name: impl Into<OsString>) -> Self {
+        self.names.insert(name.into());
+        self
+    }
+
+    /// Stop skipping directories named `name`.
+    pub fn without(mut self, name: impl AsRef<OsStr>) -> Self {
+        self.names.remove(name.as_ref());
+        self
+    }
+
+    fn skips(&self, name: &OsStr) -> bool {
+        self.names.contains(name)
+    }
+}
+
+impl Matcher for SkipDirs {
+    fn is_match(&self, entry: &Entry) -> bool {
+        self.is_match_ctx(entry, &MatchContext::new(entry))
+    }
+
+    fn is_match_ctx(&self, _entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        !ctx.relative_path().components().any(|c| match c {
+            Component::Normal(name) => self.skips(name),
+            _ => false,
+        })
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
+
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        dir.file_name().is_some_and(|name| self.skips(name))
+    }
+}
@@ -0,0 +1,769 @@
+//! Point-in-time records of what a search saw, and structured diffs between them.
+//!
+//! A [`Snapshot`] maps paths to the size, modification time, and optional
+//! content hash of each entry. Comparing two snapshots with
+//! [`Snapshot::diff`] yields a [`Diff`] of added, removed, and modified paths —
+//! the building block for change-auditing tools. [`Snapshot::save`] and
+//! [`Snapshot::load`] keep snapshots between runs in a compact checksummed
+//! file; with the `serde` feature, both types also serialize to any serde
+//! format.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::entry::{Entry, EntryId, EntryKind};
+use crate::error::ParexError;
+use crate::persist::{
+    BodyWriter, Decoder, Encoder, FLAG_SEALED, FLAG_ZSTD, Header, KNOWN_FLAGS, body_reader,
+};
+
+#[cfg(not(feature = "encryption"))]
+use crate::persist::EncryptionKey;
+#[cfg(feature = "encryption")]
+pub use crate::seal::EncryptionKey;
+
+// ---------------------------------------------------------------------------
+// Snapshot
+// ---------------------------------------------------------------------------
+
+/// What a search saw at one point in time, keyed by path.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::snapshot::{Record, Snapshot};
+/// use parex::EntryKind;
+///
+/// let mut older = Snapshot::new();
+/// older.insert("a.txt", Record::new(EntryKind::File).with_size(1));
+/// older.insert("b.txt", Record::new(EntryKind::File).with_size(2));
+///
+/// let mut newer = Snapshot::new();
+/// newer.insert("b.txt", Record::new(EntryKind::File).with_size(3));
+/// newer.insert("c.txt", Record::new(EntryKind::File).with_size(4));
+///
+/// let diff = newer.diff(&older);
+/// assert_eq!(diff.added, vec![std::path::PathBuf::from("c.txt")]);
+/// assert_eq!(diff.removed, vec![std::path::PathBuf::from("a.txt")]);
+/// assert_eq!(diff.modified, vec![std::path::PathBuf::from("b.txt")]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    records: BTreeMap<PathBuf, Record>,
+}
+
+/// The recorded state of a single entry.
+///
+/// Build records with [`Record::from_entry`], or [`Record::new`] and the
+/// `with_*` methods; the struct is `#[non_exhaustive]`, so new optional
+/// fields are not breaking changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Record {
+    /// What kind of entry this is.
+    pub kind: EntryKind,
+
+    /// Size in bytes, if known.
+    pub size: Option<u64>,
+
+    /// Last modification time, if known.
+    pub modified: Option<SystemTime>,
+
+    /// A caller-computed content hash, if any. parex never hashes content itself.
+    pub hash: Option<String>,
+
+    /// The entry's [stable id](Entry::stable_id), if known, so
+    /// [`Snapshot::diff`] can tell a rename from a removal and an addition.
+    pub id: Option<EntryId>,
+}
+
+impl Record {
+    /// A record of `kind` with nothing else known.
+    ///
+    /// ```rust
+    /// use parex::snapshot::Record;
+    /// use parex::EntryKind;
+    ///
+    /// let record = Record::new(EntryKind::File).with_size(42).with_hash("abc123");
+    /// assert_eq!(record.size, Some(42));
+    /// assert_eq!(record.modified, None);
+    /// ```
+    pub fn new(kind: EntryKind) -> Self {
+        Self {
+            kind,
+            size: None,
+            modified: None,
+            hash: None,
+            id: None,
+        }
+    }
+
+    /// Set the size in bytes.
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the last modification time.
+    pub fn with_modified(mut self, modified: SystemTime) -> Self {
+        self.modified = Some(modified);
+        self
+    }
+
+    /// Set the caller-computed content hash.
+    pub fn with_hash(mut self, hash: impl Into<String>) -> Self {
+        self.hash = Some(hash.into());
+        self
+    }
+
+    /// Set the entry's stable id.
+    pub fn with_id(mut self, id: EntryId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Record an entry, taking size and mtime from `entry.metadata` and the
+    /// id from [`Entry::stable_id`] when present.
+    pub fn from_entry(entry: &Entry) -> Self {
+        let meta = entry.metadata.as_ref();
+        Self {
+            kind: entry.kind.clone(),
+            size: meta.map(|m| m.len()),
+            modified: meta.and_then(|m| m.modified().ok()),
+            hash: None,
+            id: entry.stable_id(),
+        }
+    }
+
+    /// Whether `self` differs from `older` in any field both sides know.
+    ///
+    /// Fields missing on either side are not compared, so a snapshot taken
+    /// without metadata does not report every entry as modified.
+    fn differs_from(&self, older: &Record) -> bool {
+        fn known_and_differ<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+            matches!((a, b), (Some(a), Some(b)) if a != b)
+        }
+
+        self.kind != older.kind
+            || known_and_differ(&self.size, &older.size)
+            || known_and_differ(&self.modified, &older.modified)
+            || known_and_differ(&self.hash, &older.hash)
+            || known_and_differ(&self.id, &older.id)
+    }
+}
+
+impl Snapshot {
+    /// Create an empty snapshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a snapshot from entries, using [`Record::from_entry`] for each.
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> Self {
+        Self {
+            records: entries
+                .into_iter()
+                .map(|e| (e.path.clone(), Record::from_entry(e)))
+                .collect(),
+        }
+    }
+
+    /// Add or replace the record for `path`.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, record: Record) {
+        self.records.insert(path.into(), record);
+    }
+
+    /// The record for `path`, if present.
+    pub fn get(&self, path: &std::path::Path) -> Option<&Record> {
+        self.records.get(path)
+    }
+
+    /// Iterate records in path order.
+    pub fn iter(&self) -> impl Iterator<Item = (&PathBuf, &Record)> {
+        self.records.iter()
+    }
+
+    /// Number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the snapshot is empty.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Compare this snapshot against an `older` one.
+    ///
+    /// Paths only in `self` are added, paths only in `older` are removed, and
+    /// paths in both whose kind, size, mtime, hash, or id changed are
+    /// modified. A removed and an added path that are the same entry are
+    /// reported as renamed instead: first by equal [`Record::id`], then by
+    /// an equal hash and size that occur once on each side. Every list is
+    /// sorted by path — `renamed` by the old one.
+    pub fn diff(&self, older: &Snapshot) -> Diff {
+        let mut diff = Diff::default();
+
+        for (path, record) in &self.records {
+            match older.records.get(path) {
+                None => diff.added.push(path.clone()),
+                Some(old) if record.differs_from(old) => diff.modified.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+
+        diff.removed = older
+            .records
+            .keys()
+            .filter(|p| !self.records.contains_key(*p))
+            .cloned()
+            .collect();
+
+        diff.take_renames(self, older, |r| r.id.clone());
+        diff.take_renames(self, older, |r| r.hash.clone().map(|h| (h, r.size)));
+        diff.renamed.sort();
+
+        diff
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// First bytes of every snapshot file.
+const MAGIC: &[u8; 8] = b"PAREXSNP";
+
+/// The snapshot file format [`Snapshot::save`] writes. [`Snapshot::load`]
+/// also reads every earlier version.
+pub const FORMAT_VERSION: u32 = crate::persist::FORMAT_VERSION;
+
+/// Longest path or hash a snapshot file may hold.
+const MAX_FIELD: usize = 64 * 1024;
+
+impl Snapshot {
+    /// Write the snapshot to `path`, replacing any previous file.
+    ///
+    /// The write is atomic (see [`write_atomic`](crate::state::write_atomic)):
+    /// a crash leaves the previous snapshot intact. The file ends with a
+    /// checksum that [`load`](Self::load) verifies. On Windows, paths that
+    /// are not valid Unicode are stored lossily.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ParexError> {
+        self.write(path.as_ref(), None, None)
+    }
+
+    /// Like [`save`](Self::save), compressing the records with zstd at
+    /// `level` (1–22; 3 is zstd's default, 19 and up are slow to write).
+    ///
+    /// Paths in one tree share long prefixes, so snapshots compress
+    /// several-fold. Reading decompresses as a stream, record by record;
+    /// builds without the `zstd` feature refuse the file with
+    /// [`ParexError::UnsupportedFormat`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if the file cannot be written or `level`
+    /// is out of range.
+    #[cfg(feature = "zstd")]
+    pub fn save_compressed(&self, path: impl AsRef<Path>, level: i32) -> Result<(), ParexError> {
+        self.write(path.as_ref(), Some(level), None)
+    }
+
+    /// Like [`save`](Self::save), encrypting the records with `key`
+    /// (ChaCha20-Poly1305).
+    ///
+    /// Paths and sizes alone can reveal what a tree holds, so encrypt
+    /// snapshots of sensitive data at rest. Only the header — format
+    /// version and flags — stays readable; it is authenticated along with
+    /// the records, so any change to the file makes it fail to load.
+    /// Read the file back with [`load_encrypted`](Self::load_encrypted).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if the file cannot be written or the
+    /// system has no randomness to draw a nonce from.
+    #[cfg(feature = "encryption")]
+    pub fn save_encrypted(
+        &self,
+        path: impl AsRef<Path>,
+        key: &EncryptionKey,
+    ) -> Result<(), ParexError> {
+        self.write(path.as_ref(), None, Some(key))
+    }
+
+    /// Like [`save_encrypted`](Self::save_encrypted), compressing the
+    /// records with zstd at `level` before they are encrypted.
+    ///
+    /// # Errors
+    ///
+    /// As for [`save_encrypted`](Self::save_encrypted).
+    #[cfg(all(feature = "zstd", feature = "encryption"))]
+    pub fn save_encrypted_compressed(
+        &self,
+        path: impl AsRef<Path>,
+        key: &EncryptionKey,
+        level: i32,
+    ) -> Result<(), ParexError> {
+        self.write(path.as_ref(), Some(level), Some(key))
+    }
+
+    /// Read a snapshot written by [`save`](Self::save) in this or any
+    /// earlier format version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Corrupt`] if the file is truncated, fails its
+    /// checksum, or is not a snapshot,
+    /// [`ParexError::UnsupportedFormat`] if a newer parex wrote it, and
+    /// [`ParexError::Io`] if it cannot be read.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ParexError> {
+        Self::read(path.as_ref(), None).map(|(snapshot, _)| snapshot)
+    }
+
+    /// Read a snapshot written by [`save_encrypted`](Self::save_encrypted).
+    ///
+    /// # Errors
+    ///
+    /// As for [`load`](Self::load). A wrong key and a tampered file are
+    /// indistinguishable by design; both are [`ParexError::Corrupt`]. So
+    /// is an unencrypted file, which could otherwise be slipped in place of
+    /// an encrypted one.
+    #[cfg(feature = "encryption")]
+    pub fn load_encrypted(path: impl AsRef<Path>, key: &EncryptionKey) -> Result<Self, ParexError> {
+        Self::read(path.as_ref(), Some(key)).map(|(snapshot, _)| snapshot)
+    }
+
+    /// Stream the records of an encrypted snapshot file; see
+    /// [`open`](Self::open) and [`load_encrypted`](Self::load_encrypted).
+    ///
+    /// # Errors
+    ///
+    /// As for [`load_encrypted`](Self::load_encrypted), for problems found
+    /// in the header.
+    #[cfg(feature = "encryption")]
+    pub fn open_encrypted(
+        path: impl AsRef<Path>,
+        key: &EncryptionKey,
+    ) -> Result<SnapshotReader, ParexError> {
+        Self::open_with(path.as_ref(), Some(key))
+    }
+
+    /// Stream the records of a snapshot file in path order, without
+    /// building the whole snapshot in memory.
+    ///
+    /// The checksum covers the whole file, so it is verified after the
+    /// last record: a damaged file yields an `Err` as its final item, and
+    /// records before it should be treated as suspect.
+    ///
+    /// # Errors
+    ///
+    /// As for [`load`](Self::load), for problems found in the header.
+    pub fn open(path: impl AsRef<Path>) -> Result<SnapshotReader, ParexError> {
+        Self::open_with(path.as_ref(), None)
+    }
+
+    fn open_with(path: &Path, key: Option<&EncryptionKey>) -> Result<SnapshotReader, ParexError> {
+        let file = File::open(path).map_err(|source| ParexError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut d = Decoder::new(BufReader::new(file), path);
+        let header = d.header(MAGIC, KNOWN_FLAGS)?;
+        match (header.flags & FLAG_SEALED != 0, key.is_some()) {
+            (true, false) => return Err(ParexError::KeyRequired(path.to_path_buf())),
+            (false, true) => return Err(d.corrupt("expected an encrypted snapshot")),
+            _ => {}
+        }
+        let aad = header.aad(MAGIC);
+        let mut d = d.try_map(|r| body_reader(r, &header, key, &aad))?;
+
+        let remaining = d.u64()?;
+        Ok(SnapshotReader {
+            decoder: Some(d),
+            remaining,
+            version: header.version,
+            compressed: header.flags & FLAG_ZSTD != 0,
+            encrypted: header.flags & FLAG_SEALED != 0,
+        })
+    }
+
+    /// Rewrite the snapshot file at `path` in the current format version,
+    /// if it is older. Returns the version it was written in.
+    ///
+    /// [`load`](Self::load) reads older files as they are, so migrating is
+    /// never required — it lets a file be read by tools that only know the
+    /// current format, and pays the upgrade cost once instead of per load.
+    ///
+    /// # Errors
+    ///
+    /// As for [`load`](Self::load) and [`save`](Self::save). A file that
+    /// fails to load is left untouched.
+    ///
+    /// Encrypted files need their key; use
+    /// [`load_encrypted`](Self::load_encrypted) and
+    /// [`save_encrypted`](Self::save_encrypted) to migrate them.
+    pub fn migrate(path: impl AsRef<Path>) -> Result<u32, ParexError> {
+        let path = path.as_ref();
+        let reader = Self::open(path)?;
+        let (version, compressed) = (reader.version, reader.compressed);
+        let snapshot = reader.collect::<Result<Self, _>>()?;
+        if version < FORMAT_VERSION {
+            // Keep compressed files compressed, at zstd's default level
+            snapshot.write(path, compressed.then_some(3), None)?;
+        }
+        Ok(version)
+    }
+
+    /// Decode a snapshot file, returning its format version.
+    fn read(path: &Path, key: Option<&EncryptionKey>) -> Result<(Self, u32), ParexError> {
+        let reader = Self::open_with(path, key)?;
+        let version = reader.version;
+        Ok((reader.collect::<Result<Self, _>>()?, version))
+    }
+
+    /// Save atomically, zstd-compressed at `level` and encrypted with `key`
+    /// if given.
+    fn write(
+        &self,
+        path: &Path,
+        level: Option<i32>,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), ParexError> {
+        crate::state::write_atomic(path, |out| {
+            self.encode(out, level, key)
+                .map_err(|source| ParexError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+        })
+    }
+
+    fn encode(
+        &self,
+        out: &mut impl Write,
+        level: Option<i32>,
+        key: Option<&EncryptionKey>,
+    ) -> std::io::Result<()> {
+        let flags = BodyWriter::<&mut dyn Write>::flags(level, key);
+        let mut e = Encoder::new(out);
+        e.header(MAGIC, flags)?;
+        let aad = Header::current(flags).aad(MAGIC);
+        let mut e = e.try_map(|w| BodyWriter::new(w, level, key, &aad))?;
+        self.encode_records(&mut e)?;
+        e.finish()?.finish()?;
+        Ok(())
+    }
+
+    fn encode_records<W: Write>(&self, e: &mut Encoder<W>) -> std::io::Result<()> {
+        e.u64(self.records.len() as u64)?;
+        for (path, record) in &self.records {
+            e.bytes(&path_to_bytes(path))?;
+            encode_record(e, record)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<(PathBuf, Record)> for Snapshot {
+    fn from_iter<I: IntoIterator<Item = (PathBuf, Record)>>(iter: I) -> Self {
+        Self {
+            records: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Records streamed from a snapshot file. See [`Snapshot::open`].
+pub struct SnapshotReader {
+    /// `None` once finished or failed.
+    decoder: Option<Decoder<Box<dyn Read>>>,
+    remaining: u64,
+    version: u32,
+    compressed: bool,
+    encrypted: bool,
+}
+
+impl SnapshotReader {
+    /// The format version the file was written in.
+    pub fn format_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Whether the file is zstd-compressed.
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// Whether the file is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Records not yet read.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+impl Iterator for SnapshotReader {
+    type Item = Result<(PathBuf, Record), ParexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut d = self.decoder.take()?;
+        if self.remaining == 0 {
+            return d.finish().err().map(Err);
+        }
+        let item = decode_entry(&mut d, self.version);
+        if item.is_ok() {
+            self.remaining -= 1;
+            self.decoder = Some(d);
+        }
+        Some(item)
+    }
+}
+
+fn encode_record<W: Write>(e: &mut Encoder<W>, record: &Record) -> std::io::Result<()> {
+    e.u8(match record.kind {
+        EntryKind::File => 0,
+        EntryKind::Dir => 1,
+        EntryKind::Symlink => 2,
+        EntryKind::Other => 3,
+    })?;
+
+    match record.size {
+        Some(size) => {
+            e.u8(1)?;
+            e.u64(size)?;
+        }
+        None => e.u8(0)?,
+    }
+
+    // Times before the epoch are stored as a distance backwards
+    let since_epoch = record
+        .modified
+        .map(|t| match t.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => (1, d),
+            Err(before) => (2, before.duration()),
+        });
+    match since_epoch {
+        Some((sign, d)) => {
+            e.u8(sign)?;
+            e.u64(d.as_secs())?;
+            e.u32(d.subsec_nanos())?;
+        }
+        None => e.u8(0)?,
+    }
+
+    match &record.hash {
+        Some(hash) => {
+            e.u8(1)?;
+            e.bytes(hash.as_bytes())?;
+        }
+        None => e.u8(0)?,
+    }
+
+    match &record.id {
+        Some(EntryId::Inode { dev, ino }) => {
+            e.u8(1)?;
+            e.u64(*dev)?;
+            e.u64(*ino)?;
+        }
+        Some(EntryId::Key(key)) => {
+            e.u8(2)?;
+            e.bytes(key.as_bytes())?;
+        }
+        None => e.u8(0)?,
+    }
+    Ok(())
+}
+
+fn decode_entry<R: Read>(
+    d: &mut Decoder<R>,
+    version: u32,
+) -> Result<(PathBuf, Record), ParexError> {
+    let path = path_from_bytes(d.bytes(MAX_FIELD)?).ok_or_else(|| d.corrupt("bad path"))?;
+    Ok((path, decode_record(d, version)?))
+}
+
+/// A record as laid out in `version`: v1 and v2 records have no id.
+fn decode_record<R: std::io::Read>(d: &mut Decoder<R>, version: u32) -> Result<Record, ParexError> {
+    let kind = match d.u8()? {
+        0 => EntryKind::File,
+        1 => EntryKind::Dir,
+        2 => EntryKind::Symlink,
+        3 => EntryKind::Other,
+        k => return Err(d.corrupt(format!("unknown entry kind {k}"))),
+    };
+
+    let size = match d.u8()? {
+        0 => None,
+        1 => Some(d.u64()?),
+        f => return Err(d.corrupt(format!("bad size flag {f}"))),
+    };
+
+    let modified = match d.u8()? {
+        0 => None,
+        sign @ (1 | 2) => {
+            let secs = d.u64()?;
+            let nanos = d.u32()?;
+            if nanos >= 1_000_000_000 {
+                return Err(d.corrupt("bad timestamp"));
+            }
+            let offset = Duration::new(secs, nanos);
+            let time = if sign == 1 {
+                SystemTime::UNIX_EPOCH.checked_add(offset)
+            } else {
+                SystemTime::UNIX_EPOCH.checked_sub(offset)
+            };
+            Some(time.ok_or_else(|| d.corrupt("timestamp out of range"))?)
+        }
+        f => return Err(d.corrupt(format!("bad timestamp flag {f}"))),
+    };
+
+    let hash = match d.u8()? {
+        0 => None,
+        1 => Some(String::from_utf8(d.bytes(MAX_FIELD)?).map_err(|_| d.corrupt("bad hash"))?),
+        f => return Err(d.corrupt(format!("bad hash flag {f}"))),
+    };
+
+    let id = match version {
+        ..3 => None,
+        _ => match d.u8()? {
+            0 => None,
+            1 => Some(EntryId::Inode {
+                dev: d.u64()?,
+                ino: d.u64()?,
+            }),
+            2 => Some(EntryId::Key(
+                String::from_utf8(d.bytes(MAX_FIELD)?).map_err(|_| d.corrupt("bad id"))?,
+            )),
+            f => return Err(d.corrupt(format!("bad id flag {f}"))),
+        },
+    };
+
+    Ok(Record {
+        kind,
+        size,
+        modified,
+        hash,
+        id,
+    })
+}
+
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().into()
+}
+
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    match path.to_string_lossy() {
+        std::borrow::Cow::Borrowed(s) => s.as_bytes().into(),
+        std::borrow::Cow::Owned(s) => s.into_bytes().into(),
+    }
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStringExt;
+    Some(std::ffi::OsString::from_vec(bytes).into())
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    String::from_utf8(bytes).ok().map(Into::into)
+}
+
+// ---------------------------------------------------------------------------
+// Diff
+// ---------------------------------------------------------------------------
+
+/// The difference between two snapshots. See [`Snapshot::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diff {
+    /// Paths present only in the newer snapshot.
+    pub added: Vec<PathBuf>,
+
+    /// Paths present only in the older snapshot.
+    pub removed: Vec<PathBuf>,
+
+    /// Paths present in both whose recorded state changed.
+    pub modified: Vec<PathBuf>,
+
+    /// Entries that moved, as `(old path, new path)`. Neither path appears
+    /// in `added` or `removed`. The entry may also have changed; compare
+    /// its records to find out.
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Diff {
+    /// Whether the two snapshots were identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+    }
+
+    /// Move removed and added paths whose records share a `key` into
+    /// `renamed`. Keys held by more than one path on either side are
+    /// ambiguous — hard links, identical files — and left alone.
+    fn take_renames<K: Eq + Hash>(
+        &mut self,
+        newer: &Snapshot,
+        older: &Snapshot,
+        key: impl Fn(&Record) -> Option<K>,
+    ) {
+        fn unique<K: Eq + Hash>(
+            keys: impl Iterator<Item = Option<K>>,
+        ) -> HashMap<K, Option<usize>> {
+            let mut index = HashMap::new();
+            for (i, k) in keys.enumerate() {
+                if let Some(k) = k {
+                    index
+                        .entry(k)
+                        .and_modify(|at| *at = None)
+                        .or_insert(Some(i));
+                }
+            }
+            index
+        }
+
+        let added = unique(self.added.iter().map(|p| key(&newer.records[p])));
+        let mut moved: Vec<(usize, usize)> =
+            unique(self.removed.iter().map(|p| key(&older.records[p])))
+                .into_iter()
+                .filter_map(|(k, from)| Some((from?, (*added.get(&k)?)?)))
+                .collect();
+        if moved.is_empty() {
+            return;
+        }
+
+        moved.sort_unstable();
+        let (mut gone, mut arrived) = (
+            vec![false; self.removed.len()],
+            vec![false; self.added.len()],
+        );
+        for &(from, to) in &moved {
+            self.renamed
+                .push((self.removed[from].clone(), self.added[to].clone()));
+            gone[from] = true;
+            arrived[to] = true;
+        }
+        let mut gone = gone.into_iter();
+        self.removed.retain(|_| !gone.next().unwrap_or(false));
+        let mut arrived = arrived.into_iter();
+        self.added.retain(|_| !arrived.next().unwrap_or(false));
+    }
+}
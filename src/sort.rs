@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::{Component, Path};
+#[cfg(feature = "collation")]
+use std::sync::Arc;
+
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// SortOrder
+// ---------------------------------------------------------------------------
+
+/// How to order matched paths. See [`Results::sort_paths`](crate::Results::sort_paths)
+/// and [`SearchBuilder::sort`](crate::SearchBuilder::sort).
+///
+/// Paths are compared component by component, so every entry of a directory
+/// sorts together and `a/b` comes before `a.txt` — the order a file browser
+/// shows. Only the comparison of individual names differs between orders.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum SortOrder {
+    /// Byte-wise, as `Path::cmp` does: `file10` before `file2`, and every
+    /// uppercase letter before any lowercase one.
+    #[default]
+    Lexical,
+
+    /// Runs of digits compare by numeric value (`file2` before `file10`);
+    /// everything else compares case-insensitively, falling back to the
+    /// exact text to break ties.
+    Natural,
+
+    /// Locale-aware collation from ICU — `ä` next to `a` in German, after
+    /// `z` in Swedish. Build with [`SortOrder::locale`].
+    #[cfg(feature = "collation")]
+    Locale(Collation),
+}
+
+impl SortOrder {
+    /// Collation for the BCP 47 language tag `tag`, e.g. `"de"` or `"sv-SE"`,
+    /// with digits compared numerically as in [`Natural`](Self::Natural).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::InvalidPattern`] if `tag` is not a valid
+    /// language tag or has no collation data.
+    #[cfg(feature = "collation")]
+    pub fn locale(tag: &str) -> Result<Self, ParexError> {
+        use icu_collator::{Collator, CollatorOptions, Numeric};
+
+        let invalid =
+            |e: &dyn fmt::Display| ParexError::InvalidPattern(format!("locale {tag:?}: {e}"));
+        let locale: icu_locid::Locale = tag.parse().map_err(|e| invalid(&e))?;
+        let mut options = CollatorOptions::new();
+        options.numeric = Some(Numeric::On);
+        let collator = Collator::try_new(&(&locale).into(), options).map_err(|e| invalid(&e))?;
+        Ok(Self::Locale(Collation {
+            tag: tag.to_owned(),
+            collator: Arc::new(collator),
+        }))
+    }
+
+    /// The order's name as saved in a [`SearchConfig`](crate::SearchConfig):
+    /// `"lexical"`, `"natural"`, or a locale order's language tag.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Lexical => "lexical",
+            Self::Natural => "natural",
+            #[cfg(feature = "collation")]
+            Self::Locale(c) => c.tag(),
+        }
+    }
+
+    /// The order a [`name`](Self::name) stands for.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::InvalidPattern`] if `name` is neither
+    /// `"lexical"` nor `"natural"` and is not a language tag
+    /// [`SortOrder::locale`] accepts — always the case without the
+    /// `collation` feature.
+    pub fn from_name(name: &str) -> Result<Self, ParexError> {
+        match name {
+            "lexical" => Ok(Self::Lexical),
+            "natural" => Ok(Self::Natural),
+            #[cfg(feature = "collation")]
+            tag => Self::locale(tag),
+            #[cfg(not(feature = "collation"))]
+            other => Err(ParexError::InvalidPattern(format!(
+                "unknown sort order {other:?}; locale orders need the `collation` feature"
+            ))),
+        }
+    }
+
+    /// Compare two paths in this order.
+    pub fn compare(&self, a: &Path, b: &Path) -> Ordering {
+        if let Self::Lexical = self {
+            return a.cmp(b);
+        }
+
+        let mut a = a.components();
+        let mut b = b.components();
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) => {
+                    let ord = match (x, y) {
+                        (Component::Normal(x), Component::Normal(y)) => {
+                            self.compare_names(&x.to_string_lossy(), &y.to_string_lossy())
+                        }
+                        (x, y) => x.cmp(&y),
+                    };
+                    if ord != Ordering::Equal {
+                        return ord;
+                    }
+                }
+            }
+        }
+    }
+
+    fn compare_names(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Lexical => a.cmp(b),
+            Self::Natural => natural_cmp(a, b),
+            #[cfg(feature = "collation")]
+            Self::Locale(c) => c.collator.compare(a, b).then_with(|| a.cmp(b)),
+        }
+    }
+}
+
+impl fmt::Debug for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lexical => f.write_str("Lexical"),
+            Self::Natural => f.write_str("Natural"),
+            #[cfg(feature = "collation")]
+            Self::Locale(c) => f.debug_tuple("Locale").field(&c.tag).finish(),
+        }
+    }
+}
+
+/// An ICU collator for one locale, shared cheaply between clones.
+#[cfg(feature = "collation")]
+#[derive(Clone)]
+pub struct Collation {
+    tag: String,
+    collator: Arc<icu_collator::Collator>,
+}
+
+#[cfg(feature = "collation")]
+impl Collation {
+    /// The language tag the collation was built for.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Natural comparison
+// ---------------------------------------------------------------------------
+
+/// Compare `a` and `b` with digit runs taken as numbers.
+///
+/// Numbers of any length are compared without parsing, so names with
+/// 30-digit timestamps cannot overflow. `file01` and `file1` compare equal
+/// numerically and are then ordered by the exact text.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let (cx, cy) = match (x.peek(), y.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(&cx), Some(&cy)) => (cx, cy),
+        };
+
+        let ord = if cx.is_ascii_digit() && cy.is_ascii_digit() {
+            let nx = digits(&mut x);
+            let ny = digits(&mut y);
+            let (tx, ty) = (nx.trim_start_matches('0'), ny.trim_start_matches('0'));
+            tx.len().cmp(&ty.len()).then_with(|| tx.cmp(ty))
+        } else {
+            x.next();
+            y.next();
+            cx.to_lowercase().cmp(cy.to_lowercase())
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+fn digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut run = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        run.push(c);
+    }
+    run
+}
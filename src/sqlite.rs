@@ -0,0 +1,132 @@
+//! SQLite integration: search rows as entries, and persist results to a table.
+//!
+//! Enabled with the `sqlite` feature.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::{Connection, Row};
+
+use crate::engine::WalkConfig;
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::results::Results;
+use crate::traits::Source;
+
+type RowMapper = dyn Fn(&Row<'_>) -> rusqlite::Result<Entry> + Send + Sync;
+
+// ---------------------------------------------------------------------------
+// SqliteSource
+// ---------------------------------------------------------------------------
+
+/// A [`Source`] that runs a query and maps each row to an [`Entry`].
+///
+/// The mapping is supplied by the caller, so any schema works — an
+/// inventory table, a previous scan, a file catalogue. The query runs once
+/// per search. A failed query or a row that fails to map is yielded as a
+/// [`ParexError::Source`], which is fatal and halts the walk.
+///
+/// # Example
+///
+/// ```rust
+/// use parex::sqlite::SqliteSource;
+/// use parex::{Entry, EntryKind};
+/// use rusqlite::Connection;
+///
+/// let conn = Connection::open_in_memory().unwrap();
+/// conn.execute_batch("CREATE TABLE files (path TEXT); INSERT INTO files VALUES ('invoice.txt'), ('notes.md');")
+///     .unwrap();
+///
+/// let source = SqliteSource::new(conn, "SELECT path FROM files", |row| {
+///     Ok(Entry::new(row.get::<_, String>(0)?, EntryKind::File, 0))
+/// });
+///
+/// let results = parex::search().source(source).matching("invoice").run().unwrap();
+/// assert_eq!(results.matches, 1);
+/// ```
+pub struct SqliteSource {
+    conn: Mutex<Connection>,
+    query: String,
+    map: Box<RowMapper>,
+}
+
+impl SqliteSource {
+    /// Search the rows returned by `query` on `conn`, mapping each with `map`.
+    pub fn new(
+        conn: Connection,
+        query: impl Into<String>,
+        map: impl Fn(&Row<'_>) -> rusqlite::Result<Entry> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            conn: Mutex::new(conn),
+            query: query.into(),
+            map: Box::new(map),
+        }
+    }
+
+    /// Open the database at `path` and search the rows returned by `query`.
+    pub fn open(
+        path: impl AsRef<Path>,
+        query: impl Into<String>,
+        map: impl Fn(&Row<'_>) -> rusqlite::Result<Entry> + Send + Sync + 'static,
+    ) -> Result<Self, ParexError> {
+        let conn = Connection::open(path.as_ref()).map_err(ParexError::source_err)?;
+        Ok(Self::new(conn, query, map))
+    }
+
+    fn rows(&self) -> Result<Vec<Result<Entry, ParexError>>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(&self.query)?;
+        let rows = stmt
+            .query_map([], |row| Ok((self.map)(row)))?
+            .map(|row| row.and_then(|r| r).map_err(ParexError::source_err))
+            .collect();
+        Ok(rows)
+    }
+}
+
+impl Source for SqliteSource {
+    fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
+        match self.rows() {
+            Ok(rows) => Box::new(rows.into_iter()),
+            Err(e) => Box::new(std::iter::once(Err(ParexError::source_err(e)))),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Results store
+// ---------------------------------------------------------------------------
+
+impl Results {
+    /// Append the matched paths to `table`, creating it if needed.
+    ///
+    /// The table has a single `path TEXT NOT NULL` column. Rows are written in
+    /// one transaction. Only [`paths`](Results::paths) are stored, so enable
+    /// `.collect_paths(true)` on the builder first.
+    pub fn write_sqlite(&self, conn: &mut Connection, table: &str) -> Result<(), ParexError> {
+        let table = quote_identifier(table);
+        let tx = conn.transaction().map_err(ParexError::source_err)?;
+        tx.execute(
+            &format!("CREATE TABLE IF NOT EXISTS {table} (path TEXT NOT NULL)"),
+            [],
+        )
+        .map_err(ParexError::source_err)?;
+        {
+            let mut insert = tx
+                .prepare(&format!("INSERT INTO {table} (path) VALUES (?1)"))
+                .map_err(ParexError::source_err)?;
+            for path in &self.paths {
+                insert
+                    .execute([path.to_string_lossy()])
+                    .map_err(ParexError::source_err)?;
+            }
+        }
+        tx.commit().map_err(ParexError::source_err)
+    }
+}
+
+/// Quote a table name so arbitrary caller input cannot inject SQL.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+use std::fs::Metadata;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::engine::MetadataMode;
+use crate::entry::Entry;
+use crate::error::ParexError;
+
+// ---------------------------------------------------------------------------
+// Metadata prefetch adapter
+// ---------------------------------------------------------------------------
+
+type Item = Result<Entry, ParexError>;
+
+/// Wraps a source iterator, `stat()`ing upcoming entries on IO threads.
+///
+/// Keeps a window of `distance` entries buffered. Every entry without
+/// cached metadata is queued for a `stat()` as it enters the window, and
+/// the result is stored in [`Entry::metadata`] before the entry is yielded
+/// — so a metadata matcher finds it already there instead of blocking on
+/// one `stat()` after another. Entries come out in source order.
+///
+/// Requests go to the IO threads in batches of up to `batch` consecutive
+/// entries of the same directory, so one thread walks a directory's inodes
+/// together — they tend to sit close on disk and share cached metadata
+/// blocks — and channel traffic is per batch rather than per entry.
+///
+/// A failed `stat()` leaves `metadata` empty; the matcher's own lookup
+/// then fails the same way it would have without the adapter.
+pub(crate) struct StatAheadIter<'a> {
+    inner: Box<dyn Iterator<Item = Item> + 'a>,
+    distance: usize,
+    /// Buffered items and whether each still awaits its `stat()`.
+    buffer: VecDeque<(Item, bool)>,
+    /// Sequence number of the front of `buffer`.
+    front: u64,
+    batch: usize,
+    /// Requests not yet sent, all from one directory.
+    unsent: Vec<(u64, PathBuf)>,
+    requests: Option<Sender<Vec<(u64, PathBuf)>>>,
+    replies: Receiver<Vec<(u64, Option<Metadata>)>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<'a> StatAheadIter<'a> {
+    pub(crate) fn new(
+        inner: Box<dyn Iterator<Item = Item> + 'a>,
+        distance: usize,
+        threads: usize,
+        batch: usize,
+        mode: MetadataMode,
+    ) -> Self {
+        let (requests, queue) = channel::<Vec<(u64, PathBuf)>>();
+        let (reply, replies) = channel();
+        let queue = Arc::new(Mutex::new(queue));
+
+        let workers = (0..threads.max(1))
+            .filter_map(|i| {
+                let queue = Arc::clone(&queue);
+                let reply = reply.clone();
+                std::thread::Builder::new()
+                    .name(format!("parex-stat-{i}"))
+                    .spawn(move || {
+                        loop {
+                            let next = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                            let Ok(batch) = next else {
+                                break;
+                            };
+                            let stats = batch
+                                .into_iter()
+                                .map(|(seq, path)| (seq, mode.metadata(&path).ok()))
+                                .collect();
+                            if reply.send(stats).is_err() {
+                                break;
+                            }
+                        }
+                    })
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            inner,
+            distance,
+            buffer: VecDeque::with_capacity(distance + 1),
+            front: 0,
+            batch: batch.max(1),
+            unsent: Vec::new(),
+            // With no thread to serve them, stat nothing ahead
+            requests: (!workers.is_empty()).then_some(requests),
+            replies,
+            workers,
+        }
+    }
+
+    /// Queue a `stat()` of `path`. `false` if no IO thread will serve it.
+    fn request(&mut self, seq: u64, path: PathBuf) -> bool {
+        if self.requests.is_none() {
+            return false;
+        }
+        if self
+            .unsent
+            .last()
+            .is_some_and(|(_, last)| last.parent() != path.parent())
+        {
+            self.flush();
+        }
+        self.unsent.push((seq, path));
+        if self.unsent.len() >= self.batch {
+            self.flush();
+        }
+        true
+    }
+
+    /// Send the unsent batch, if any.
+    fn flush(&mut self) {
+        if self.unsent.is_empty() {
+            return;
+        }
+        let batch = std::mem::take(&mut self.unsent);
+        if let Some(requests) = &self.requests
+            && requests.send(batch).is_err()
+        {
+            // Every worker is gone; nothing sent will be answered
+            self.requests = None;
+        }
+    }
+
+    /// Store a reply in its buffered entry.
+    fn fill(&mut self, seq: u64, metadata: Option<Metadata>) {
+        let Some((item, pending)) = self.buffer.get_mut((seq - self.front) as usize) else {
+            return;
+        };
+        *pending = false;
+        if let Ok(entry) = item {
+            entry.metadata = metadata;
+        }
+    }
+}
+
+impl Iterator for StatAheadIter<'_> {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() <= self.distance {
+            let Some(item) = self.inner.next() else {
+                break;
+            };
+            let seq = self.front + self.buffer.len() as u64;
+            let pending = match &item {
+                Ok(entry) if entry.metadata.is_none() => self.request(seq, entry.path.clone()),
+                _ => false,
+            };
+            self.buffer.push_back((item, pending));
+        }
+
+        // Wait for the front entry's stat, filing any others that arrive first
+        if self.buffer.front()?.1
+            && self
+                .unsent
+                .first()
+                .is_some_and(|(seq, _)| *seq == self.front)
+        {
+            self.flush();
+        }
+        while self.buffer.front()?.1 {
+            match self.replies.recv() {
+                Ok(stats) => stats
+                    .into_iter()
+                    .for_each(|(seq, metadata)| self.fill(seq, metadata)),
+                // Every worker is gone; yield what we have
+                Err(_) => self.buffer.iter_mut().for_each(|(_, p)| *p = false),
+            }
+        }
+
+        self.front += 1;
+        self.buffer.pop_front().map(|(item, _)| item)
+    }
+}
+
+impl Drop for StatAheadIter<'_> {
+    fn drop(&mut self) {
+        // Closing the queue lets the workers exit once their current stat returns
+        self.requests = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
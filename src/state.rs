@@ -0,0 +1,247 @@
+//! Where parex keeps data between runs.
+//!
+//! A [`StateDir`] resolves a per-user cache location for the current OS,
+//! keeps everything under a versioned layout directory, and cleans up what
+//! older layouts or long-idle runs left behind — so embedders storing
+//! [snapshots](crate::snapshot) or caches agree on one place for them
+//! instead of each picking their own.
+//!
+//! [`write_atomic`] is how parex writes every persistent file: a crash
+//! mid-write leaves the previous version in place, never half of the new.
+//!
+//! | OS | Base directory |
+//! |----|----------------|
+//! | Linux and other Unix | `$XDG_CACHE_HOME`, or `~/.cache` |
+//! | macOS | `~/Library/Caches` |
+//! | Windows | `%LOCALAPPDATA%` |
+
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::error::ParexError;
+
+/// Version of the layout inside a state directory. Bumped when files move
+/// or change meaning in ways older releases cannot read.
+pub const LAYOUT_VERSION: u32 = 1;
+
+/// Temp files this old were left by a writer that crashed.
+const TEMP_GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Numbers the temp files of one process, so concurrent [`write_atomic`]
+/// calls never share one.
+static TEMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+// ---------------------------------------------------------------------------
+// StateDir
+// ---------------------------------------------------------------------------
+
+/// A versioned directory for one application's persistent parex data.
+///
+/// Files live in `<base>/v<LAYOUT_VERSION>/`. Directories of older layout
+/// versions under the same base are stale: no release reads them once the
+/// layout moves on, and [`clean_stale`](Self::clean_stale) removes them.
+/// Newer versions belong to a newer release sharing the base and are kept.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use parex::state::StateDir;
+///
+/// let dir = StateDir::for_app("ldx").expect("no cache directory");
+/// dir.create()?;
+/// let snapshot_file = dir.file("home.snapshot");
+/// # Ok::<(), parex::ParexError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDir {
+    base: PathBuf,
+    current: PathBuf,
+}
+
+impl StateDir {
+    /// `<os cache dir>/<app>/parex`, or `None` if the OS cache directory
+    /// cannot be determined (no `HOME`, for instance).
+    pub fn for_app(app: &str) -> Option<Self> {
+        Some(Self::at(cache_dir()?.join(app).join("parex")))
+    }
+
+    /// Keep state under `base` instead of the OS cache directory.
+    pub fn at(base: impl Into<PathBuf>) -> Self {
+        let base = base.into();
+        let current = base.join(format!("v{LAYOUT_VERSION}"));
+        Self { base, current }
+    }
+
+    /// The directory of the current layout version. May not exist yet.
+    pub fn path(&self) -> &Path {
+        &self.current
+    }
+
+    /// The path of the state file `name`.
+    pub fn file(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.current.join(name)
+    }
+
+    /// Create the directory of the current layout version if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] if the directory cannot be created.
+    pub fn create(&self) -> Result<(), ParexError> {
+        std::fs::create_dir_all(&self.current).map_err(|e| io_err(&self.current, e))
+    }
+
+    /// Remove stale state: directories of older layout versions, files of
+    /// the current layout not modified for `max_age`, and temp files that
+    /// [`write_atomic`] calls interrupted by a crash left behind. Returns
+    /// how many files and directories were removed.
+    ///
+    /// Pass [`Duration::MAX`] to keep every current file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParexError::Io`] for the first entry that could not be
+    /// listed or removed. A base directory that does not exist yet is not
+    /// an error.
+    pub fn clean_stale(&self, max_age: Duration) -> Result<usize, ParexError> {
+        let mut removed = 0;
+        for entry in read_dir(&self.base)? {
+            let path = entry.path();
+            if layout_version(entry.file_name().as_os_str()).is_some_and(|v| v < LAYOUT_VERSION) {
+                std::fs::remove_dir_all(&path).map_err(|e| io_err(&path, e))?;
+                removed += 1;
+            }
+        }
+
+        let now = SystemTime::now();
+        for entry in read_dir(&self.current)? {
+            let path = entry.path();
+            let meta = entry.metadata().map_err(|e| io_err(&path, e))?;
+            let idle = meta
+                .modified()
+                .ok()
+                .and_then(|m| now.duration_since(m).ok())
+                .unwrap_or_default();
+            let limit = if is_temp(&path) {
+                max_age.min(TEMP_GRACE)
+            } else {
+                max_age
+            };
+            if meta.is_file() && idle > limit {
+                std::fs::remove_file(&path).map_err(|e| io_err(&path, e))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Atomic writes
+// ---------------------------------------------------------------------------
+
+/// Write `path` so that readers, and a crash at any point, see either the
+/// old contents or the complete new ones.
+///
+/// `write` fills a temp file next to `path`; the file is flushed to disk
+/// and then renamed over `path`, and on Unix the directory is synced so
+/// the rename itself survives power loss. If `write` fails, the temp file
+/// is removed and `path` is untouched. Each call writes its own temp file,
+/// so concurrent writers of the same `path` — threads or processes — never
+/// interleave; the last rename wins. A temp file orphaned by a crash is
+/// named `.<file>.<pid>.<n>.tmp` and removed by [`StateDir::clean_stale`].
+///
+/// # Errors
+///
+/// Returns [`ParexError::Io`] if the file cannot be written or renamed,
+/// or whatever `write` returns.
+pub fn write_atomic(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), ParexError>,
+) -> Result<(), ParexError> {
+    let dir = match path.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d,
+        _ => Path::new("."),
+    };
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or(path.as_os_str()));
+    let seq = TEMP_SEQ.fetch_add(1, Ordering::Relaxed);
+    name.push(format!(".{}.{seq}.tmp", std::process::id()));
+    let temp = dir.join(name);
+
+    let result = (|| {
+        let file = File::create(&temp).map_err(|e| io_err(&temp, e))?;
+        let mut out = BufWriter::new(file);
+        write(&mut out)?;
+        let file = out
+            .into_inner()
+            .map_err(|e| io_err(&temp, e.into_error()))?;
+        file.sync_all().map_err(|e| io_err(&temp, e))?;
+        std::fs::rename(&temp, path).map_err(|e| io_err(path, e))
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+        return result;
+    }
+
+    // Persist the rename; other platforms have no portable way to do this
+    #[cfg(unix)]
+    File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| io_err(dir, e))?;
+    Ok(())
+}
+
+/// A temp file of [`write_atomic`].
+fn is_temp(path: &Path) -> bool {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .is_some_and(|n| n.starts_with('.') && n.ends_with(".tmp"))
+}
+
+/// The per-user cache directory of the current OS.
+pub fn cache_dir() -> Option<PathBuf> {
+    let var = |name| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+    };
+
+    if cfg!(windows) {
+        var("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Caches"))
+    } else {
+        var("XDG_CACHE_HOME").or_else(|| var("HOME").map(|home| home.join(".cache")))
+    }
+}
+
+/// The version of a layout directory, named `v` followed by digits.
+fn layout_version(name: &OsStr) -> Option<u32> {
+    name.to_str()
+        .and_then(|n| n.strip_prefix('v'))
+        .filter(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|v| v.parse().ok())
+}
+
+/// The entries of `dir`; none if it does not exist.
+fn read_dir(dir: &Path) -> Result<Vec<std::fs::DirEntry>, ParexError> {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .collect::<Result<_, _>>()
+            .map_err(|e| io_err(dir, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(io_err(dir, e)),
+    }
+}
+
+fn io_err(path: &Path, source: std::io::Error) -> ParexError {
+    ParexError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
@@ -0,0 +1,134 @@
+//! Storage-type detection and per-device tuning.
+//!
+//! Enabled with the `storage` feature. The best thread count depends on what
+//! sits under the root: an SSD rewards wide parallelism, a spinning disk
+//! thrashes on seeks, and a network mount is latency-bound. [`detect`] makes a
+//! best-effort guess and [`StorageKind::profile`] turns it into defaults that
+//! [`SearchBuilder::tune_for`](crate::SearchBuilder::tune_for) applies.
+
+use std::path::Path;
+
+// ---------------------------------------------------------------------------
+// StorageKind
+// ---------------------------------------------------------------------------
+
+/// The kind of device a path lives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StorageKind {
+    /// Solid-state or otherwise non-rotational local storage.
+    Ssd,
+
+    /// A rotational disk.
+    Hdd,
+
+    /// NFS, SMB, or another network filesystem.
+    Network,
+
+    /// Detection failed or is not supported on this platform.
+    Unknown,
+}
+
+/// Tuning defaults for one kind of storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// Worker threads.
+    pub threads: usize,
+
+    /// In-flight directory reads, or `None` for no limit.
+    pub max_dir_reads: Option<usize>,
+}
+
+impl StorageKind {
+    /// Defaults for this kind of storage on a machine with `cpus` logical cores.
+    pub fn profile(self, cpus: usize) -> Profile {
+        let cpus = cpus.max(1);
+        match self {
+            // Random reads are cheap — use every core
+            Self::Ssd | Self::Unknown => Profile {
+                threads: cpus,
+                max_dir_reads: None,
+            },
+            // Concurrent reads turn into head seeks; keep them sequential
+            Self::Hdd => Profile {
+                threads: cpus.min(4),
+                max_dir_reads: Some(1),
+            },
+            // Latency-bound: more threads hide round trips, but the export
+            // itself degrades under hundreds of concurrent readdirs
+            Self::Network => Profile {
+                threads: cpus * 2,
+                max_dir_reads: Some(8),
+            },
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Detection
+// ---------------------------------------------------------------------------
+
+/// Guess the kind of storage `path` lives on.
+///
+/// On Linux this reads `/proc/self/mountinfo` to find the mount holding
+/// `path`, reports network filesystems by type, and checks the block
+/// device's `queue/rotational` flag in sysfs. Elsewhere, and whenever any
+/// step fails, it returns [`StorageKind::Unknown`].
+pub fn detect(path: impl AsRef<Path>) -> StorageKind {
+    imp::detect(path.as_ref()).unwrap_or(StorageKind::Unknown)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::path::Path;
+
+    use super::StorageKind;
+
+    const NETWORK_FS: &[&str] = &[
+        "nfs",
+        "nfs4",
+        "cifs",
+        "smb3",
+        "smbfs",
+        "ceph",
+        "glusterfs",
+        "fuse.sshfs",
+        "9p",
+        "afs",
+        "lustre",
+    ];
+
+    pub(super) fn detect(path: &Path) -> Option<StorageKind> {
+        let path = path.canonicalize().ok()?;
+        let mount = crate::mounts::all()
+            .into_iter()
+            .filter(|m| path.starts_with(&m.point))
+            .max_by_key(|m| m.point.as_os_str().len())?;
+
+        if NETWORK_FS.contains(&mount.fs_type.as_str()) {
+            return Some(StorageKind::Network);
+        }
+
+        // Partitions have no queue/ of their own — fall back to the parent disk
+        let dev = Path::new("/sys/dev/block").join(&mount.device);
+        let rotational = std::fs::read_to_string(dev.join("queue/rotational"))
+            .or_else(|_| std::fs::read_to_string(dev.join("../queue/rotational")))
+            .ok()?;
+        match rotational.trim() {
+            "1" => Some(StorageKind::Hdd),
+            "0" => Some(StorageKind::Ssd),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use std::path::Path;
+
+    use super::StorageKind;
+
+    pub(super) fn detect(_path: &Path) -> Option<StorageKind> {
+        None
+    }
+}
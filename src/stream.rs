@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use futures_core::Stream;
+
+use crate::engine::{CancelToken, EngineOptions, execute};
+use crate::entry::Entry;
+use crate::error::ParexError;
+use crate::results::Results;
+
+// ---------------------------------------------------------------------------
+// MatchStream
+// ---------------------------------------------------------------------------
+
+/// How often a worker blocked on a full queue rechecks for cancellation.
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// An async [`Stream`] of matched entries, produced by a background search.
+///
+/// Created via [`SearchBuilder::match_stream()`](crate::SearchBuilder::match_stream).
+/// The walk runs on a dedicated worker thread; each match is queued and the
+/// task polling the stream is woken, so results plug straight into async
+/// combinators such as `StreamExt::for_each_concurrent`.
+///
+/// The stream ends once the walk finishes or the match limit is reached.
+/// Results are unordered, as with [`run()`](crate::SearchBuilder::run).
+///
+/// With [`EngineTuning::stream_capacity`](crate::engine::EngineTuning::stream_capacity)
+/// set, the worker pauses once that many matches are queued and resumes as
+/// the stream is polled, so a slow consumer applies backpressure to the walk.
+///
+/// Dropping the stream before it ends cancels the search: the worker observes the shared
+/// [`CancelToken`] before pulling its next entry and exits, so an abandoned
+/// stream never leaves a detached walk running in the background.
+pub struct MatchStream {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<()>>,
+    cancel: CancelToken,
+}
+
+/// State shared between the worker thread and the polling task.
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled when the consumer makes room or the search is cancelled.
+    space: Condvar,
+    capacity: Option<usize>,
+    cancel: CancelToken,
+}
+
+struct State {
+    queue: VecDeque<Entry>,
+    done: bool,
+    waker: Option<Waker>,
+    /// The search's results, once the walk has ended.
+    results: Option<Results>,
+}
+
+impl Shared {
+    /// Queue a match and wake the polling task, if one is parked.
+    ///
+    /// Blocks while the queue is full, unless the search is cancelled.
+    fn push(&self, entry: Entry) {
+        let waker = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(cap) = self.capacity {
+                // Timed, so a token cancelled from outside the stream is noticed too
+                while state.queue.len() >= cap && !self.cancel.is_cancelled() {
+                    state = self
+                        .space
+                        .wait_timeout(state, CANCEL_POLL)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0;
+                }
+            }
+            state.queue.push_back(entry);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Mark the walk as finished and wake the polling task.
+    fn finish(&self, results: Option<Results>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.done = true;
+            state.results = results;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl MatchStream {
+    /// Spawn the worker thread and start walking.
+    pub(crate) fn spawn(opts: EngineOptions) -> Result<Self, ParexError> {
+        let cancel = opts.config.cancel.clone();
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                done: false,
+                waker: None,
+                results: None,
+            }),
+            space: Condvar::new(),
+            // A zero capacity would never admit a match
+            capacity: opts.tuning.stream_capacity.map(|c| c.max(1)),
+            cancel: cancel.clone(),
+        });
+
+        let worker_shared = Arc::clone(&shared);
+        let worker = std::thread::Builder::new()
+            .name("parex-stream".into())
+            .spawn(move || {
+                // Marks the stream finished even if the source or matcher panics,
+                // so the polling task is never left waiting forever.
+                struct FinishOnDrop(Arc<Shared>, Option<Results>);
+                impl Drop for FinishOnDrop {
+                    fn drop(&mut self) {
+                        self.0.finish(self.1.take());
+                    }
+                }
+
+                let mut guard = FinishOnDrop(worker_shared, None);
+                guard.1 = Some(execute(opts, |entry, _| guard.0.push(entry)));
+            })
+            .map_err(|e| ParexError::ThreadPool(e.to_string()))?;
+
+        Ok(Self {
+            shared,
+            worker: Some(worker),
+            cancel,
+        })
+    }
+
+    /// The search's [`Results`] — counts, stats, errors, and how it
+    /// completed — once the stream has ended. `paths` is empty.
+    ///
+    /// `None` while matches may still arrive, after the results have been
+    /// taken, or if the source or matcher panicked.
+    pub fn results(&mut self) -> Option<Results> {
+        let mut state = self.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !state.done || !state.queue.is_empty() {
+            return None;
+        }
+        state.results.take()
+    }
+
+    /// Stop the search early, keeping any matches already queued.
+    ///
+    /// The stream drains what was found so far and then ends.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+        self.shared.space.notify_all();
+    }
+}
+
+impl Drop for MatchStream {
+    fn drop(&mut self) {
+        // Not joined — the worker may be blocked inside the source, and
+        // blocking an async executor on drop is worse than a brief overlap.
+        // A search already over leaves the token alone, since the caller may
+        // share it with other searches.
+        let done = self
+            .shared
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .done;
+        if self.worker.is_some() && !done {
+            self.cancel();
+        }
+    }
+}
+
+impl Stream for MatchStream {
+    type Item = Entry;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Entry>> {
+        let this = self.get_mut();
+        let mut state = this.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(entry) = state.queue.pop_front() {
+            this.shared.space.notify_one();
+            return Poll::Ready(Some(entry));
+        }
+
+        if state.done {
+            drop(state);
+            if let Some(worker) = this.worker.take() {
+                let _ = worker.join();
+            }
+            return Poll::Ready(None);
+        }
+
+        match &state.waker {
+            Some(w) if w.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
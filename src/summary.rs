@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::entry::{Entry, EntryKind};
+
+// ---------------------------------------------------------------------------
+// DirSummary
+// ---------------------------------------------------------------------------
+
+/// What a search found below one directory, for matchers that select
+/// directories by their contents — "more than 10 000 files", "nothing
+/// touched in a year".
+///
+/// Read with [`MatchContext::dir_summary`](crate::MatchContext::dir_summary)
+/// in searches built with
+/// [`dir_summaries`](crate::SearchBuilder::dir_summaries). Only entries the
+/// search admitted are counted: excluded paths and entries beyond
+/// `max_depth` are not.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirSummary {
+    /// Entries directly inside the directory.
+    pub children: usize,
+
+    /// Regular files anywhere below it.
+    pub files: usize,
+
+    /// Bytes in those files, counting those whose [`Entry::metadata`] was
+    /// known.
+    pub bytes: u64,
+
+    /// The newest modification time of anything below it, among entries
+    /// whose metadata was known.
+    pub newest: Option<SystemTime>,
+}
+
+// ---------------------------------------------------------------------------
+// DirSummaries
+// ---------------------------------------------------------------------------
+
+/// Running summaries of every directory seen, and the directory entries
+/// held back until their summaries are complete.
+#[derive(Default)]
+pub(crate) struct DirSummaries {
+    dirs: HashMap<PathBuf, DirSummary>,
+    held: Vec<Entry>,
+}
+
+impl DirSummaries {
+    /// Count `entry` in the summary of every directory above it, up to the
+    /// root of the walk.
+    pub(crate) fn add(&mut self, entry: &Entry) {
+        let file = entry.kind == EntryKind::File;
+        let meta = entry.metadata.as_ref();
+        let bytes = meta.filter(|_| file).map_or(0, |m| m.len());
+        let modified = meta.and_then(|m| m.modified().ok());
+
+        for (i, dir) in entry.path.ancestors().skip(1).take(entry.depth).enumerate() {
+            let summary = match self.dirs.get_mut(dir) {
+                Some(summary) => summary,
+                None => self.dirs.entry(dir.to_path_buf()).or_default(),
+            };
+            if i == 0 {
+                summary.children += 1;
+            }
+            if file {
+                summary.files += 1;
+                summary.bytes += bytes;
+            }
+            summary.newest = summary.newest.max(modified);
+        }
+    }
+
+    /// Keep `dir` back until the walk is over.
+    pub(crate) fn hold(&mut self, dir: Entry) {
+        self.held.push(dir);
+    }
+
+    /// The held directories, in the order they arrived, with their
+    /// summaries.
+    pub(crate) fn finish(self) -> impl Iterator<Item = (Entry, DirSummary)> {
+        let dirs = self.dirs;
+        self.held.into_iter().map(move |entry| {
+            let summary = dirs.get(&entry.path).copied().unwrap_or_default();
+            (entry, summary)
+        })
+    }
+}
@@ -1,5 +1,9 @@
+use std::path::Path;
+
+use crate::context::MatchContext;
 use crate::entry::Entry;
 use crate::error::ParexError;
+use crate::plan::Cost;
 
 /// A source of entries to search through.
 ///
@@ -34,12 +38,7 @@ use crate::error::ParexError;
 ///
 /// impl Source for VecSource {
 ///     fn walk(&self, _config: &WalkConfig) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>> {
-///         let entries = self.0.iter().map(|name| Ok(Entry {
-///             path:     name.into(),
-///             kind:     EntryKind::File,
-///             depth:    0,
-///             metadata: None,
-///         })).collect::<Vec<_>>();
+///         let entries = self.0.iter().map(|name| Ok(Entry::new(name, EntryKind::File, 0))).collect::<Vec<_>>();
 ///         Box::new(entries.into_iter())
 ///     }
 /// }
@@ -56,6 +55,17 @@ pub trait Source: Send + Sync {
         &self,
         config: &crate::engine::WalkConfig,
     ) -> Box<dyn Iterator<Item = Result<Entry, ParexError>>>;
+
+    /// The directory the walk starts from — depth 0 — for sources backed
+    /// by a directory tree.
+    ///
+    /// Reported in [`Plan::root`](crate::Plan::root) without walking
+    /// anything. Defaults to `None`, which suits sources with no single
+    /// starting path, such as a database or an in-memory list; the engine
+    /// never needs it, and works from each entry's path and depth alone.
+    fn root(&self) -> Option<&std::path::Path> {
+        None
+    }
 }
 
 /// Determines whether an entry is a match.
@@ -87,4 +97,143 @@ pub trait Source: Send + Sync {
 pub trait Matcher: Send + Sync {
     /// Returns `true` if this entry should be included in results.
     fn is_match(&self, entry: &Entry) -> bool;
+
+    /// Like [`is_match`](Self::is_match), with access to derivations cached
+    /// across every matcher run on this entry.
+    ///
+    /// The default delegates to [`try_match`](Self::try_match), and so to
+    /// `is_match`. Override it when the matcher needs something another
+    /// matcher may already have computed, such as the lowercased name.
+    fn is_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> bool {
+        self.try_match(entry).unwrap_or_else(|err| {
+            ctx.fail(err);
+            false
+        })
+    }
+
+    /// Like [`is_match`](Self::is_match), for matchers that can fail —
+    /// content matchers hitting IO errors, remote classifiers timing out.
+    ///
+    /// The default delegates to `is_match` and never fails. An error is
+    /// collected into [`Results::errors`](crate::Results::errors) when
+    /// `.collect_errors(true)` is set, like a source error, and the entry
+    /// counts as not matching; the walk continues. Only a fatal error such
+    /// as [`ParexError::InvalidPattern`] halts it, reported through
+    /// [`Results::completion`](crate::Results::completion).
+    ///
+    /// `is_match` should still give an answer, typically treating an error
+    /// as no match, for callers outside the engine.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use parex::{Cost, Entry, Matcher, ParexError};
+    ///
+    /// /// Files whose content mentions "invoice".
+    /// struct MentionsInvoice;
+    ///
+    /// impl Matcher for MentionsInvoice {
+    ///     fn is_match(&self, entry: &Entry) -> bool {
+    ///         self.try_match(entry).unwrap_or(false)
+    ///     }
+    ///
+    ///     fn try_match(&self, entry: &Entry) -> Result<bool, ParexError> {
+    ///         let text = std::fs::read_to_string(&entry.path).map_err(|source| ParexError::Io {
+    ///             path: entry.path.clone(),
+    ///             source,
+    ///         })?;
+    ///         Ok(text.contains("invoice"))
+    ///     }
+    ///
+    ///     fn cost(&self) -> Cost {
+    ///         Cost::Content
+    ///     }
+    /// }
+    /// ```
+    fn try_match(&self, entry: &Entry) -> Result<bool, ParexError> {
+        Ok(self.is_match(entry))
+    }
+
+    /// [`try_match`](Self::try_match) with the entry's [`MatchContext`]:
+    /// the method the engine calls.
+    ///
+    /// The default runs [`is_match_ctx`](Self::is_match_ctx) and returns the
+    /// error its `try_match` reported, so overriding either of those is
+    /// enough. Override this one instead for a matcher that can fail and
+    /// also wants the context's cached metadata or
+    /// [`warn()`](MatchContext::warn) — a content matcher that `stat()`s
+    /// before reading, for instance.
+    fn try_match_ctx(&self, entry: &Entry, ctx: &MatchContext<'_>) -> Result<bool, ParexError> {
+        let matched = self.is_match_ctx(entry, ctx);
+        ctx.take_error().map_or(Ok(matched), Err)
+    }
+
+    /// How much work [`is_match`](Self::is_match) does per entry.
+    ///
+    /// Reported by [`SearchBuilder::explain()`](crate::SearchBuilder::explain),
+    /// and used to order `.filter()` predicates. Defaults to [`Cost::Name`],
+    /// so a matcher that does not say otherwise is matched on the thread
+    /// consuming the walk and sees entries in discovery order. Declaring
+    /// [`Cost::Metadata`] or [`Cost::Content`] lets a search with
+    /// `.threads(n > 1)` match on a pool of workers — matches then arrive
+    /// in whatever order the workers finish — and turns on metadata and
+    /// content read-ahead.
+    fn cost(&self) -> Cost {
+        Cost::Name
+    }
+
+    /// Whether no entry at or below `dir` can match.
+    ///
+    /// Returning `true` lets sources skip the whole subtree through
+    /// [`WalkConfig::skip_dir`](crate::engine::WalkConfig::skip_dir). Only
+    /// return `true` when it is certain — a wrong answer hides matches.
+    fn prunes_dir(&self, dir: &Path) -> bool {
+        let _ = dir;
+        false
+    }
+}
+
+/// Receives every matched entry while the walk is still running.
+///
+/// Sinks let embedders do per-match work — hashing a file into a
+/// content-addressable store, forwarding to a queue, updating a UI — during
+/// traversal instead of after it, so that work overlaps with the source's IO
+/// rather than running as a second serialized phase.
+///
+/// # Errors
+///
+/// Returning a recoverable error records it in [`Results::errors`] (when
+/// `.collect_errors(true)` is set) and the walk continues. A fatal error halts
+/// the walk and is reported through [`Results::completion`].
+///
+/// # Example
+///
+/// ```rust
+/// use parex::{Entry, ParexError, Sink};
+/// use std::collections::HashMap;
+/// use std::path::PathBuf;
+/// use std::sync::Mutex;
+///
+/// /// A toy content-addressable store keyed by file length.
+/// #[derive(Default)]
+/// struct CasStore(Mutex<HashMap<u64, Vec<PathBuf>>>);
+///
+/// impl Sink for CasStore {
+///     fn accept(&self, entry: &Entry) -> Result<(), ParexError> {
+///         let key = entry.metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+///         self.0.lock().unwrap().entry(key).or_default().push(entry.path.clone());
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Sink: Send + Sync {
+    /// Handle one matched entry.
+    fn accept(&self, entry: &Entry) -> Result<(), ParexError>;
+}
+
+/// Lets the caller keep a handle to a sink it passes to the builder.
+impl<S: Sink + ?Sized> Sink for std::sync::Arc<S> {
+    fn accept(&self, entry: &Entry) -> Result<(), ParexError> {
+        (**self).accept(entry)
+    }
 }
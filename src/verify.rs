@@ -0,0 +1,265 @@
+//! Integrity checking of a source against a manifest of expected hashes.
+//!
+//! [`SearchBuilder::verify()`](crate::SearchBuilder::verify) walks the source
+//! as usual and hands every matched file to a pool of hashing threads, so
+//! hashing overlaps with traversal. parex does not pick a hash function —
+//! the caller supplies one, and hashes are compared as opaque strings.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::entry::{Entry, EntryKind};
+use crate::error::ParexError;
+use crate::results::Results;
+use crate::traits::Sink;
+
+// ---------------------------------------------------------------------------
+// Manifest
+// ---------------------------------------------------------------------------
+
+/// Expected content hashes, keyed by path.
+///
+/// Paths must be spelled exactly as the source yields them — the manifest
+/// does no normalization.
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    expected: HashMap<PathBuf, String>,
+}
+
+impl Manifest {
+    /// Create an empty manifest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect `path` to hash to `hash`.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, hash: impl Into<String>) {
+        self.expected.insert(path.into(), hash.into());
+    }
+
+    /// The expected hash for `path`, if listed.
+    pub fn get(&self, path: &Path) -> Option<&str> {
+        self.expected.get(path).map(String::as_str)
+    }
+
+    /// Number of listed paths.
+    pub fn len(&self) -> usize {
+        self.expected.len()
+    }
+
+    /// Whether the manifest lists no paths.
+    pub fn is_empty(&self) -> bool {
+        self.expected.is_empty()
+    }
+}
+
+impl<P: Into<PathBuf>, H: Into<String>> FromIterator<(P, H)> for Manifest {
+    fn from_iter<I: IntoIterator<Item = (P, H)>>(iter: I) -> Self {
+        Self {
+            expected: iter
+                .into_iter()
+                .map(|(p, h)| (p.into(), h.into()))
+                .collect(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Verification
+// ---------------------------------------------------------------------------
+
+/// The outcome of [`SearchBuilder::verify()`](crate::SearchBuilder::verify).
+///
+/// Every list is sorted by path.
+pub struct Verification {
+    /// The underlying search results.
+    pub results: Results,
+
+    /// Number of files whose hash matched the manifest.
+    pub verified: usize,
+
+    /// Listed in the manifest but never seen during the walk.
+    pub missing: Vec<PathBuf>,
+
+    /// Seen during the walk but not listed in the manifest.
+    pub extra: Vec<PathBuf>,
+
+    /// Listed and seen, but hashing to a different value.
+    pub mismatched: Vec<Mismatch>,
+
+    /// Files that could not be hashed. They count as neither verified nor mismatched.
+    pub errors: Vec<ParexError>,
+}
+
+impl Verification {
+    /// Whether every listed file was present and matched, with nothing extra.
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.mismatched.is_empty()
+            && self.errors.is_empty()
+    }
+}
+
+/// A file whose hash differs from the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The file.
+    pub path: PathBuf,
+
+    /// The hash listed in the manifest.
+    pub expected: String,
+
+    /// The hash computed during the walk.
+    pub actual: String,
+}
+
+// ---------------------------------------------------------------------------
+// Hashing pool
+// ---------------------------------------------------------------------------
+
+pub(crate) type Hasher = dyn Fn(&Entry) -> Result<String, ParexError> + Send + Sync;
+
+#[derive(Default)]
+struct Tally {
+    seen: HashSet<PathBuf>,
+    verified: usize,
+    extra: Vec<PathBuf>,
+    mismatched: Vec<Mismatch>,
+    errors: Vec<ParexError>,
+}
+
+/// Forwards matched files to the hashing pool, after any caller sink.
+pub(crate) struct VerifySink {
+    inner: Option<Box<dyn Sink>>,
+    tx: Mutex<Sender<Entry>>,
+}
+
+impl Sink for VerifySink {
+    fn accept(&self, entry: &Entry) -> Result<(), ParexError> {
+        if let Some(inner) = &self.inner {
+            inner.accept(entry)?;
+        }
+        if entry.kind == EntryKind::File {
+            let tx = self.tx.lock().unwrap_or_else(|e| e.into_inner());
+            // The pool only hangs up once this sink is dropped
+            let _ = tx.send(entry.clone());
+        }
+        Ok(())
+    }
+}
+
+/// A running pool of hashing threads.
+pub(crate) struct Pool {
+    manifest: Arc<Manifest>,
+    tally: Arc<Mutex<Tally>>,
+    workers: Vec<std::thread::JoinHandle<()>>,
+}
+
+impl Pool {
+    /// Spawn `threads` hashing workers and the sink that feeds them.
+    pub(crate) fn spawn(
+        manifest: Manifest,
+        hasher: Arc<Hasher>,
+        threads: usize,
+        inner: Option<Box<dyn Sink>>,
+    ) -> Result<(Self, VerifySink), ParexError> {
+        let (tx, rx) = std::sync::mpsc::channel::<Entry>();
+        let rx = Arc::new(Mutex::new(rx));
+        let manifest = Arc::new(manifest);
+        let tally = Arc::new(Mutex::new(Tally::default()));
+
+        let workers = (0..threads.max(1))
+            .map(|i| {
+                let rx = Arc::clone(&rx);
+                let manifest = Arc::clone(&manifest);
+                let tally = Arc::clone(&tally);
+                let hasher = Arc::clone(&hasher);
+                std::thread::Builder::new()
+                    .name(format!("parex-verify-{i}"))
+                    .spawn(move || hash_worker(&rx, &manifest, &tally, &*hasher))
+                    .map_err(|e| ParexError::ThreadPool(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sink = VerifySink {
+            inner,
+            tx: Mutex::new(tx),
+        };
+        Ok((
+            Self {
+                manifest,
+                tally,
+                workers,
+            },
+            sink,
+        ))
+    }
+
+    /// Wait for the workers to drain and assemble the report.
+    ///
+    /// The feeding [`VerifySink`] must already be dropped, or this blocks forever.
+    pub(crate) fn finish(self, results: Results) -> Verification {
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+
+        let mut tally = std::mem::take(&mut *self.tally.lock().unwrap_or_else(|e| e.into_inner()));
+
+        let mut missing: Vec<PathBuf> = self
+            .manifest
+            .expected
+            .keys()
+            .filter(|p| !tally.seen.contains(*p))
+            .cloned()
+            .collect();
+        missing.sort();
+        tally.extra.sort();
+        tally.mismatched.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Verification {
+            results,
+            verified: tally.verified,
+            missing,
+            extra: tally.extra,
+            mismatched: tally.mismatched,
+            errors: tally.errors,
+        }
+    }
+}
+
+fn hash_worker(
+    rx: &Mutex<Receiver<Entry>>,
+    manifest: &Manifest,
+    tally: &Mutex<Tally>,
+    hasher: &Hasher,
+) {
+    loop {
+        let entry = match rx.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+            Ok(entry) => entry,
+            Err(_) => return,
+        };
+
+        let Some(expected) = manifest.get(&entry.path) else {
+            let mut tally = tally.lock().unwrap_or_else(|e| e.into_inner());
+            tally.seen.insert(entry.path.clone());
+            tally.extra.push(entry.path);
+            continue;
+        };
+
+        let outcome = hasher(&entry);
+        let mut tally = tally.lock().unwrap_or_else(|e| e.into_inner());
+        tally.seen.insert(entry.path.clone());
+        match outcome {
+            Ok(actual) if actual == expected => tally.verified += 1,
+            Ok(actual) => tally.mismatched.push(Mismatch {
+                path: entry.path,
+                expected: expected.to_owned(),
+                actual,
+            }),
+            Err(err) => tally.errors.push(err),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// ---------------------------------------------------------------------------
+// Warning
+// ---------------------------------------------------------------------------
+
+/// Something worth reporting that did not stop an entry from being handled.
+///
+/// Errors mean an entry could not be visited or matched; warnings mean it
+/// was, but with a caveat the user may want to know about — a file skipped
+/// as binary, a path shortened for display, a source slowing down to spare
+/// a server. Sources emit them through
+/// [`WalkConfig::warn`](crate::engine::WalkConfig::warn) and matchers
+/// through [`MatchContext::warn`](crate::MatchContext::warn).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// What kind of event this is.
+    pub kind: WarningKind,
+
+    /// The entry it concerns, if any.
+    pub path: Option<PathBuf>,
+
+    /// Human-readable detail.
+    pub message: String,
+}
+
+impl Warning {
+    /// A warning of `kind` about `path`.
+    pub fn new(kind: WarningKind, path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            path: Some(path.into()),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)?;
+        if let Some(path) = &self.path {
+            write!(f, " ({})", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The category of a [`Warning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A content matcher skipped a file that looks binary.
+    SkippedBinary,
+
+    /// A content matcher skipped a file above its size limit.
+    SkippedTooLarge,
+
+    /// A path was shortened or lossily converted.
+    TruncatedPath,
+
+    /// The source deliberately slowed down, e.g. to respect a rate limit.
+    Throttled,
+
+    /// Anything else.
+    Other,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SkippedBinary => "skipped binary file",
+            Self::SkippedTooLarge => "skipped large file",
+            Self::TruncatedPath => "truncated path",
+            Self::Throttled => "throttled",
+            Self::Other => "warning",
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Warnings
+// ---------------------------------------------------------------------------
+
+pub(crate) type Observer = Box<dyn Fn(&Warning) + Send + Sync>;
+
+/// Where warnings go during a search: an optional collection for
+/// [`Results::warnings`](crate::Results::warnings) and an optional observer.
+/// With neither, emitting a warning is free.
+#[derive(Default)]
+pub(crate) struct Warnings {
+    collected: Option<Mutex<Vec<Warning>>>,
+    observer: Option<Observer>,
+}
+
+impl Warnings {
+    pub(crate) fn new(collect: bool, observer: Option<Observer>) -> Self {
+        Self {
+            collected: collect.then(|| Mutex::new(Vec::new())),
+            observer,
+        }
+    }
+
+    pub(crate) fn emit(&self, warning: Warning) {
+        if let Some(observer) = &self.observer {
+            observer(&warning);
+        }
+        if let Some(collected) = &self.collected {
+            collected
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(warning);
+        }
+    }
+
+    /// Whether anyone will see an emitted warning.
+    pub(crate) fn enabled(&self) -> bool {
+        self.collected.is_some() || self.observer.is_some()
+    }
+
+    /// Everything collected so far.
+    pub(crate) fn take(&self) -> Vec<Warning> {
+        self.collected
+            .as_ref()
+            .map(|c| std::mem::take(&mut *c.lock().unwrap_or_else(|e| e.into_inner())))
+            .unwrap_or_default()
+    }
+}